@@ -0,0 +1,189 @@
+//! Chapters of a track, either from the tags of a file (ID3 `CHAP` frames) or
+//! from a [Podcasting 2.0 chapters](https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md) JSON file.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// How far into a chapter "previous chapter" jumps to the start of the current chapter instead of the one before it
+const PREVIOUS_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// A single chapter of a track
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chapter {
+    /// Position in the track where the chapter starts
+    pub start: Duration,
+    pub title: String,
+}
+
+/// The top-level object of a chapters JSON file, only containing the fields that are used
+#[derive(Debug, Deserialize)]
+struct JsonChapters {
+    chapters: Vec<JsonChapter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonChapter {
+    /// Start of the chapter in seconds
+    start_time: f64,
+    title: Option<String>,
+    /// Chapters with `false` are not meant to be shown in a table of contents, like ones only changing the image
+    toc: Option<bool>,
+}
+
+/// Parse a Podcasting 2.0 chapters JSON file, the chapters are returned sorted by their start
+pub fn parse_json(json: &str) -> Result<Vec<Chapter>> {
+    let parsed: JsonChapters = serde_json::from_str(json).context("parse chapters json")?;
+
+    let mut chapters: Vec<Chapter> = parsed
+        .chapters
+        .into_iter()
+        .filter(|v| v.toc != Some(false) && v.start_time.is_finite() && v.start_time >= 0.0)
+        .enumerate()
+        .map(|(idx, v)| Chapter {
+            start: Duration::from_secs_f64(v.start_time),
+            title: v
+                .title
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| format!("Chapter {}", idx + 1)),
+        })
+        .collect();
+    chapters.sort_by_key(|v| v.start);
+
+    Ok(chapters)
+}
+
+/// Get the chapters from the `CHAP` frames of a ID3 tag, the chapters are returned sorted by their start
+pub fn from_id3(tag: &id3::Tag) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = tag
+        .chapters()
+        .map(|chapter| {
+            let title = chapter
+                .frames
+                .iter()
+                .find(|frame| frame.id() == "TIT2")
+                .and_then(|frame| frame.content().text())
+                .unwrap_or(chapter.element_id.as_str());
+
+            Chapter {
+                start: Duration::from_millis(u64::from(chapter.start_time)),
+                title: title.to_string(),
+            }
+        })
+        .collect();
+    chapters.sort_by_key(|v| v.start);
+
+    chapters
+}
+
+/// Get the index of the chapter playing at `position`
+pub fn current_index(chapters: &[Chapter], position: Duration) -> Option<usize> {
+    chapters.iter().rposition(|v| v.start <= position)
+}
+
+/// Get where the chapter after the one playing at `position` starts
+pub fn next_start(chapters: &[Chapter], position: Duration) -> Option<Duration> {
+    chapters
+        .iter()
+        .find(|v| v.start > position)
+        .map(|v| v.start)
+}
+
+/// Get where to go for "previous chapter" at `position`
+///
+/// Like for tracks, this is the start of the current chapter unless it just started.
+pub fn previous_start(chapters: &[Chapter], position: Duration) -> Option<Duration> {
+    let current = current_index(chapters, position)?;
+    let start = chapters[current].start;
+
+    if position.saturating_sub(start) > PREVIOUS_THRESHOLD || current == 0 {
+        return Some(start);
+    }
+
+    Some(chapters[current - 1].start)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn chapters() -> Vec<Chapter> {
+        [(0, "Intro"), (60, "Topic"), (300, "Outro")]
+            .into_iter()
+            .map(|(start, title)| Chapter {
+                start: Duration::from_secs(start),
+                title: title.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_json() {
+        let json = r#"{
+            "version": "1.2.0",
+            "chapters": [
+                {"startTime": 300, "title": "Outro"},
+                {"startTime": 0, "title": "Intro"},
+                {"startTime": 30.5, "img": "https://example.com/image.jpg", "toc": false},
+                {"startTime": 60, "title": "Topic", "url": "https://example.com"}
+            ]
+        }"#;
+
+        assert_eq!(parse_json(json).unwrap(), chapters());
+        assert!(parse_json("{}").is_err());
+    }
+
+    #[test]
+    fn should_name_untitled_chapters() {
+        let json = r#"{"version": "1.2.0", "chapters": [{"startTime": 0}, {"startTime": 10, "title": ""}]}"#;
+
+        let titles: Vec<String> = parse_json(json)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.title)
+            .collect();
+        assert_eq!(titles, ["Chapter 1", "Chapter 2"]);
+    }
+
+    #[test]
+    fn should_find_current() {
+        let chapters = chapters();
+
+        assert_eq!(current_index(&chapters, Duration::ZERO), Some(0));
+        assert_eq!(current_index(&chapters, Duration::from_secs(60)), Some(1));
+        assert_eq!(current_index(&chapters, Duration::from_secs(1000)), Some(2));
+        assert_eq!(current_index(&[], Duration::from_secs(10)), None);
+    }
+
+    #[test]
+    fn should_find_next_and_previous() {
+        let chapters = chapters();
+
+        assert_eq!(
+            next_start(&chapters, Duration::from_secs(10)),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(next_start(&chapters, Duration::from_secs(300)), None);
+
+        // far enough into a chapter, go to its start
+        assert_eq!(
+            previous_start(&chapters, Duration::from_secs(100)),
+            Some(Duration::from_secs(60))
+        );
+        // just started, go to the one before
+        assert_eq!(
+            previous_start(&chapters, Duration::from_secs(61)),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            previous_start(&chapters, Duration::from_secs(1)),
+            Some(Duration::ZERO)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     net::{IpAddr, SocketAddr},
     num::{NonZeroU32, NonZeroU8},
     path::PathBuf,
@@ -235,6 +236,8 @@ pub struct PlayerSettings {
     ///
     /// This for example affects how deep the auto-tag extraction will go
     pub library_scan_depth: ScanDepth,
+    /// How the server keeps the library database in sync with the music directories
+    pub library_watch: LibraryWatchSettings,
     /// Set if the position should be remembered for tracks
     pub remember_position: RememberLastPosition,
 
@@ -249,8 +252,21 @@ pub struct PlayerSettings {
     pub speed: i32,
     /// Enable gapless decoding & prefetching the next track
     pub gapless: bool,
+    /// Crossfade between tracks for this many milliseconds, `0` to disable
+    ///
+    /// Only supported by the rusty backend, consecutive tracks of the same album are never crossfaded
+    pub crossfade_ms: u32,
     /// How much to seek on a seek event
     pub seek_step: SeekStep,
+    /// Name of the audio output device to use, uses the system default if unset
+    ///
+    /// Only supported by the rusty backend, see `termusic-server list-output-devices` for available names
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_device: Option<String>,
+    /// Volume normalization via `ReplayGain` / `R128` tags
+    pub replaygain: ReplayGainSettings,
+    /// Parametric equalizer, only supported by the rusty backend
+    pub equalizer: EqualizerSettings,
 
     /// Controls if support via Media-Controls (like mpris on linux) is enabled
     pub use_mediacontrols: bool,
@@ -275,6 +291,7 @@ impl Default for PlayerSettings {
         Self {
             music_dirs: default_music_dirs(),
             library_scan_depth: ScanDepth::Limited(10),
+            library_watch: LibraryWatchSettings::default(),
             remember_position: RememberLastPosition::default(),
 
             loop_mode: LoopMode::default(),
@@ -282,7 +299,11 @@ impl Default for PlayerSettings {
             volume: 30,
             speed: 10,
             gapless: true,
+            crossfade_ms: 0,
             seek_step: SeekStep::default(),
+            output_device: None,
+            replaygain: ReplayGainSettings::default(),
+            equalizer: EqualizerSettings::default(),
 
             use_mediacontrols: true,
             set_discord_status: true,
@@ -324,8 +345,201 @@ impl LoopMode {
     }
 }
 
-/// Settings for the gRPC server (and potentially future ways to communicate)
+/// Which `ReplayGain` value to apply
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayGainMode {
+    /// Do not apply any gain
+    #[default]
+    Off,
+    /// Use the track gain, so that every track has the same loudness
+    Track,
+    /// Use the album gain, keeps the loudness differences between the tracks of a album
+    Album,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct LibraryWatchSettings {
+    /// Watch the music directories for changes and apply them to the library database as they happen
+    pub watch: bool,
+    /// Interval in seconds for a full rescan of the music directories, `0` to disable
+    ///
+    /// This is a fallback for changes the watcher cannot see, like on some network mounts
+    pub full_scan_interval_secs: u64,
+}
+
+impl LibraryWatchSettings {
+    /// Get whether the server keeps the library database in sync at all
+    pub fn is_enabled(&self) -> bool {
+        self.watch || self.full_scan_interval_secs > 0
+    }
+}
+
+impl Default for LibraryWatchSettings {
+    fn default() -> Self {
+        Self {
+            watch: true,
+            full_scan_interval_secs: 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ReplayGainSettings {
+    /// Which gain to apply
+    ///
+    /// If the selected gain is not tagged, the other one is used
+    pub mode: ReplayGainMode,
+    /// Extra gain in dB applied on top of the tagged gain, also applies to untagged tracks
+    pub preamp: f32,
+    /// Lower the gain if it would cause the tagged peak to clip
+    pub prevent_clipping: bool,
+}
+
+impl Default for ReplayGainSettings {
+    fn default() -> Self {
+        Self {
+            mode: ReplayGainMode::default(),
+            preamp: 0.0,
+            prevent_clipping: true,
+        }
+    }
+}
+
+/// The kind of filter a [`EqualizerBand`] uses
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EqualizerFilter {
+    /// Boost or cut around `frequency`, `q` controls the width
+    #[default]
+    Peaking,
+    /// Boost or cut everything below `frequency`
+    LowShelf,
+    /// Boost or cut everything above `frequency`
+    HighShelf,
+}
+
+/// A single band of the equalizer
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub struct EqualizerBand {
+    #[serde(default)]
+    pub kind: EqualizerFilter,
+    /// Center (or corner for shelves) frequency in Hz
+    pub frequency: f32,
+    /// Gain in dB
+    pub gain: f32,
+    /// Quality factor, higher values affect a narrower range around `frequency`
+    pub q: f32,
+}
+
+impl EqualizerBand {
+    /// The lowest and highest gain a band may have
+    pub const GAIN_RANGE: std::ops::RangeInclusive<f32> = -24.0..=24.0;
+
+    /// Check that the values of this band are usable for a filter
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.frequency.is_finite() && self.frequency > 0.0) {
+            anyhow::bail!("Frequency must be positive, found {}", self.frequency);
+        }
+        if !(self.q.is_finite() && self.q > 0.0) {
+            anyhow::bail!("Q must be positive, found {}", self.q);
+        }
+        if !Self::GAIN_RANGE.contains(&self.gain) {
+            anyhow::bail!(
+                "Gain must be within {} and {} dB, found {}",
+                Self::GAIN_RANGE.start(),
+                Self::GAIN_RANGE.end(),
+                self.gain
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The frequencies of the default presets, one band per octave
+const DEFAULT_EQ_FREQUENCIES: [f32; 10] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+/// The Q for a peaking filter with a width of one octave
+const DEFAULT_EQ_Q: f32 = 1.41;
+
+/// Create a preset from the gains for [`DEFAULT_EQ_FREQUENCIES`]
+fn default_eq_preset(gains: [f32; 10]) -> Vec<EqualizerBand> {
+    DEFAULT_EQ_FREQUENCIES
+        .into_iter()
+        .zip(gains)
+        .map(|(frequency, gain)| EqualizerBand {
+            kind: EqualizerFilter::Peaking,
+            frequency,
+            gain,
+            q: DEFAULT_EQ_Q,
+        })
+        .collect()
+}
+
+/// Settings for the equalizer
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct EqualizerSettings {
+    /// Enable the equalizer
+    pub enabled: bool,
+    /// Name of the preset in `presets` to use
+    pub preset: String,
+    /// Available presets by name
+    pub presets: BTreeMap<String, Vec<EqualizerBand>>,
+}
+
+impl Default for EqualizerSettings {
+    fn default() -> Self {
+        let presets = BTreeMap::from([
+            ("flat".to_string(), default_eq_preset([0.0; 10])),
+            (
+                "bass".to_string(),
+                default_eq_preset([6.0, 5.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+            ),
+            (
+                "treble".to_string(),
+                default_eq_preset([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 4.0, 5.0, 6.0]),
+            ),
+            (
+                "vocal".to_string(),
+                default_eq_preset([-2.0, -2.0, -1.0, 0.0, 2.0, 4.0, 4.0, 2.0, 0.0, -1.0]),
+            ),
+            (
+                "loudness".to_string(),
+                default_eq_preset([5.0, 4.0, 2.0, 0.0, -1.0, -1.0, 0.0, 2.0, 4.0, 5.0]),
+            ),
+        ]);
+
+        Self {
+            enabled: false,
+            preset: "flat".to_string(),
+            presets,
+        }
+    }
+}
+
+impl EqualizerSettings {
+    /// Get the bands of the selected preset
+    pub fn preset_bands(&self) -> Option<&[EqualizerBand]> {
+        self.presets.get(&self.preset).map(Vec::as_slice)
+    }
+
+    /// Get the bands that should be applied, empty if disabled or the preset does not exist
+    pub fn active_bands(&self) -> &[EqualizerBand] {
+        if !self.enabled {
+            return &[];
+        }
+
+        self.preset_bands().unwrap_or_default()
+    }
+}
+
+/// Settings for the gRPC server (and potentially future ways to communicate)
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 // for now, require that both port and ip are specified at once
 // #[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
 pub struct ComSettings {
@@ -333,6 +547,44 @@ pub struct ComSettings {
     pub port: u16,
     /// gRPC server interface / address
     pub address: IpAddr,
+    /// Transport to use between server and clients
+    ///
+    /// Configs without it use [`ComProtocol::Tcp`], so that existing setups are not changed,
+    /// new configs get [`ComProtocol::default_new`]
+    #[serde(default)]
+    pub protocol: ComProtocol,
+    /// Path of the unix socket, only used with protocol [`ComProtocol::Uds`]
+    ///
+    /// If unset, [`ComSettings::socket_path`] will resolve a per-user default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<PathBuf>,
+    /// Shared secret clients need to send with every request, no authentication if unset
+    ///
+    /// Recommended when `address` is not a loopback address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// The filename of the default unix socket
+const SOCKET_FILE_NAME: &str = "server.sock";
+
+impl ComSettings {
+    /// Get the configured unix socket path, or the default one
+    ///
+    /// The default is `$XDG_RUNTIME_DIR/termusic/server.sock`, or if that is not available, in the app config dir.
+    /// Both are per-user, so multiple users can run their own server without clashing.
+    pub fn socket_path(&self) -> anyhow::Result<PathBuf> {
+        if let Some(ref path) = self.socket_path {
+            return Ok(path.clone());
+        }
+
+        let dir = match dirs::runtime_dir() {
+            Some(dir) => dir.join("termusic"),
+            None => crate::utils::get_app_config_path()?,
+        };
+
+        Ok(dir.join(SOCKET_FILE_NAME))
+    }
 }
 
 impl Default for ComSettings {
@@ -340,23 +592,52 @@ impl Default for ComSettings {
         Self {
             port: 50101,
             address: "::".parse().unwrap(),
+            protocol: ComProtocol::default_new(),
+            socket_path: None,
+            token: None,
         }
     }
 }
 
-impl From<ComSettings> for SocketAddr {
-    fn from(value: ComSettings) -> Self {
+impl From<&ComSettings> for SocketAddr {
+    fn from(value: &ComSettings) -> Self {
         Self::new(value.address, value.port)
     }
 }
 
+/// The transport used for the gRPC communication
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ComProtocol {
+    /// Use TCP with `address` and `port`, allows connecting from other hosts
+    ///
+    /// This is the default for configs that do not specify a protocol
+    #[default]
+    Tcp,
+    /// Use a unix domain socket at `socket_path`, only accessible by the current user
+    ///
+    /// Only available on unix platforms
+    Uds,
+}
+
+impl ComProtocol {
+    /// The protocol for newly generated configs: [`ComProtocol::Uds`] where available, otherwise [`ComProtocol::Tcp`]
+    pub const fn default_new() -> Self {
+        if cfg!(unix) {
+            Self::Uds
+        } else {
+            Self::Tcp
+        }
+    }
+}
+
 mod v1_interop {
     use std::{error::Error, fmt::Display, num::TryFromIntError};
 
     use super::{
-        ComSettings, LoopMode, NonZeroU32, NonZeroU8, PlayerSettings, PodcastSettings,
-        PositionYesNo, PositionYesNoLower, RememberLastPosition, ScanDepth, SeekStep,
-        ServerSettings,
+        ComProtocol, ComSettings, EqualizerSettings, LibraryWatchSettings, LoopMode, NonZeroU32,
+        NonZeroU8, PlayerSettings, PodcastSettings, PositionYesNo, PositionYesNoLower,
+        RememberLastPosition, ReplayGainSettings, ScanDepth, SeekStep, ServerSettings,
     };
     use crate::config::v1;
 
@@ -430,6 +711,9 @@ mod v1_interop {
             let com_settings = ComSettings {
                 port: value.player_port,
                 address: value.player_interface,
+                // keep using tcp like in v1, to not break remote control
+                protocol: ComProtocol::Tcp,
+                ..Default::default()
             };
 
             let podcast_settings = PodcastSettings {
@@ -454,12 +738,18 @@ mod v1_interop {
                 // not converting old scan_depth as that is not stored in the config, but set via CLI, using default instead
                 // library_scan_depth: ScanDepth::Limited(value.max_depth_cli),
                 library_scan_depth: ScanDepth::Limited(10),
+                // not available in v1
+                library_watch: LibraryWatchSettings::default(),
                 remember_position: value.player_remember_last_played_position.into(),
                 loop_mode: value.player_loop_mode.into(),
                 volume: value.player_volume,
                 speed: value.player_speed,
                 gapless: value.player_gapless,
+                crossfade_ms: 0,
                 seek_step: value.player_seek_step.into(),
+                output_device: None,
+                replaygain: ReplayGainSettings::default(),
+                equalizer: EqualizerSettings::default(),
 
                 use_mediacontrols: value.player_use_mpris,
                 set_discord_status: value.player_use_discord,
@@ -525,7 +815,10 @@ mod v1_interop {
                 converted.com,
                 ComSettings {
                     port: 50101,
-                    address: "::".parse().unwrap()
+                    address: "::".parse().unwrap(),
+                    protocol: ComProtocol::Tcp,
+                    socket_path: None,
+                    token: None,
                 }
             );
 
@@ -543,6 +836,10 @@ mod v1_interop {
                 PlayerSettings {
                     music_dirs: Vec::new(),
                     library_scan_depth: ScanDepth::Limited(10),
+                    library_watch: LibraryWatchSettings {
+                        watch: true,
+                        full_scan_interval_secs: 60 * 60,
+                    },
                     remember_position: RememberLastPosition::Depends {
                         music: PositionYesNo::Simple(PositionYesNoLower::No),
                         podcast: PositionYesNo::Simple(PositionYesNoLower::Yes),
@@ -551,10 +848,14 @@ mod v1_interop {
                     volume: 70,
                     speed: 10,
                     gapless: true,
+                    crossfade_ms: 0,
                     seek_step: SeekStep::Depends {
                         short_tracks: NonZeroU32::new(5).unwrap(),
                         long_tracks: NonZeroU32::new(30).unwrap(),
                     },
+                    output_device: None,
+                    replaygain: ReplayGainSettings::default(),
+                    equalizer: EqualizerSettings::default(),
                     use_mediacontrols: true,
                     set_discord_status: true,
                     random_track_quantity: NonZeroU32::new(20).unwrap(),
@@ -564,3 +865,18 @@ mod v1_interop {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ComProtocol, ComSettings};
+
+    #[test]
+    fn should_keep_tcp_for_existing_com_settings() {
+        let existing: ComSettings = toml::from_str("port = 50101\naddress = \"::\"").unwrap();
+        assert_eq!(existing.protocol, ComProtocol::Tcp);
+
+        assert_eq!(ComSettings::default().protocol, ComProtocol::default_new());
+    }
+}
@@ -0,0 +1,336 @@
+use std::error::Error;
+use std::fmt::{Display, Write as _};
+use std::num::ParseIntError;
+
+use serde::{Deserialize, Serialize};
+
+/// The data a playlist column can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Duration,
+    Artist,
+    Title,
+    Album,
+    Genre,
+    TrackNumber,
+    Year,
+    FileName,
+    PlayCount,
+}
+
+impl ColumnKind {
+    pub const ALL: [Self; 9] = [
+        Self::Duration,
+        Self::Artist,
+        Self::Title,
+        Self::Album,
+        Self::Genre,
+        Self::TrackNumber,
+        Self::Year,
+        Self::FileName,
+        Self::PlayCount,
+    ];
+
+    /// The name used in the format string
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Duration => "duration",
+            Self::Artist => "artist",
+            Self::Title => "title",
+            Self::Album => "album",
+            Self::Genre => "genre",
+            Self::TrackNumber => "track",
+            Self::Year => "year",
+            Self::FileName => "file",
+            Self::PlayCount => "plays",
+        }
+    }
+
+    /// The header shown in the playlist table
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Duration => "Duration",
+            Self::Artist => "Artist",
+            Self::Title => "Title",
+            Self::Album => "Album",
+            Self::Genre => "Genre",
+            Self::TrackNumber => "#",
+            Self::Year => "Year",
+            Self::FileName => "File",
+            Self::PlayCount => "Plays",
+        }
+    }
+
+    /// Width in percent used if none is given
+    pub fn default_width(self) -> u16 {
+        match self {
+            Self::Duration => 12,
+            Self::TrackNumber => 5,
+            Self::Year | Self::PlayCount => 8,
+            Self::Genre => 15,
+            Self::Artist | Self::Title | Self::Album | Self::FileName => 25,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.name() == name)
+    }
+}
+
+/// Horizontal alignment of the text in a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl ColumnAlign {
+    fn from_char(val: char) -> Option<Self> {
+        match val {
+            '<' => Some(Self::Left),
+            '^' => Some(Self::Center),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            Self::Left => '<',
+            Self::Center => '^',
+            Self::Right => '>',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaylistColumn {
+    pub kind: ColumnKind,
+    /// Width in percent of the table
+    pub width: u16,
+    pub align: ColumnAlign,
+}
+
+// TODO: consider upgrading this with "thiserror"
+/// Error for when [`PlaylistColumns`] parsing fails
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnsParseError {
+    /// No column was given
+    Empty,
+    /// Text outside of a `{}` column
+    UnexpectedText(String),
+    /// A `{` without a matching `}`
+    Unclosed(String),
+    UnknownColumn(String),
+    InvalidWidth(String, ParseIntError),
+    /// Width is not within `1..=100`
+    WidthOutOfRange(u16),
+}
+
+impl Display for ColumnsParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse playlist columns because of ")?;
+        match self {
+            Self::Empty => write!(f, "no columns given"),
+            Self::UnexpectedText(v) => {
+                write!(f, "unexpected text \"{v}\", expected \"{{column}}\"")
+            }
+            Self::Unclosed(v) => write!(f, "missing \"}}\" in \"{v}\""),
+            Self::UnknownColumn(v) => write!(
+                f,
+                "unknown column \"{v}\", expected one of {}",
+                ColumnKind::ALL.map(ColumnKind::name).join(", ")
+            ),
+            Self::InvalidWidth(v, err) => write!(f, "invalid width \"{v}\": {err}"),
+            Self::WidthOutOfRange(v) => write!(f, "width {v} is not between 1 and 100"),
+        }
+    }
+}
+
+impl Error for ColumnsParseError {}
+
+/// The columns shown in the playlist, in order.
+///
+/// Stored as a format string like `{duration:12} {track:>5} {title}`,
+/// where each column is `{name:[align][width]}` with `<`, `^` or `>` as alignment and the width in percent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct PlaylistColumns(Vec<PlaylistColumn>);
+
+impl PlaylistColumns {
+    pub const DEFAULT: &'static str =
+        "{duration:12} {track:>5} {artist:18} {title:25} {album:32} {year:8}";
+
+    pub fn parse(input: &str) -> Result<Self, ColumnsParseError> {
+        let mut columns = Vec::new();
+        let mut rest = input.trim();
+
+        while !rest.is_empty() {
+            let Some(without_open) = rest.strip_prefix('{') else {
+                let text = rest.split_whitespace().next().unwrap_or(rest);
+                return Err(ColumnsParseError::UnexpectedText(text.to_string()));
+            };
+            let Some((inner, after)) = without_open.split_once('}') else {
+                return Err(ColumnsParseError::Unclosed(rest.to_string()));
+            };
+            columns.push(Self::parse_column(inner)?);
+            rest = after.trim_start();
+        }
+
+        if columns.is_empty() {
+            return Err(ColumnsParseError::Empty);
+        }
+
+        Ok(Self(columns))
+    }
+
+    /// Parse the inside of a `{}`
+    fn parse_column(inner: &str) -> Result<PlaylistColumn, ColumnsParseError> {
+        let (name, spec) = inner.split_once(':').unwrap_or((inner, ""));
+        let name = name.trim();
+        let kind = ColumnKind::from_name(name)
+            .ok_or_else(|| ColumnsParseError::UnknownColumn(name.to_string()))?;
+
+        let spec = spec.trim();
+        let mut chars = spec.chars();
+        let (align, width) = match chars.next().and_then(ColumnAlign::from_char) {
+            Some(align) => (align, chars.as_str()),
+            None => (ColumnAlign::default(), spec),
+        };
+
+        let width = if width.is_empty() {
+            kind.default_width()
+        } else {
+            let width = width
+                .parse::<u16>()
+                .map_err(|err| ColumnsParseError::InvalidWidth(width.to_string(), err))?;
+            if !(1..=100).contains(&width) {
+                return Err(ColumnsParseError::WidthOutOfRange(width));
+            }
+            width
+        };
+
+        Ok(PlaylistColumn { kind, width, align })
+    }
+
+    pub fn columns(&self) -> &[PlaylistColumn] {
+        &self.0
+    }
+
+    /// Check whether the given column is shown
+    pub fn contains(&self, kind: ColumnKind) -> bool {
+        self.0.iter().any(|v| v.kind == kind)
+    }
+}
+
+impl Default for PlaylistColumns {
+    fn default() -> Self {
+        Self::parse(Self::DEFAULT).expect("default columns to be valid")
+    }
+}
+
+impl Display for PlaylistColumns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, column) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_char(' ')?;
+            }
+            write!(f, "{{{}:", column.kind.name())?;
+            if column.align != ColumnAlign::Left {
+                f.write_char(column.align.as_char())?;
+            }
+            write!(f, "{}}}", column.width)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for PlaylistColumns {
+    type Error = ColumnsParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<PlaylistColumns> for String {
+    fn from(val: PlaylistColumns) -> Self {
+        val.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_default() {
+        let columns = PlaylistColumns::default();
+
+        assert_eq!(columns.columns().len(), 6);
+        assert_eq!(
+            columns.columns()[1],
+            PlaylistColumn {
+                kind: ColumnKind::TrackNumber,
+                width: 5,
+                align: ColumnAlign::Right
+            }
+        );
+        assert_eq!(columns.to_string(), PlaylistColumns::DEFAULT);
+    }
+
+    #[test]
+    fn should_parse_without_spec() {
+        let columns = PlaylistColumns::parse("{title}{plays:^} { file : 40 }").unwrap();
+
+        assert_eq!(
+            columns.columns(),
+            &[
+                PlaylistColumn {
+                    kind: ColumnKind::Title,
+                    width: 25,
+                    align: ColumnAlign::Left
+                },
+                PlaylistColumn {
+                    kind: ColumnKind::PlayCount,
+                    width: 8,
+                    align: ColumnAlign::Center
+                },
+                PlaylistColumn {
+                    kind: ColumnKind::FileName,
+                    width: 40,
+                    align: ColumnAlign::Left
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_parse_invalid() {
+        assert_eq!(PlaylistColumns::parse(" "), Err(ColumnsParseError::Empty));
+        assert_eq!(
+            PlaylistColumns::parse("{title} album"),
+            Err(ColumnsParseError::UnexpectedText("album".to_string()))
+        );
+        assert_eq!(
+            PlaylistColumns::parse("{title"),
+            Err(ColumnsParseError::Unclosed("{title".to_string()))
+        );
+        assert_eq!(
+            PlaylistColumns::parse("{composer}"),
+            Err(ColumnsParseError::UnknownColumn("composer".to_string()))
+        );
+        assert!(matches!(
+            PlaylistColumns::parse("{title:<wide}"),
+            Err(ColumnsParseError::InvalidWidth(..))
+        ));
+        assert_eq!(
+            PlaylistColumns::parse("{title:101}"),
+            Err(ColumnsParseError::WidthOutOfRange(101))
+        );
+    }
+}
@@ -76,6 +76,8 @@ pub struct Keys {
     pub database_keys: KeysDatabase,
     #[serde(rename = "podcast")]
     pub podcast_keys: KeysPodcast,
+    #[serde(rename = "saved_playlists")]
+    pub saved_playlist_keys: KeysSavedPlaylists,
     #[serde(rename = "adjust_cover_art")]
     pub move_cover_art_keys: KeysMoveCoverArt,
     #[serde(rename = "config")]
@@ -106,6 +108,7 @@ impl Default for Keys {
             playlist_keys: KeysPlaylist::default(),
             database_keys: KeysDatabase::default(),
             podcast_keys: KeysPodcast::default(),
+            saved_playlist_keys: KeysSavedPlaylists::default(),
             move_cover_art_keys: KeysMoveCoverArt::default(),
             config_keys: KeysConfigEditor::default(),
         }
@@ -217,6 +220,14 @@ impl CheckConflict for Keys {
             conflicts.extend(new);
         }
         key_path.pop();
+        key_path.push("saved_playlists");
+        if let Err(new) = self
+            .saved_playlist_keys
+            .check_conflict(key_path, global_keys)
+        {
+            conflicts.extend(new);
+        }
+        key_path.pop();
 
         // -------------
         if !conflicts.is_empty() {
@@ -242,6 +253,10 @@ pub struct KeysSelectView {
     pub open_config: KeyBinding,
     /// Key to open the Help-Popup
     pub open_help: KeyBinding,
+    /// Key to open the Equalizer-Popup
+    pub open_equalizer: KeyBinding,
+    /// Key to open the Saved-Playlists-Popup
+    pub open_saved_playlists: KeyBinding,
 }
 
 impl Default for KeysSelectView {
@@ -260,6 +275,16 @@ impl Default for KeysSelectView {
                 tuievents::KeyModifiers::CONTROL,
             )
             .into(),
+            open_equalizer: tuievents::KeyEvent::new(
+                tuievents::Key::Char('E'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            open_saved_playlists: tuievents::KeyEvent::new(
+                tuievents::Key::Char('P'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
         }
     }
 }
@@ -272,7 +297,9 @@ impl CheckConflict for KeysSelectView {
             (&self.view_podcasts, "view_podcasts"),
 
             (&self.open_config, "open_config"),
-            (&self.open_help, "open_help")
+            (&self.open_help, "open_help"),
+            (&self.open_equalizer, "open_equalizer"),
+            (&self.open_saved_playlists, "open_saved_playlists")
         }
     }
 
@@ -361,6 +388,14 @@ pub struct KeysPlayer {
     /// Will only apply in specific widgets (like the Playlist, but not in Config)
     // TODO: always enable "gapless" in rusty backend and rename option to "prefetch"
     pub toggle_prefetch: KeyBinding,
+    /// Key to seek to the start of the next chapter of the current track
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub next_chapter: KeyBinding,
+    /// Key to seek to the start of the current or previous chapter of the current track
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub previous_chapter: KeyBinding,
 
     /// Key to save the current playlist as a "m3u" playlist
     pub save_playlist: KeyBinding,
@@ -395,6 +430,8 @@ impl Default for KeysPlayer {
                 tuievents::KeyModifiers::CONTROL,
             )
             .into(),
+            next_chapter: tuievents::Key::Char(']').into(),
+            previous_chapter: tuievents::Key::Char('[').into(),
             save_playlist: tuievents::KeyEvent::new(
                 tuievents::Key::Char('s'),
                 tuievents::KeyModifiers::CONTROL,
@@ -417,6 +454,8 @@ impl CheckConflict for KeysPlayer {
             (&self.speed_up, "speed_up"),
             (&self.speed_down, "speed_down"),
             (&self.toggle_prefetch, "toggle_prefetch"),
+            (&self.next_chapter, "next_chapter"),
+            (&self.previous_chapter, "previous_chapter"),
 
             (&self.save_playlist, "save_playlist"),
         }
@@ -779,6 +818,16 @@ pub struct KeysPlaylist {
     /// previously known as `cmus_lqueue`
     // NOTE: currently this can be somewhat broken sometimes, cause unknown
     pub add_random_album: KeyBinding,
+
+    /// Key to move the currently selected node to be played directly after the current track
+    pub play_next: KeyBinding,
+    /// Key to add the currently selected node to the end of the "up next" tracks
+    pub add_up_next: KeyBinding,
+
+    /// Key to undo the last change to the playlist
+    pub undo: KeyBinding,
+    /// Key to redo the last undone change to the playlist
+    pub redo: KeyBinding,
 }
 
 impl Default for KeysPlaylist {
@@ -810,6 +859,14 @@ impl Default for KeysPlaylist {
             )
             .into(),
             add_random_album: tuievents::Key::Char('s').into(),
+            play_next: tuievents::Key::Char('p').into(),
+            add_up_next: tuievents::Key::Char('a').into(),
+            undo: tuievents::Key::Char('u').into(),
+            redo: tuievents::KeyEvent::new(
+                tuievents::Key::Char('r'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
         }
     }
 }
@@ -828,6 +885,12 @@ impl CheckConflict for KeysPlaylist {
 
             (&self.add_random_songs, "add_random_songs"),
             (&self.add_random_album, "add_random_album"),
+
+            (&self.play_next, "play_next"),
+            (&self.add_up_next, "add_up_next"),
+
+            (&self.undo, "undo"),
+            (&self.redo, "redo"),
         }
     }
 
@@ -885,8 +948,12 @@ pub struct KeysPodcast {
     pub refresh_all_feeds: KeyBinding,
     /// Key to download the currently selected episode
     pub download_episode: KeyBinding,
+    /// Key to cancel the running download of the currently selected episode
+    pub cancel_download: KeyBinding,
     /// Key to delete the downloaded local file of the currently selected episode
     pub delete_local_episode: KeyBinding,
+    /// Key to edit the playback speed and intro / outro skipping of the currently selected feed
+    pub playback_settings: KeyBinding,
     /// Key to delete the currently selected feed
     pub delete_feed: KeyBinding,
     /// Key to delete all the added feeds
@@ -910,11 +977,13 @@ impl Default for KeysPodcast {
             )
             .into(),
             download_episode: tuievents::Key::Char('d').into(),
+            cancel_download: tuievents::Key::Char('c').into(),
             delete_local_episode: tuievents::KeyEvent::new(
                 tuievents::Key::Char('D'),
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            playback_settings: tuievents::Key::Char('e').into(),
             delete_feed: tuievents::Key::Char('x').into(),
             delete_all_feeds: tuievents::KeyEvent::new(
                 tuievents::Key::Char('X'),
@@ -934,7 +1003,9 @@ impl CheckConflict for KeysPodcast {
             (&self.refresh_feed, "refresh_feed"),
             (&self.refresh_all_feeds, "refresh_all_feeds"),
             (&self.download_episode, "download_episode"),
+            (&self.cancel_download, "cancel_download"),
             (&self.delete_local_episode, "delete_local_episode"),
+            (&self.playback_settings, "playback_settings"),
             (&self.delete_feed, "delete_feed"),
             (&self.delete_all_feeds, "delete_all_feeds"),
         }
@@ -1172,6 +1243,8 @@ pub struct KeysDatabase {
     pub add_selected: KeyBinding,
     /// Add all tracks in the Database view "Tracks" section
     pub add_all: KeyBinding,
+    /// Cycle how the tracks in the Database view "Tracks" section are sorted
+    pub cycle_sort: KeyBinding,
 }
 
 impl Default for KeysDatabase {
@@ -1183,6 +1256,7 @@ impl Default for KeysDatabase {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            cycle_sort: tuievents::Key::Char('s').into(),
         }
     }
 }
@@ -1191,6 +1265,89 @@ impl CheckConflict for KeysDatabase {
     fn iter(&self) -> impl Iterator<Item = (&KeyBinding, &'static str)> {
         once_chain! {
             (&self.add_all, "add_all"),
+            (&self.cycle_sort, "cycle_sort"),
+        }
+    }
+
+    fn check_conflict(
+        &self,
+        key_path: &mut KeyPath,
+        global_keys: &mut KeyHashMapOwned,
+    ) -> Result<(), Vec<KeyConflictError>> {
+        let mut conflicts: Vec<KeyConflictError> = Vec::new();
+        let mut current_keys = KeyHashMap::new();
+
+        for (key, path) in self.iter() {
+            // check global first
+            if let Some(existing_path) = global_keys.get(key) {
+                conflicts.push(KeyConflictError {
+                    key_path_first: existing_path.to_string(),
+                    key_path_second: key_path.join_with_field(path),
+                    key: key.clone(),
+                });
+                continue;
+            }
+
+            if let Some(existing_path) = current_keys.get(key) {
+                conflicts.push(KeyConflictError {
+                    key_path_first: key_path.join_with_field(existing_path),
+                    key_path_second: key_path.join_with_field(path),
+                    key: key.clone(),
+                });
+                continue;
+            }
+
+            current_keys.insert(key, path);
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        Ok(())
+    }
+}
+
+/// Keys for the saved playlists popup
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct KeysSavedPlaylists {
+    /// Append the selected saved playlist to the queue
+    pub append: KeyBinding,
+    /// Save the current queue as a new saved playlist
+    pub save_queue: KeyBinding,
+    /// Add the current queue to the selected saved playlist
+    pub add_queue: KeyBinding,
+    /// Rename the selected saved playlist
+    pub rename: KeyBinding,
+    /// Delete the selected saved playlist
+    pub delete: KeyBinding,
+}
+
+impl Default for KeysSavedPlaylists {
+    fn default() -> Self {
+        Self {
+            append: tuievents::Key::Char('a').into(),
+            save_queue: tuievents::Key::Char('s').into(),
+            add_queue: tuievents::Key::Char('u').into(),
+            rename: tuievents::Key::Char('r').into(),
+            delete: tuievents::KeyEvent::new(
+                tuievents::Key::Char('D'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+        }
+    }
+}
+
+impl CheckConflict for KeysSavedPlaylists {
+    fn iter(&self) -> impl Iterator<Item = (&KeyBinding, &'static str)> {
+        once_chain! {
+            (&self.append, "append"),
+            (&self.save_queue, "save_queue"),
+            (&self.add_queue, "add_queue"),
+            (&self.rename, "rename"),
+            (&self.delete, "delete"),
         }
     }
 
@@ -1808,7 +1965,8 @@ impl From<SupportedModifiers> for tuievents::KeyModifiers {
 mod v1_interop {
     use super::{
         tuievents, KeyBinding, Keys, KeysConfigEditor, KeysDatabase, KeysLibrary, KeysLyric,
-        KeysMoveCoverArt, KeysNavigation, KeysPlayer, KeysPlaylist, KeysPodcast, KeysSelectView,
+        KeysMoveCoverArt, KeysNavigation, KeysPlayer, KeysPlaylist, KeysPodcast,
+        KeysSavedPlaylists, KeysSelectView,
     };
     use crate::config::v1;
 
@@ -1893,6 +2051,10 @@ mod v1_interop {
                     view_podcasts: value.global_layout_podcast.into(),
                     open_config: value.global_config_open.into(),
                     open_help: value.global_help.into(),
+                    // not available in v1
+                    open_equalizer: KeysSelectView::default().open_equalizer,
+                    // not available in v1
+                    open_saved_playlists: KeysSelectView::default().open_saved_playlists,
                 },
                 navigation_keys: KeysNavigation {
                     up: value.global_up.into(),
@@ -1913,6 +2075,10 @@ mod v1_interop {
                     speed_up: value.global_player_speed_up.into(),
                     speed_down: value.global_player_speed_down.into(),
                     toggle_prefetch: value.global_player_toggle_gapless.into(),
+                    // not available in v1
+                    next_chapter: KeysPlayer::default().next_chapter,
+                    // not available in v1
+                    previous_chapter: KeysPlayer::default().previous_chapter,
                     save_playlist: value.global_save_playlist.into(),
                 },
                 lyric_keys: KeysLyric {
@@ -1945,11 +2111,18 @@ mod v1_interop {
                     swap_down: value.playlist_swap_down.into(),
                     add_random_songs: value.playlist_add_random_tracks.into(),
                     add_random_album: value.playlist_add_random_album.into(),
+                    // not available in v1
+                    play_next: KeysPlaylist::default().play_next,
+                    add_up_next: KeysPlaylist::default().add_up_next,
+                    undo: KeysPlaylist::default().undo,
+                    redo: KeysPlaylist::default().redo,
                 },
                 database_keys: KeysDatabase {
                     // this is weird, but the previous implementation used "global_right" as the loading key to not conflict
                     add_selected: value.global_right.into(),
                     add_all: value.database_add_all.into(),
+                    // not available in v1
+                    cycle_sort: KeysDatabase::default().cycle_sort,
                 },
                 podcast_keys: KeysPodcast {
                     search: value.podcast_search_add_feed.into(),
@@ -1958,10 +2131,16 @@ mod v1_interop {
                     refresh_feed: value.podcast_refresh_feed.into(),
                     refresh_all_feeds: value.podcast_refresh_all_feeds.into(),
                     download_episode: value.podcast_episode_download.into(),
+                    // not available in v1
+                    cancel_download: KeysPodcast::default().cancel_download,
                     delete_local_episode: podcast_delete_episode_key,
+                    // not available in v1
+                    playback_settings: KeysPodcast::default().playback_settings,
                     delete_feed: podcast_delete_feed_key,
                     delete_all_feeds: podcast_delete_all_feeds_key,
                 },
+                // not available in v1
+                saved_playlist_keys: KeysSavedPlaylists::default(),
                 move_cover_art_keys: KeysMoveCoverArt {
                     move_left: value.global_xywh_move_left.into(),
                     move_right: value.global_xywh_move_right.into(),
@@ -2003,6 +2182,16 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                open_equalizer: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('E'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                open_saved_playlists: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('P'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
             };
             assert_eq!(converted.select_view_keys, expected_select_view_keys);
 
@@ -2056,6 +2245,8 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                next_chapter: tuievents::Key::Char(']').into(),
+                previous_chapter: tuievents::Key::Char('[').into(),
                 save_playlist: tuievents::KeyEvent::new(
                     tuievents::Key::Char('s'),
                     tuievents::KeyModifiers::CONTROL,
@@ -2133,6 +2324,14 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                play_next: tuievents::Key::Char('p').into(),
+                add_up_next: tuievents::Key::Char('a').into(),
+                undo: tuievents::Key::Char('u').into(),
+                redo: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('r'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
             };
             assert_eq!(converted.playlist_keys, expected_playlist_keys);
 
@@ -2143,6 +2342,7 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                cycle_sort: tuievents::Key::Char('s').into(),
             };
             assert_eq!(converted.database_keys, expected_database_keys);
 
@@ -2161,11 +2361,13 @@ mod v1_interop {
                 )
                 .into(),
                 download_episode: tuievents::Key::Char('d').into(),
+                cancel_download: tuievents::Key::Char('c').into(),
                 delete_local_episode: tuievents::KeyEvent::new(
                     tuievents::Key::Char('D'),
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                playback_settings: tuievents::Key::Char('e').into(),
                 delete_feed: tuievents::Key::Char('x').into(),
                 delete_all_feeds: tuievents::KeyEvent::new(
                     tuievents::Key::Char('X'),
@@ -2234,6 +2436,7 @@ mod v1_interop {
                 playlist_keys: expected_playlist_keys,
                 database_keys: expected_database_keys,
                 podcast_keys: expected_podcast_keys,
+                saved_playlist_keys: KeysSavedPlaylists::default(),
                 move_cover_art_keys: expected_move_cover_art_keys,
                 config_keys: expected_config_editor_keys,
             };
@@ -2291,6 +2494,8 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                next_chapter: tuievents::Key::Char(']').into(),
+                previous_chapter: tuievents::Key::Char('[').into(),
                 save_playlist: tuievents::KeyEvent::new(
                     tuievents::Key::Char('s'),
                     tuievents::KeyModifiers::CONTROL,
@@ -2666,5 +2871,22 @@ mod test {
                 keys.check_keys()
             );
         }
+
+        #[test]
+        fn should_err_on_saved_playlists_key_conflict() {
+            let mut keys = Keys::default();
+            keys.saved_playlist_keys.rename = keys.saved_playlist_keys.append.clone();
+
+            assert_eq!(
+                Err(KeysCheckError {
+                    errored_keys: vec![KeyConflictError {
+                        key_path_first: "keys.saved_playlists.append".into(),
+                        key_path_second: "keys.saved_playlists.rename".into(),
+                        key: tuievents::Key::Char('a').into()
+                    }]
+                }),
+                keys.check_keys()
+            );
+        }
     }
 }
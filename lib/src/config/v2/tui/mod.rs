@@ -4,7 +4,9 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use super::server::ComSettings;
+use crate::library_db::SmartPlaylist;
 
+pub mod columns;
 pub mod config_extra;
 pub mod keys;
 pub mod theme;
@@ -19,9 +21,12 @@ pub struct TuiSettings {
     pub com_resolved: Option<ComSettings>,
     pub behavior: BehaviorSettings,
     pub coverart: CoverArtPosition,
+    pub playlist: PlaylistSettings,
     #[serde(flatten)]
     pub theme: theme::ThemeWrap,
     pub keys: keys::Keys,
+    /// Rule based playlists shown in the database view
+    pub smart_playlists: Vec<SmartPlaylist>,
 }
 
 impl TuiSettings {
@@ -36,7 +41,7 @@ impl TuiSettings {
         match self.com {
             MaybeComSettings::ComSettings(ref v) => {
                 // this could likely be avoided, but for simplicity this is set
-                self.com_resolved = Some(*v);
+                self.com_resolved = Some(v.clone());
                 return Ok(());
             }
             MaybeComSettings::Same => (),
@@ -78,6 +83,13 @@ impl Default for BehaviorSettings {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct PlaylistSettings {
+    /// Columns shown in the playlist, see [`columns::PlaylistColumns`] for the format
+    pub columns: columns::PlaylistColumns,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum MaybeComSettings {
@@ -114,7 +126,10 @@ pub enum Alignment {
 }
 
 mod v1_interop {
-    use super::{Alignment, BehaviorSettings, CoverArtPosition, MaybeComSettings, TuiSettings};
+    use super::{
+        Alignment, BehaviorSettings, CoverArtPosition, MaybeComSettings, PlaylistSettings,
+        TuiSettings,
+    };
     use crate::config::v1;
 
     impl From<v1::Alignment> for Alignment {
@@ -152,8 +167,12 @@ mod v1_interop {
                     confirm_quit: value.enable_exit_confirmation,
                 },
                 coverart: value.album_photo_xywh.into(),
+                // not available in v1
+                playlist: PlaylistSettings::default(),
                 theme,
                 keys: value.keys.into(),
+                // not available in v1
+                smart_playlists: Vec::new(),
             }
         }
     }
@@ -184,6 +203,8 @@ mod v1_interop {
                 }
             );
 
+            assert_eq!(converted.playlist, PlaylistSettings::default());
+
             // the following below are already checked in their separate tests and do not need to be repeated
             // assert_eq!(converted.theme, ());
             // assert_eq!(converted.keys, ());
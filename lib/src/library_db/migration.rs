@@ -2,7 +2,7 @@ use anyhow::{bail, Context, Result};
 use rusqlite::Connection;
 
 /// The Current Database schema version this application is meant to run against
-pub(super) const DB_VERSION: u32 = 2;
+pub(super) const DB_VERSION: u32 = 7;
 /// The Lowest Database schema version this application supports migration up against
 ///
 /// Expection being "0" as that indicates a fresh database
@@ -68,6 +68,36 @@ fn apply_migrations(conn: &Connection, mut user_version: u32) -> Result<()> {
         user_version = set_user_version(conn, 2)?;
     }
 
+    if user_version == 2 {
+        conn.execute_batch(include_str!("./migrations/003.sql"))
+            .context("Database version 3 could not be applied")?;
+        user_version = set_user_version(conn, 3)?;
+    }
+
+    if user_version == 3 {
+        conn.execute_batch(include_str!("./migrations/004.sql"))
+            .context("Database version 4 could not be applied")?;
+        user_version = set_user_version(conn, 4)?;
+    }
+
+    if user_version == 4 {
+        conn.execute_batch(include_str!("./migrations/005.sql"))
+            .context("Database version 5 could not be applied")?;
+        user_version = set_user_version(conn, 5)?;
+    }
+
+    if user_version == 5 {
+        conn.execute_batch(include_str!("./migrations/006.sql"))
+            .context("Database version 6 could not be applied")?;
+        user_version = set_user_version(conn, 6)?;
+    }
+
+    if user_version == 6 {
+        conn.execute_batch(include_str!("./migrations/007.sql"))
+            .context("Database version 7 could not be applied")?;
+        user_version = set_user_version(conn, 7)?;
+    }
+
     Ok(())
 }
 
@@ -83,7 +113,7 @@ mod tests {
 
         assert_eq!(0, get_user_version(&conn).unwrap());
         migrate(&conn).unwrap();
-        assert_eq!(2, get_user_version(&conn).unwrap());
+        assert_eq!(7, get_user_version(&conn).unwrap());
 
         let all_tracks: Vec<String> = {
             let mut prep = conn.prepare("SELECT name FROM sqlite_schema WHERE type ='table' AND name NOT LIKE 'sqlite_%';").unwrap();
@@ -93,6 +123,182 @@ mod tests {
                 .collect()
         };
 
-        assert_eq!(&all_tracks, &["tracks"]);
+        assert_eq!(
+            &all_tracks,
+            &[
+                "tracks",
+                "play_history",
+                "tracks_fts",
+                "tracks_fts_data",
+                "tracks_fts_idx",
+                "tracks_fts_docsize",
+                "tracks_fts_config"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_migrate_from_v2() {
+        let conn = gen_database();
+
+        conn.execute_batch(include_str!("./migrations/002.sql"))
+            .unwrap();
+        set_user_version(&conn, 2).unwrap();
+
+        migrate(&conn).unwrap();
+        assert_eq!(7, get_user_version(&conn).unwrap());
+
+        let columns: Vec<String> = {
+            let mut prep = conn
+                .prepare(
+                    "SELECT name FROM pragma_table_info('tracks') WHERE name LIKE 'replaygain_%';",
+                )
+                .unwrap();
+            prep.query_map([], |r| r.get(0))
+                .unwrap()
+                .flatten()
+                .collect()
+        };
+
+        assert_eq!(
+            &columns,
+            &[
+                "replaygain_track_gain",
+                "replaygain_track_peak",
+                "replaygain_album_gain",
+                "replaygain_album_peak"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_migrate_from_v3() {
+        let conn = gen_database();
+
+        conn.execute_batch(include_str!("./migrations/002.sql"))
+            .unwrap();
+        conn.execute_batch(include_str!("./migrations/003.sql"))
+            .unwrap();
+        set_user_version(&conn, 3).unwrap();
+
+        migrate(&conn).unwrap();
+        assert_eq!(7, get_user_version(&conn).unwrap());
+
+        let columns: Vec<String> = {
+            let mut prep = conn
+                .prepare("SELECT name FROM pragma_table_info('play_history');")
+                .unwrap();
+            prep.query_map([], |r| r.get(0))
+                .unwrap()
+                .flatten()
+                .collect()
+        };
+
+        assert_eq!(
+            &columns,
+            &["id", "file", "played_at", "listened", "skipped"]
+        );
+    }
+
+    #[test]
+    fn should_migrate_from_v4() {
+        let conn = gen_database();
+
+        conn.execute_batch(include_str!("./migrations/002.sql"))
+            .unwrap();
+        conn.execute_batch(include_str!("./migrations/003.sql"))
+            .unwrap();
+        conn.execute_batch(include_str!("./migrations/004.sql"))
+            .unwrap();
+        set_user_version(&conn, 4).unwrap();
+        conn.execute(
+            "INSERT INTO tracks (file, last_modified) VALUES ('/a.mp3', '1000')",
+            [],
+        )
+        .unwrap();
+
+        migrate(&conn).unwrap();
+        assert_eq!(7, get_user_version(&conn).unwrap());
+
+        let added_at: i64 = conn
+            .query_row(
+                "SELECT added_at FROM tracks WHERE file = '/a.mp3'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(added_at, 1000);
+    }
+
+    #[test]
+    fn should_migrate_from_v5() {
+        let conn = gen_database();
+
+        conn.execute_batch(include_str!("./migrations/002.sql"))
+            .unwrap();
+        conn.execute(
+            "INSERT INTO tracks (artist, title, album, genre, file) VALUES ('Some Artist', 'Some Title', 'Some Album', 'Jazz', '/a.mp3')",
+            [],
+        )
+        .unwrap();
+        for migration in [
+            include_str!("./migrations/003.sql"),
+            include_str!("./migrations/004.sql"),
+            include_str!("./migrations/005.sql"),
+        ] {
+            conn.execute_batch(migration).unwrap();
+        }
+        set_user_version(&conn, 5).unwrap();
+
+        migrate(&conn).unwrap();
+        assert_eq!(7, get_user_version(&conn).unwrap());
+
+        // existing tracks need to be indexed
+        let found: Vec<String> = {
+            let mut prep = conn
+                .prepare("SELECT tracks.file FROM tracks JOIN tracks_fts ON tracks_fts.rowid = tracks.id WHERE tracks_fts MATCH 'artist : artist'")
+                .unwrap();
+            prep.query_map([], |r| r.get(0))
+                .unwrap()
+                .flatten()
+                .collect()
+        };
+        assert_eq!(&found, &["/a.mp3"]);
+    }
+
+    #[test]
+    fn should_migrate_from_v6() {
+        let conn = gen_database();
+
+        conn.execute_batch(include_str!("./migrations/002.sql"))
+            .unwrap();
+        for migration in [
+            include_str!("./migrations/003.sql"),
+            include_str!("./migrations/004.sql"),
+            include_str!("./migrations/005.sql"),
+            include_str!("./migrations/006.sql"),
+        ] {
+            conn.execute_batch(migration).unwrap();
+        }
+        set_user_version(&conn, 6).unwrap();
+        conn.execute(
+            "INSERT INTO tracks (file, last_modified) VALUES ('/a.mp3', '1000')",
+            [],
+        )
+        .unwrap();
+
+        migrate(&conn).unwrap();
+        assert_eq!(7, get_user_version(&conn).unwrap());
+
+        // existing tracks need to be read again to get the new values
+        let (last_modified, track_number): (String, Option<u32>) = conn
+            .query_row(
+                "SELECT last_modified, track_number FROM tracks WHERE file = '/a.mp3'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(last_modified, "0");
+        assert_eq!(track_number, None);
     }
 }
@@ -0,0 +1,4 @@
+ALTER TABLE tracks ADD COLUMN replaygain_track_gain REAL;
+ALTER TABLE tracks ADD COLUMN replaygain_track_peak REAL;
+ALTER TABLE tracks ADD COLUMN replaygain_album_gain REAL;
+ALTER TABLE tracks ADD COLUMN replaygain_album_peak REAL;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS play_history(
+    id INTEGER PRIMARY KEY,
+    file TEXT NOT NULL,
+    played_at INTEGER NOT NULL,
+    listened INTEGER NOT NULL,
+    skipped INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS play_history_file ON play_history(file);
+CREATE INDEX IF NOT EXISTS play_history_played_at ON play_history(played_at);
@@ -0,0 +1,3 @@
+ALTER TABLE tracks ADD COLUMN added_at INTEGER;
+-- the real time is unknown for existing tracks, the modification time is the closest there is
+UPDATE tracks SET added_at = CAST(last_modified AS INTEGER);
@@ -0,0 +1,32 @@
+ALTER TABLE tracks ADD COLUMN year INTEGER;
+
+-- full-text index over the text columns of "tracks", only storing the index itself
+CREATE VIRTUAL TABLE IF NOT EXISTS tracks_fts USING fts5(
+    artist,
+    title,
+    album,
+    genre,
+    file,
+    content='tracks',
+    content_rowid='id',
+    tokenize='unicode61 remove_diacritics 2'
+);
+
+-- keep the index in sync with "tracks"
+CREATE TRIGGER IF NOT EXISTS tracks_fts_insert AFTER INSERT ON tracks BEGIN
+    INSERT INTO tracks_fts(rowid, artist, title, album, genre, file)
+    VALUES (new.id, new.artist, new.title, new.album, new.genre, new.file);
+END;
+CREATE TRIGGER IF NOT EXISTS tracks_fts_delete AFTER DELETE ON tracks BEGIN
+    INSERT INTO tracks_fts(tracks_fts, rowid, artist, title, album, genre, file)
+    VALUES ('delete', old.id, old.artist, old.title, old.album, old.genre, old.file);
+END;
+CREATE TRIGGER IF NOT EXISTS tracks_fts_update AFTER UPDATE OF artist, title, album, genre, file ON tracks BEGIN
+    INSERT INTO tracks_fts(tracks_fts, rowid, artist, title, album, genre, file)
+    VALUES ('delete', old.id, old.artist, old.title, old.album, old.genre, old.file);
+    INSERT INTO tracks_fts(rowid, artist, title, album, genre, file)
+    VALUES (new.id, new.artist, new.title, new.album, new.genre, new.file);
+END;
+
+-- index all already existing tracks
+INSERT INTO tracks_fts(tracks_fts) VALUES ('rebuild');
@@ -0,0 +1,6 @@
+ALTER TABLE tracks ADD COLUMN album_artist TEXT;
+ALTER TABLE tracks ADD COLUMN composer TEXT;
+ALTER TABLE tracks ADD COLUMN track_number INTEGER;
+ALTER TABLE tracks ADD COLUMN disc_number INTEGER;
+-- make the next scan read all tracks again to fill the new columns, existing rows are updated in-place
+UPDATE tracks SET last_modified = '0';
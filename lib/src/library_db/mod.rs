@@ -27,17 +27,26 @@ use crate::track::Track;
 use crate::utils::{filetype_supported, get_app_config_path, get_pin_yin};
 use anyhow::Context;
 use parking_lot::Mutex;
-use rusqlite::{params, Connection, Error, Result};
+use play_history::PlayHistoryInsertable;
+use rusqlite::{params, params_from_iter, Connection, Error, Result};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use track_db::TrackDBInsertable;
 
 mod migration;
+mod play_history;
+pub mod search;
+mod smart_playlist;
 mod track_db;
 
+pub use play_history::PlayStats;
+pub use search::SearchQuery;
+pub use smart_playlist::SmartPlaylist;
 pub use track_db::TrackDB;
 
+#[derive(Debug)]
 pub struct DataBase {
     conn: Arc<Mutex<Connection>>,
     max_depth: ScanDepth,
@@ -47,10 +56,11 @@ pub struct DataBase {
 pub enum SearchCriteria {
     Artist,
     Album,
-
-    // TODO: the values below are current unused
     Genre,
     Directory,
+    /// Rule based playlists, see [`SmartPlaylist`]
+    SmartPlaylist,
+    /// Playlist files found in the music directories
     Playlist,
 }
 
@@ -61,6 +71,7 @@ impl From<usize> for SearchCriteria {
             2 => Self::Genre,
             3 => Self::Directory,
             4 => Self::Playlist,
+            5 => Self::SmartPlaylist,
             /* 0 | */ _ => Self::Artist,
         }
     }
@@ -74,6 +85,7 @@ impl std::fmt::Display for SearchCriteria {
             Self::Genre => write!(f, "genre"),
             Self::Directory => write!(f, "directory"),
             Self::Playlist => write!(f, "playlist"),
+            Self::SmartPlaylist => write!(f, "smart_playlist"),
         }
     }
 }
@@ -87,6 +99,12 @@ impl DataBase {
         let mut db_path = get_app_config_path().context("failed to get app configuration path")?;
         db_path.push("library.db");
         let conn = Connection::open(db_path).context("open/create database")?;
+        // the server and the tui both write to the database, so wait for the other instead of failing
+        conn.busy_timeout(Duration::from_secs(5))
+            .context("set database busy timeout")?;
+        // let readers not block the writer and the other way around
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+            .context("set database journal mode")?;
 
         migration::migrate(&conn).context("Database creation / migration")?;
 
@@ -96,13 +114,13 @@ impl DataBase {
         Ok(Self { conn, max_depth })
     }
 
-    /// Insert multiple tracks into the database
+    /// Insert multiple tracks into the database, updating tracks that already exist
     fn add_records(conn: &Arc<Mutex<Connection>>, tracks: Vec<Track>) -> Result<()> {
         let mut conn = conn.lock();
         let tx = conn.transaction()?;
 
         for track in tracks {
-            TrackDBInsertable::from(&track).insert_track(&tx)?;
+            TrackDBInsertable::from(&track).upsert_track(&tx)?;
         }
 
         tx.commit()?;
@@ -112,21 +130,23 @@ impl DataBase {
     /// Check if the given path's track needs to be updated in the database by comparing `last_modified` times
     fn need_update(conn: &Arc<Mutex<Connection>>, path: &Path) -> Result<bool> {
         let conn = conn.lock();
-        let filename = path
-            .file_name()
-            .ok_or_else(|| Error::InvalidParameterName("file name missing".to_string()))?
-            .to_string_lossy();
-        let mut stmt = conn.prepare("SELECT last_modified FROM tracks WHERE name = ?")?;
-        let rows = stmt.query_map([filename], |row| {
+        // the file may have been removed again since it was found
+        let Ok(timestamp) = path.metadata().and_then(|v| v.modified()) else {
+            return Ok(false);
+        };
+        let timestamp_u64 = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut stmt = conn.prepare("SELECT last_modified FROM tracks WHERE file = ?")?;
+        let rows = stmt.query_map([path.to_string_lossy()], |row| {
             let last_modified: String = row.get(0)?;
 
             Ok(last_modified)
         })?;
 
         for r in rows.flatten() {
-            let r_u64: u64 = r.parse().unwrap();
-            let timestamp = path.metadata().unwrap().modified().unwrap();
-            let timestamp_u64 = timestamp.duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let r_u64: u64 = r.parse().unwrap_or_default();
             if timestamp_u64 <= r_u64 {
                 return Ok(false);
             }
@@ -169,60 +189,155 @@ impl DataBase {
         Ok(())
     }
 
-    /// Synchronize the database with the on-disk paths (insert, update, remove), limited to `path` root
-    pub fn sync_database(&mut self, path: &Path) {
-        // add updated records
-        let conn = self.conn.clone();
-        let all_items = {
-            let mut walker = walkdir::WalkDir::new(path).follow_links(true);
-
-            if let ScanDepth::Limited(limit) = self.max_depth {
-                walker = walker.max_depth(usize::try_from(limit).unwrap_or(usize::MAX));
-            }
+    /// Create the walker for `path`, limited to the configured scan depth
+    fn walker(&self, path: &Path) -> walkdir::WalkDir {
+        let mut walker = walkdir::WalkDir::new(path).follow_links(true);
 
-            walker
-        };
+        if let ScanDepth::Limited(limit) = self.max_depth {
+            walker = walker.max_depth(usize::try_from(limit).unwrap_or(usize::MAX));
+        }
 
-        std::thread::spawn(move || -> Result<()> {
-            let mut need_updates: Vec<Track> = vec![];
+        walker
+    }
 
-            for record in all_items
-                .into_iter()
-                .filter_map(std::result::Result::ok)
-                .filter(|f| f.file_type().is_file())
-                .filter(|f| filetype_supported(&f.path().to_string_lossy()))
-            {
-                match Self::need_update(&conn, record.path()) {
-                    Ok(true) => {
-                        if let Ok(track) = Track::read_from_path(record.path(), true) {
-                            need_updates.push(track);
-                        }
-                    }
-                    Ok(false) => {}
-                    Err(e) => {
-                        error!("Error in need_update: {e}");
+    /// Insert or update all supported files found by `walker` that changed since they were last added
+    ///
+    /// Returns the number of tracks inserted or updated
+    fn update_records(conn: &Arc<Mutex<Connection>>, walker: walkdir::WalkDir) -> Result<usize> {
+        let mut need_updates: Vec<Track> = vec![];
+
+        for record in walker
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|f| f.file_type().is_file())
+            .filter(|f| filetype_supported(&f.path().to_string_lossy()))
+        {
+            match Self::need_update(conn, record.path()) {
+                Ok(true) => {
+                    if let Ok(track) = Track::read_from_path(record.path(), true) {
+                        need_updates.push(track);
                     }
                 }
+                Ok(false) => {}
+                Err(e) => {
+                    error!("Error in need_update: {e}");
+                }
             }
-            if !need_updates.is_empty() {
-                Self::add_records(&conn, need_updates)?;
-            }
+        }
 
-            // delete records where local file are missing
+        let updated = need_updates.len();
+        if !need_updates.is_empty() {
+            Self::add_records(conn, need_updates)?;
+        }
 
-            match Self::need_delete(&conn) {
-                Ok(string_vec) => {
-                    if !string_vec.is_empty() {
-                        Self::delete_records(&conn, string_vec)?;
-                    }
-                }
-                Err(e) => {
-                    error!("Error in need_delete: {e}");
+        Ok(updated)
+    }
+
+    /// Do a full synchronization of the database with the on-disk paths, limited to `walker`
+    ///
+    /// Returns the number of tracks inserted, updated or removed
+    fn sync_records(conn: &Arc<Mutex<Connection>>, walker: walkdir::WalkDir) -> Result<usize> {
+        // add updated records
+        let mut changed = Self::update_records(conn, walker)?;
+
+        // delete records where local file are missing
+        match Self::need_delete(conn) {
+            Ok(string_vec) => {
+                changed += string_vec.len();
+                if !string_vec.is_empty() {
+                    Self::delete_records(conn, string_vec)?;
                 }
             }
+            Err(e) => {
+                error!("Error in need_delete: {e}");
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Synchronize the database with the on-disk paths (insert, update, remove), limited to `path` root
+    ///
+    /// This runs in a background thread, see [`Self::sync_database_blocking`] to wait for the result.
+    pub fn sync_database(&mut self, path: &Path) {
+        let conn = self.conn.clone();
+        let walker = self.walker(path);
+
+        std::thread::spawn(move || -> Result<usize> { Self::sync_records(&conn, walker) });
+    }
+
+    /// Synchronize the database with the on-disk paths (insert, update, remove), limited to `path` root
+    ///
+    /// Returns the number of tracks inserted, updated or removed
+    pub fn sync_database_blocking(&mut self, path: &Path) -> Result<usize> {
+        Self::sync_records(&self.conn, self.walker(path))
+    }
+
+    /// Insert or update the track at `path`, or all tracks below `path` if it is a directory
+    ///
+    /// Unlike [`Self::sync_database`], this does not remove tracks that are missing on disk.
+    /// Returns the number of tracks inserted or updated.
+    pub fn sync_path(&mut self, path: &Path) -> Result<usize> {
+        Self::update_records(&self.conn, self.walker(path))
+    }
+
+    /// Remove the track at `path`, or all tracks below `path` if it is a directory
+    ///
+    /// Returns the number of removed tracks
+    pub fn remove_path(&mut self, path: &Path) -> Result<usize> {
+        let conn = self.conn.lock();
+        let (file, prefix) = path_and_prefix(path);
 
-            Ok(())
-        });
+        conn.execute(
+            "DELETE FROM tracks WHERE file = ?1 OR substr(file, 1, length(?2)) = ?2",
+            params![file, prefix],
+        )
+    }
+
+    /// Move the track at `from`, or all tracks below `from` if it is a directory, to `to`
+    ///
+    /// This keeps the play position and play history of the moved tracks.
+    /// Returns the number of moved tracks.
+    pub fn rename_path(&mut self, from: &Path, to: &Path) -> Result<usize> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let (file, prefix) = path_and_prefix(from);
+
+        let moved: Vec<(u64, String)> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, file FROM tracks WHERE file = ?1 OR substr(file, 1, length(?2)) = ?2",
+            )?;
+            let rows =
+                stmt.query_map(params![file, prefix], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.flatten().collect()
+        };
+
+        for (id, old_file) in &moved {
+            let new_path = match Path::new(old_file).strip_prefix(from) {
+                // "join" with a empty path would add a trailing separator
+                Ok(rest) if !rest.as_os_str().is_empty() => to.join(rest),
+                _ => to.to_path_buf(),
+            };
+            let new_file = new_path.to_string_lossy();
+
+            tx.execute(
+                "UPDATE tracks SET file = ?1, name = ?2, ext = ?3, directory = ?4 WHERE id = ?5",
+                params![
+                    new_file,
+                    new_path.file_name().map(|v| v.to_string_lossy()),
+                    new_path.extension().map(|v| v.to_string_lossy()),
+                    new_path.parent().map(|v| v.to_string_lossy()),
+                    id
+                ],
+            )?;
+            tx.execute(
+                "UPDATE play_history SET file = ?1 WHERE file = ?2",
+                params![new_file, old_file],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(moved.len())
     }
 
     /// Get all Tracks in the database at once
@@ -242,35 +357,70 @@ impl DataBase {
         criteria_val: &str,
         criteria: &SearchCriteria,
     ) -> Result<Vec<TrackDB>> {
-        let search_str = format!("SELECT * FROM tracks WHERE {criteria} = ?");
+        self.get_records_by_filters(&[(*criteria, criteria_val)])
+    }
+
+    /// Get Tracks matching all the given `(criteria, value)` filters, like `[(Genre, "Jazz"), (Artist, "Miles Davis")]`
+    ///
+    /// Tracks are sorted by file name, or in album order if filtered by album
+    pub fn get_records_by_filters(
+        &mut self,
+        filters: &[(SearchCriteria, &str)],
+    ) -> Result<Vec<TrackDB>> {
+        let search_str = format!("SELECT * FROM tracks{}", filters_to_where(filters));
         let conn = self.conn.lock();
         let mut stmt = conn.prepare(&search_str)?;
 
         let mut vec_records: Vec<TrackDB> = stmt
-            .query_map([criteria_val], TrackDB::try_from_row_named)?
+            .query_map(
+                params_from_iter(filters.iter().map(|(_, value)| value)),
+                TrackDB::try_from_row_named,
+            )?
             .flatten()
             .collect();
 
         // Left for debug
-        // error!("criteria_val: {}", criteria_val);
-        // error!("criteria: {}", criteria);
+        // error!("filters: {:?}", filters);
         // error!("vec: {:?}", vec_records);
 
-        vec_records.sort_by_cached_key(|k| get_pin_yin(&k.name));
+        if filters
+            .iter()
+            .any(|(criteria, _)| *criteria == SearchCriteria::Album)
+        {
+            vec_records.sort_by_cached_key(|k| (k.album_order_key(), get_pin_yin(&k.name)));
+        } else {
+            vec_records.sort_by_cached_key(|k| get_pin_yin(&k.name));
+        }
         Ok(vec_records)
     }
 
     /// Get a list of available distinct [`SearchCriteria`] (ie get Artist names deduplicated)
     pub fn get_criterias(&mut self, criteria: &SearchCriteria) -> Result<Vec<String>> {
-        let search_str = format!("SELECT DISTINCT {criteria} FROM tracks");
+        self.get_criterias_by_filters(criteria, &[])
+    }
+
+    /// Get a list of available distinct [`SearchCriteria`] of the Tracks matching all the given filters
+    /// (ie get the Artist names deduplicated of a given Genre)
+    pub fn get_criterias_by_filters(
+        &mut self,
+        criteria: &SearchCriteria,
+        filters: &[(SearchCriteria, &str)],
+    ) -> Result<Vec<String>> {
+        let search_str = format!(
+            "SELECT DISTINCT {criteria} FROM tracks{}",
+            filters_to_where(filters)
+        );
         let conn = self.conn.lock();
         let mut stmt = conn.prepare(&search_str)?;
 
         let mut vec: Vec<String> = stmt
-            .query_map([], |row| {
-                let criteria: String = row.get(0)?;
-                Ok(criteria)
-            })?
+            .query_map(
+                params_from_iter(filters.iter().map(|(_, value)| value)),
+                |row| {
+                    let criteria: String = row.get(0)?;
+                    Ok(criteria)
+                },
+            )?
             .flatten()
             .collect();
 
@@ -326,6 +476,151 @@ impl DataBase {
 
         Err(Error::QueryReturnedNoRows)
     }
+
+    /// Record a single play of the track at the given full file path in the play history
+    ///
+    /// `skipped` should be `true` if the track was not played to the end.
+    pub fn add_play(
+        &mut self,
+        file_path: &str,
+        played_at: SystemTime,
+        listened: Duration,
+        skipped: bool,
+    ) -> Result<()> {
+        let conn = self.conn.lock();
+        PlayHistoryInsertable {
+            file: file_path,
+            played_at,
+            listened,
+            skipped,
+        }
+        .insert_play(&conn)?;
+
+        Ok(())
+    }
+
+    /// Get the [`PlayStats`] of the track at the given full file path
+    pub fn get_play_stats(&mut self, file_path: &str) -> Result<PlayStats> {
+        let query = "SELECT COALESCE(SUM(skipped = 0), 0) AS play_count, COALESCE(SUM(skipped), 0) AS skip_count, MAX(played_at) AS last_played
+            FROM play_history WHERE file = ?";
+        let conn = self.conn.lock();
+        conn.query_row(query, [file_path], PlayStats::try_from_row_named)
+    }
+
+    /// Get how often each track was played to the end, by file
+    ///
+    /// Tracks that were never played to the end are not included.
+    pub fn get_play_counts(&mut self) -> Result<HashMap<String, u64>> {
+        let query =
+            "SELECT file, COUNT(*) AS play_count FROM play_history WHERE skipped = 0 GROUP BY file";
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(query)?;
+
+        let play_counts = stmt
+            .query_map([], |row| Ok((row.get("file")?, row.get("play_count")?)))?
+            .collect();
+        play_counts
+    }
+
+    /// Get the `limit` Tracks with the most plays to the end, most played first
+    pub fn get_most_played(&mut self, limit: u32) -> Result<Vec<TrackDB>> {
+        let search_str = "SELECT tracks.* FROM tracks
+            INNER JOIN (SELECT file, COUNT(*) AS play_count FROM play_history WHERE skipped = 0 GROUP BY file) AS history
+            ON history.file = tracks.file
+            ORDER BY history.play_count DESC LIMIT ?";
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(search_str)?;
+
+        let vec_records: Vec<TrackDB> = stmt
+            .query_map([limit], TrackDB::try_from_row_named)?
+            .flatten()
+            .collect();
+
+        Ok(vec_records)
+    }
+
+    /// Get the `limit` Tracks that were played last, including skipped plays, most recent first
+    pub fn get_recently_played(&mut self, limit: u32) -> Result<Vec<TrackDB>> {
+        let search_str = "SELECT tracks.* FROM tracks
+            INNER JOIN (SELECT file, MAX(played_at) AS last_played FROM play_history GROUP BY file) AS history
+            ON history.file = tracks.file
+            ORDER BY history.last_played DESC LIMIT ?";
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(search_str)?;
+
+        let vec_records: Vec<TrackDB> = stmt
+            .query_map([limit], TrackDB::try_from_row_named)?
+            .flatten()
+            .collect();
+
+        Ok(vec_records)
+    }
+
+    /// Get all Tracks matching the rules of the given [`SmartPlaylist`], evaluated at the time of calling
+    pub fn get_smart_playlist_tracks(&mut self, playlist: &SmartPlaylist) -> Result<Vec<TrackDB>> {
+        let (search_str, params) = playlist.to_query()?;
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&search_str)?;
+
+        let vec_records: Vec<TrackDB> = stmt
+            .query_map(params_from_iter(params), TrackDB::try_from_row_named)?
+            .flatten()
+            .collect();
+
+        Ok(vec_records)
+    }
+
+    /// Get at most `limit` Tracks matching the given [`SearchQuery`], most relevant first
+    pub fn search_tracks(&mut self, query: &SearchQuery, limit: u32) -> Result<Vec<TrackDB>> {
+        let (search_str, params) = query.to_sql(limit);
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&search_str)?;
+
+        let vec_records: Vec<TrackDB> = stmt
+            .query_map(params_from_iter(params), TrackDB::try_from_row_named)?
+            .flatten()
+            .collect();
+
+        Ok(vec_records)
+    }
+
+    /// Get all Tracks that have never been played, not even skipped
+    pub fn get_never_played(&mut self) -> Result<Vec<TrackDB>> {
+        let search_str =
+            "SELECT * FROM tracks WHERE file NOT IN (SELECT DISTINCT file FROM play_history)";
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(search_str)?;
+
+        let mut vec_records: Vec<TrackDB> = stmt
+            .query_map([], TrackDB::try_from_row_named)?
+            .flatten()
+            .collect();
+
+        vec_records.sort_by_cached_key(|k| get_pin_yin(&k.name));
+        Ok(vec_records)
+    }
+}
+
+/// Build a `WHERE` clause (including the leading space) matching all `filters` with one parameter each, empty if there are no filters
+fn filters_to_where(filters: &[(SearchCriteria, &str)]) -> String {
+    if filters.is_empty() {
+        return String::new();
+    }
+
+    let conditions: Vec<String> = filters
+        .iter()
+        .map(|(criteria, _)| format!("{criteria} = ?"))
+        .collect();
+
+    format!(" WHERE {}", conditions.join(" AND "))
+}
+
+/// Get `path` as a string and as a directory prefix with a trailing separator, to match `path` itself and everything below it
+fn path_and_prefix(path: &Path) -> (String, String) {
+    let file = path.to_string_lossy().to_string();
+    let prefix = format!("{file}{}", std::path::MAIN_SEPARATOR);
+
+    (file, prefix)
 }
 
 #[cfg(test)]
@@ -337,3 +632,343 @@ mod test_utils {
         Connection::open_in_memory().expect("open db failed")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use parking_lot::Mutex;
+    use pretty_assertions::assert_eq;
+
+    use super::test_utils::gen_database;
+    use super::{migration, DataBase, SearchQuery, SmartPlaylist};
+    use crate::config::v2::server::ScanDepth;
+
+    /// Create a migrated in-memory [`DataBase`] with tracks for all given files
+    fn gen_library(files: &[&str]) -> DataBase {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+        for file in files {
+            conn.execute(
+                "INSERT INTO tracks (artist, title, album, genre, file, duration, name, ext, directory, last_modified, last_position)
+                VALUES ('', '', '', '', ?1, 0, ?1, '', '', '0', 0)",
+                [file],
+            )
+            .unwrap();
+        }
+
+        DataBase {
+            conn: Arc::new(Mutex::new(conn)),
+            max_depth: ScanDepth::Unlimited,
+        }
+    }
+
+    fn files(tracks: &[super::TrackDB]) -> Vec<&str> {
+        tracks.iter().map(|v| v.file.as_str()).collect()
+    }
+
+    #[test]
+    fn should_get_play_counts_of_all_tracks() {
+        let mut db = gen_library(&["/a.mp3", "/b.mp3", "/c.mp3"]);
+
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        for (file, skipped) in [
+            ("/a.mp3", false),
+            ("/a.mp3", false),
+            ("/b.mp3", false),
+            ("/b.mp3", true),
+            ("/c.mp3", true),
+        ] {
+            db.add_play(file, start, Duration::from_secs(120), skipped)
+                .unwrap();
+        }
+
+        let play_counts = db.get_play_counts().unwrap();
+        assert_eq!(play_counts.len(), 2);
+        assert_eq!(play_counts.get("/a.mp3"), Some(&2));
+        assert_eq!(play_counts.get("/b.mp3"), Some(&1));
+        assert_eq!(play_counts.get("/c.mp3"), None);
+    }
+
+    #[test]
+    fn should_count_plays_and_skips() {
+        let mut db = gen_library(&["/a.mp3"]);
+
+        let stats = db.get_play_stats("/a.mp3").unwrap();
+        assert_eq!(stats.play_count, 0);
+        assert_eq!(stats.skip_count, 0);
+        assert_eq!(stats.last_played, None);
+
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        db.add_play("/a.mp3", start, Duration::from_secs(120), false)
+            .unwrap();
+        db.add_play(
+            "/a.mp3",
+            start + Duration::from_secs(500),
+            Duration::from_secs(3),
+            true,
+        )
+        .unwrap();
+        db.add_play(
+            "/a.mp3",
+            start + Duration::from_secs(200),
+            Duration::from_secs(120),
+            false,
+        )
+        .unwrap();
+
+        let stats = db.get_play_stats("/a.mp3").unwrap();
+        assert_eq!(stats.play_count, 2);
+        assert_eq!(stats.skip_count, 1);
+        assert_eq!(stats.last_played.map(|v| v.timestamp()), Some(1_500));
+    }
+
+    #[test]
+    fn should_list_by_history() {
+        let mut db = gen_library(&["/a.mp3", "/b.mp3", "/c.mp3"]);
+
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let listened = Duration::from_secs(60);
+        db.add_play("/a.mp3", start, listened, false).unwrap();
+        db.add_play("/b.mp3", start + listened, listened, false)
+            .unwrap();
+        db.add_play("/b.mp3", start + listened * 2, listened, false)
+            .unwrap();
+        db.add_play("/a.mp3", start + listened * 3, listened, true)
+            .unwrap();
+
+        assert_eq!(
+            files(&db.get_most_played(10).unwrap()),
+            &["/b.mp3", "/a.mp3"]
+        );
+        assert_eq!(files(&db.get_most_played(1).unwrap()), &["/b.mp3"]);
+        assert_eq!(
+            files(&db.get_recently_played(10).unwrap()),
+            &["/a.mp3", "/b.mp3"]
+        );
+        assert_eq!(files(&db.get_never_played().unwrap()), &["/c.mp3"]);
+    }
+
+    #[test]
+    fn should_evaluate_smart_playlist() {
+        let mut db = gen_library(&["/a.mp3", "/b.mp3", "/c.mp3"]);
+        {
+            let conn = db.conn.lock();
+            for (file, genre, duration, added_at) in [
+                ("/a.mp3", "Jazz", 300, 1_000),
+                ("/b.mp3", "Acid Jazz", 100, 2_000_000_000),
+                ("/c.mp3", "Rock", 400, 2_000_000_000),
+            ] {
+                conn.execute(
+                    "UPDATE tracks SET genre = ?2, duration = ?3, added_at = ?4 WHERE file = ?1",
+                    rusqlite::params![file, genre, duration, added_at],
+                )
+                .unwrap();
+            }
+        }
+        db.add_play("/a.mp3", SystemTime::now(), Duration::from_secs(300), false)
+            .unwrap();
+
+        let mut playlist = SmartPlaylist {
+            name: "jazz".to_string(),
+            genre: Some("jazz".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            files(&db.get_smart_playlist_tracks(&playlist).unwrap()),
+            &["/a.mp3", "/b.mp3"]
+        );
+
+        playlist.min_duration_secs = Some(200);
+        assert_eq!(
+            files(&db.get_smart_playlist_tracks(&playlist).unwrap()),
+            &["/a.mp3"]
+        );
+
+        playlist.max_play_count = Some(0);
+        assert!(db.get_smart_playlist_tracks(&playlist).unwrap().is_empty());
+
+        let playlist = SmartPlaylist {
+            added_after: Some("2020-01-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            files(&db.get_smart_playlist_tracks(&playlist).unwrap()),
+            &["/b.mp3", "/c.mp3"]
+        );
+
+        let playlist = SmartPlaylist {
+            added_after: Some("not a date".to_string()),
+            ..Default::default()
+        };
+        assert!(db.get_smart_playlist_tracks(&playlist).is_err());
+    }
+
+    #[test]
+    fn should_filter_by_criterias() {
+        use super::SearchCriteria;
+
+        let mut db = gen_library(&["/jazz/a.mp3", "/jazz/b.mp3", "/rock/c.mp3"]);
+        {
+            let conn = db.conn.lock();
+            for (file, genre, artist, album, directory) in [
+                ("/jazz/a.mp3", "Jazz", "Artist A", "Album 1", "/jazz"),
+                ("/jazz/b.mp3", "Jazz", "Artist B", "Album 2", "/jazz"),
+                ("/rock/c.mp3", "Rock", "Artist A", "Album 3", "/rock"),
+            ] {
+                conn.execute(
+                    "UPDATE tracks SET genre = ?2, artist = ?3, album = ?4, directory = ?5 WHERE file = ?1",
+                    rusqlite::params![file, genre, artist, album, directory],
+                )
+                .unwrap();
+            }
+        }
+
+        assert_eq!(
+            db.get_criterias(&SearchCriteria::Genre).unwrap(),
+            &["Jazz", "Rock"]
+        );
+        assert_eq!(
+            db.get_criterias(&SearchCriteria::Directory).unwrap(),
+            &["/jazz", "/rock"]
+        );
+        assert_eq!(
+            db.get_criterias_by_filters(
+                &SearchCriteria::Artist,
+                &[(SearchCriteria::Genre, "Jazz")]
+            )
+            .unwrap(),
+            &["Artist A", "Artist B"]
+        );
+        assert_eq!(
+            db.get_criterias_by_filters(
+                &SearchCriteria::Album,
+                &[
+                    (SearchCriteria::Genre, "Jazz"),
+                    (SearchCriteria::Artist, "Artist A")
+                ]
+            )
+            .unwrap(),
+            &["Album 1"]
+        );
+        assert_eq!(
+            files(
+                &db.get_records_by_filters(&[(SearchCriteria::Artist, "Artist A")])
+                    .unwrap()
+            ),
+            &["/jazz/a.mp3", "/rock/c.mp3"]
+        );
+    }
+
+    #[test]
+    fn should_sort_album_in_album_order() {
+        use super::SearchCriteria;
+
+        let mut db = gen_library(&["/a.mp3", "/b.mp3", "/c.mp3", "/d.mp3"]);
+        {
+            let conn = db.conn.lock();
+            for (file, disc_number, track_number) in [
+                ("/a.mp3", Some(2), Some(1)),
+                ("/b.mp3", None, None),
+                ("/c.mp3", Some(1), Some(2)),
+                ("/d.mp3", None, Some(1)),
+            ] {
+                conn.execute(
+                    "UPDATE tracks SET album = 'Album', disc_number = ?2, track_number = ?3 WHERE file = ?1",
+                    rusqlite::params![file, disc_number, track_number],
+                )
+                .unwrap();
+            }
+        }
+
+        assert_eq!(
+            files(
+                &db.get_record_by_criteria("Album", &SearchCriteria::Album)
+                    .unwrap()
+            ),
+            &["/d.mp3", "/c.mp3", "/b.mp3", "/a.mp3"]
+        );
+    }
+
+    #[test]
+    fn should_search_with_query() {
+        let mut db = gen_library(&["/a.mp3", "/b.mp3", "/c.mp3"]);
+        {
+            let conn = db.conn.lock();
+            for (file, artist, album, genre, year) in [
+                ("/a.mp3", "Miles Davis", "Kind of Blue", "Jazz", 1959),
+                (
+                    "/b.mp3",
+                    "Miles Davis",
+                    "Live at the Plugged Nickel",
+                    "Live Jazz",
+                    1965,
+                ),
+                ("/c.mp3", "Davis Band", "Kind of Miles", "Rock", 2005),
+            ] {
+                conn.execute(
+                    "UPDATE tracks SET artist = ?2, album = ?3, genre = ?4, year = ?5 WHERE file = ?1",
+                    rusqlite::params![file, artist, album, genre, year],
+                )
+                .unwrap();
+            }
+        }
+
+        let sorted = |mut v: Vec<String>| {
+            v.sort();
+            v
+        };
+        let mut search = |query: &str| -> Vec<String> {
+            let query = SearchQuery::parse(query).unwrap();
+            db.search_tracks(&query, 100)
+                .unwrap()
+                .into_iter()
+                .map(|v| v.file)
+                .collect()
+        };
+
+        assert_eq!(sorted(search("artist:miles")), &["/a.mp3", "/b.mp3"]);
+        assert_eq!(search("mil -genre:live"), &["/a.mp3", "/c.mp3"]);
+        assert_eq!(sorted(search("album:\"kind of\"")), &["/a.mp3", "/c.mp3"]);
+        assert_eq!(search("davis year:>2000"), &["/c.mp3"]);
+        assert_eq!(search("-year:<1960"), &["/c.mp3", "/b.mp3"]);
+        // matches in the artist are ranked higher than in the album
+        assert_eq!(search("miles").last().unwrap(), "/c.mp3");
+        assert_eq!(search("").len(), 3);
+    }
+
+    #[test]
+    fn should_apply_path_changes() {
+        let root = Path::new("/music");
+        let a1 = root.join("a").join("1.mp3").to_string_lossy().to_string();
+        let a2 = root.join("a").join("2.mp3").to_string_lossy().to_string();
+        let ab3 = root.join("ab").join("3.mp3").to_string_lossy().to_string();
+        let mut db = gen_library(&[&a1, &a2, &ab3]);
+        db.add_play(&a1, SystemTime::now(), Duration::from_secs(1), false)
+            .unwrap();
+
+        // "ab" only shares the prefix, but is not below "a"
+        assert_eq!(db.rename_path(&root.join("a"), &root.join("c")).unwrap(), 2);
+        let c1 = root.join("c").join("1.mp3");
+        let track = db.get_record_by_path(&c1.to_string_lossy()).unwrap();
+        assert_eq!(track.name, "1.mp3");
+        assert_eq!(track.directory, root.join("c").to_string_lossy());
+        assert_eq!(
+            db.get_play_stats(&c1.to_string_lossy()).unwrap().play_count,
+            1
+        );
+
+        let d1 = root.join("d.mp3");
+        assert_eq!(db.rename_path(&c1, &d1).unwrap(), 1);
+        assert_eq!(
+            db.get_record_by_path(&d1.to_string_lossy()).unwrap().name,
+            "d.mp3"
+        );
+
+        assert_eq!(db.remove_path(&root.join("c")).unwrap(), 1);
+        assert_eq!(db.remove_path(&d1).unwrap(), 1);
+        assert_eq!(files(&db.get_all_records().unwrap()), vec![ab3.as_str()]);
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use rusqlite::{named_params, Connection, Row};
+
+/// Aggregated play statistics of a single track, from the `play_history` table
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlayStats {
+    /// How often the track was played to the end
+    pub play_count: u64,
+    /// How often the track was skipped before the end
+    pub skip_count: u64,
+    /// When the track was last started, regardless if it was skipped or not
+    pub last_played: Option<DateTime<Utc>>,
+}
+
+impl PlayStats {
+    /// Try to convert a given row to a [`PlayStats`] instance, using column names to resolve the values
+    pub fn try_from_row_named(row: &Row<'_>) -> Result<Self, rusqlite::Error> {
+        let last_played: Option<i64> = row.get("last_played")?;
+        Ok(PlayStats {
+            play_count: row.get("play_count")?,
+            skip_count: row.get("skip_count")?,
+            last_played: last_played.and_then(|v| DateTime::from_timestamp(v, 0)),
+        })
+    }
+}
+
+/// A single play of a track to be inserted into the `play_history` table
+#[derive(Clone, Debug)]
+pub struct PlayHistoryInsertable<'a> {
+    // generated by the database
+    // pub id: u64,
+    pub file: &'a str,
+    pub played_at: SystemTime,
+    pub listened: Duration,
+    pub skipped: bool,
+}
+
+impl PlayHistoryInsertable<'_> {
+    /// Insert the current [`PlayHistoryInsertable`] into the `play_history` table
+    #[inline]
+    pub fn insert_play(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
+        con.execute(
+            "INSERT INTO play_history (file, played_at, listened, skipped)
+            values (:file, :played_at, :listened, :skipped)",
+            named_params![
+                ":file": &self.file,
+                ":played_at": &self
+                    .played_at
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                ":listened": &self.listened.as_secs(),
+                ":skipped": &self.skipped,
+            ],
+        )
+    }
+}
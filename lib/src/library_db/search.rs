@@ -0,0 +1,353 @@
+//! Full-text search over the `tracks` table with a small query language
+//!
+//! A query consists of whitespace separated terms, all of which have to match:
+//!
+//! - `foo` matches any word starting with `foo` in any text field
+//! - `"foo bar"` matches the phrase in any text field
+//! - `artist:foo` / `artist:"foo bar"` only match in the given field (`artist`, `title`, `album`, `genre`, `file`)
+//! - `year:>2000` / `duration:<=300` compare numeric fields (`year`, `duration` in seconds) with `=`, `>`, `>=`, `<` or `<=`
+//! - a leading `-` negates a term, like `-genre:live`
+
+use anyhow::{bail, Context, Result};
+use rusqlite::types::Value;
+
+/// A text column of the full-text index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Artist,
+    Title,
+    Album,
+    Genre,
+    File,
+}
+
+impl TextField {
+    fn column(self) -> &'static str {
+        match self {
+            Self::Artist => "artist",
+            Self::Title => "title",
+            Self::Album => "album",
+            Self::Genre => "genre",
+            Self::File => "file",
+        }
+    }
+}
+
+/// A numeric column of the `tracks` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberField {
+    Year,
+    /// Duration in seconds
+    Duration,
+}
+
+impl NumberField {
+    fn column(self) -> &'static str {
+        match self {
+            Self::Year => "year",
+            Self::Duration => "duration",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+impl Comparison {
+    fn operator(self) -> &'static str {
+        match self {
+            Self::Equal => "=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">=",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+        }
+    }
+}
+
+/// A single term of a [`SearchQuery`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTerm {
+    /// Match words starting with `value`, in the given field or in any field if `None`
+    Text {
+        field: Option<TextField>,
+        value: String,
+        negated: bool,
+    },
+    /// Compare a numeric field with `value`
+    Number {
+        field: NumberField,
+        comparison: Comparison,
+        value: i64,
+        negated: bool,
+    },
+}
+
+/// A parsed search query, see the [module documentation](self) for the syntax
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    pub terms: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    /// Parse a query string, errors if a numeric field has a invalid value or a quote is not closed
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut terms = Vec::new();
+        let mut chars = input.chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let negated = chars.next_if_eq(&'-').is_some();
+
+            // read until the next unquoted whitespace, a quoted part may contain whitespace
+            let mut raw = String::new();
+            let mut in_quotes = false;
+            while let Some(c) = chars.next_if(|c| in_quotes || !c.is_whitespace()) {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                } else {
+                    raw.push(c);
+                }
+            }
+            if in_quotes {
+                bail!("Unclosed quote in search query");
+            }
+
+            if let Some(term) = parse_term(&raw, negated)? {
+                terms.push(term);
+            }
+        }
+
+        Ok(Self { terms })
+    }
+
+    /// Returns `true` if there are no terms, which matches all tracks
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Build the `SELECT` statement and its parameters, most relevant results first
+    pub(super) fn to_sql(&self, limit: u32) -> (String, Vec<Value>) {
+        let mut matches = Vec::new();
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        for term in &self.terms {
+            match term {
+                SearchTerm::Text {
+                    field,
+                    value,
+                    negated: false,
+                } => matches.push(fts_expression(*field, value)),
+                SearchTerm::Text {
+                    field,
+                    value,
+                    negated: true,
+                } => {
+                    // FTS5 "NOT" needs a positive left side, so exclude the matches separately
+                    conditions.push(
+                        "tracks.id NOT IN (SELECT rowid FROM tracks_fts WHERE tracks_fts MATCH ?)"
+                            .to_string(),
+                    );
+                    params.push(Value::Text(fts_expression(*field, value)));
+                }
+                SearchTerm::Number {
+                    field,
+                    comparison,
+                    value,
+                    negated,
+                } => {
+                    let condition =
+                        format!("tracks.{} {} ?", field.column(), comparison.operator());
+                    if *negated {
+                        // tracks without a value are neither matched nor excluded
+                        conditions.push(format!(
+                            "tracks.{} IS NOT NULL AND NOT ({condition})",
+                            field.column()
+                        ));
+                    } else {
+                        conditions.push(condition);
+                    }
+                    params.push(Value::Integer(*value));
+                }
+            }
+        }
+
+        let mut query = String::from("SELECT tracks.* FROM tracks");
+        let order = if matches.is_empty() {
+            "tracks.artist, tracks.album, tracks.title"
+        } else {
+            query.push_str(" INNER JOIN tracks_fts ON tracks_fts.rowid = tracks.id");
+            conditions.insert(0, "tracks_fts MATCH ?".to_string());
+            params.insert(0, Value::Text(matches.join(" AND ")));
+            // weight matches in artist and title higher than in the other fields
+            "bm25(tracks_fts, 10.0, 10.0, 5.0, 2.0, 1.0)"
+        };
+
+        if !conditions.is_empty() {
+            query.push_str(" WHERE ");
+            query.push_str(&conditions.join(" AND "));
+        }
+        query.push_str(" ORDER BY ");
+        query.push_str(order);
+        query.push_str(" LIMIT ?");
+        params.push(Value::Integer(limit.into()));
+
+        (query, params)
+    }
+}
+
+/// Parse a single term without the leading `-`, returns `None` if the term is empty
+fn parse_term(raw: &str, negated: bool) -> Result<Option<SearchTerm>> {
+    let (field, value) = match raw.split_once(':') {
+        Some((field, value)) => (Some(field.to_lowercase()), value),
+        None => (None, raw),
+    };
+
+    let text_field = match field.as_deref() {
+        None => None,
+        Some("artist") => Some(TextField::Artist),
+        Some("title") => Some(TextField::Title),
+        Some("album") => Some(TextField::Album),
+        Some("genre") => Some(TextField::Genre),
+        Some("file" | "path") => Some(TextField::File),
+        Some("year") => return parse_number(NumberField::Year, value, negated).map(Some),
+        Some("duration") => return parse_number(NumberField::Duration, value, negated).map(Some),
+        // not a known field, so likely just text containing a ":"
+        Some(_) => return Ok(text_term(None, raw, negated)),
+    };
+
+    Ok(text_term(text_field, value, negated))
+}
+
+fn text_term(field: Option<TextField>, value: &str, negated: bool) -> Option<SearchTerm> {
+    // a empty value (like while typing "artist:") does not restrict anything
+    if value.trim().is_empty() {
+        return None;
+    }
+
+    Some(SearchTerm::Text {
+        field,
+        value: value.to_string(),
+        negated,
+    })
+}
+
+fn parse_number(field: NumberField, value: &str, negated: bool) -> Result<SearchTerm> {
+    let (comparison, number) = if let Some(v) = value.strip_prefix(">=") {
+        (Comparison::GreaterEqual, v)
+    } else if let Some(v) = value.strip_prefix("<=") {
+        (Comparison::LessEqual, v)
+    } else if let Some(v) = value.strip_prefix('>') {
+        (Comparison::Greater, v)
+    } else if let Some(v) = value.strip_prefix('<') {
+        (Comparison::Less, v)
+    } else {
+        (Comparison::Equal, value.strip_prefix('=').unwrap_or(value))
+    };
+
+    let value = number.trim().parse().with_context(|| {
+        format!(
+            "Expected a number for \"{}\", got \"{number}\"",
+            field.column()
+        )
+    })?;
+
+    Ok(SearchTerm::Number {
+        field,
+        comparison,
+        value,
+        negated,
+    })
+}
+
+/// Build a FTS5 expression matching `value` as a (prefix) phrase in the given column or any column
+fn fts_expression(field: Option<TextField>, value: &str) -> String {
+    // quote the value so that FTS5 syntax in it has no meaning, quotes are escaped by doubling
+    let phrase = format!("\"{}\"*", value.replace('"', "\"\""));
+
+    match field {
+        Some(field) => format!("{} : {phrase}", field.column()),
+        None => phrase,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_parse_terms() {
+        let query =
+            SearchQuery::parse(r#"foo artist:bar album:"bar baz" year:>2000 -genre:live"#).unwrap();
+
+        assert_eq!(
+            query.terms,
+            vec![
+                SearchTerm::Text {
+                    field: None,
+                    value: "foo".to_string(),
+                    negated: false
+                },
+                SearchTerm::Text {
+                    field: Some(TextField::Artist),
+                    value: "bar".to_string(),
+                    negated: false
+                },
+                SearchTerm::Text {
+                    field: Some(TextField::Album),
+                    value: "bar baz".to_string(),
+                    negated: false
+                },
+                SearchTerm::Number {
+                    field: NumberField::Year,
+                    comparison: Comparison::Greater,
+                    value: 2000,
+                    negated: false
+                },
+                SearchTerm::Text {
+                    field: Some(TextField::Genre),
+                    value: "live".to_string(),
+                    negated: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_handle_incomplete_input() {
+        assert!(SearchQuery::parse("").unwrap().is_empty());
+        assert!(SearchQuery::parse("  artist:  ").unwrap().is_empty());
+        assert!(SearchQuery::parse("album:\"bar").is_err());
+        assert!(SearchQuery::parse("year:>abc").is_err());
+
+        // unknown fields are just text
+        assert_eq!(
+            SearchQuery::parse("12:30").unwrap().terms,
+            vec![SearchTerm::Text {
+                field: None,
+                value: "12:30".to_string(),
+                negated: false
+            }]
+        );
+    }
+
+    #[test]
+    fn should_escape_fts_syntax() {
+        assert_eq!(
+            fts_expression(Some(TextField::Title), "a \"b\" OR c"),
+            "title : \"a \"\"b\"\" OR c\"*"
+        );
+    }
+}
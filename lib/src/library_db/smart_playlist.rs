@@ -0,0 +1,92 @@
+use chrono::NaiveDate;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+/// A saved, dynamic playlist defined by rules over the tracks in the database
+///
+/// All set rules have to match for a track to be included.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct SmartPlaylist {
+    /// Name to display in the database view
+    pub name: String,
+    /// Only tracks where the artist contains this, case-insensitive
+    pub artist: Option<String>,
+    /// Only tracks where the album contains this, case-insensitive
+    pub album: Option<String>,
+    /// Only tracks where the genre contains this, case-insensitive
+    pub genre: Option<String>,
+    /// Only tracks that are at least this long
+    pub min_duration_secs: Option<u64>,
+    /// Only tracks that are at most this long
+    pub max_duration_secs: Option<u64>,
+    /// Only tracks added to the database on or after this date, in format `YYYY-MM-DD`
+    pub added_after: Option<String>,
+    /// Only tracks that were played to the end at least this many times
+    pub min_play_count: Option<u64>,
+    /// Only tracks that were played to the end at most this many times, `0` for never played tracks
+    pub max_play_count: Option<u64>,
+}
+
+impl SmartPlaylist {
+    /// Build the `SELECT` statement and its parameters for the rules of this playlist
+    pub(super) fn to_query(&self) -> Result<(String, Vec<Value>), rusqlite::Error> {
+        let mut query = String::from(
+            "SELECT tracks.* FROM tracks
+            LEFT JOIN (SELECT file, COUNT(*) AS play_count FROM play_history WHERE skipped = 0 GROUP BY file) AS history
+            ON history.file = tracks.file
+            WHERE 1",
+        );
+        let mut params = Vec::new();
+
+        let mut push = |condition: &str, value: Value| {
+            query.push_str(" AND ");
+            query.push_str(condition);
+            params.push(value);
+        };
+
+        for (column, value) in [
+            ("artist", &self.artist),
+            ("album", &self.album),
+            ("genre", &self.genre),
+        ] {
+            if let Some(value) = value {
+                push(
+                    &format!("instr(lower(tracks.{column}), lower(?)) > 0"),
+                    Value::Text(value.clone()),
+                );
+            }
+        }
+
+        if let Some(secs) = self.min_duration_secs {
+            push("tracks.duration >= ?", to_integer(secs));
+        }
+        if let Some(secs) = self.max_duration_secs {
+            push("tracks.duration <= ?", to_integer(secs));
+        }
+        if let Some(ref date) = self.added_after {
+            let timestamp = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default()
+                .and_utc()
+                .timestamp();
+            push("tracks.added_at >= ?", Value::Integer(timestamp));
+        }
+        if let Some(count) = self.min_play_count {
+            push("COALESCE(history.play_count, 0) >= ?", to_integer(count));
+        }
+        if let Some(count) = self.max_play_count {
+            push("COALESCE(history.play_count, 0) <= ?", to_integer(count));
+        }
+
+        query.push_str(" ORDER BY tracks.file");
+
+        Ok((query, params))
+    }
+}
+
+/// Convert to a sqlite integer, saturating at [`i64::MAX`]
+fn to_integer(value: u64) -> Value {
+    Value::Integer(i64::try_from(value).unwrap_or(i64::MAX))
+}
@@ -1,8 +1,11 @@
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use rusqlite::{named_params, Connection, Row};
 
-use crate::{const_str, track::Track};
+use crate::{
+    const_str,
+    track::{ReplayGain, Track},
+};
 
 /// A struct representing a [`Track`](Track) in the database
 #[derive(Clone, Debug)]
@@ -19,6 +22,17 @@ pub struct TrackDB {
     pub directory: String,
     pub last_modified: String,
     pub last_position: Duration,
+    pub replaygain_track_gain: Option<f32>,
+    pub replaygain_track_peak: Option<f32>,
+    pub replaygain_album_gain: Option<f32>,
+    pub replaygain_album_peak: Option<f32>,
+    /// Unix timestamp in seconds of when the track was added to the database
+    pub added_at: Option<u64>,
+    pub year: Option<u32>,
+    pub album_artist: Option<String>,
+    pub composer: Option<String>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
 }
 
 impl TrackDB {
@@ -41,12 +55,22 @@ impl TrackDB {
             directory: row.get(9)?,
             last_modified: row.get(10)?,
             last_position: Duration::from_secs(last_position_u64),
+            replaygain_track_gain: row.get(12)?,
+            replaygain_track_peak: row.get(13)?,
+            replaygain_album_gain: row.get(14)?,
+            replaygain_album_peak: row.get(15)?,
+            added_at: row.get(16)?,
+            year: row.get(17)?,
+            album_artist: row.get(18)?,
+            composer: row.get(19)?,
+            track_number: row.get(20)?,
+            disc_number: row.get(21)?,
         })
     }
 
     /// Try to convert a given row to a [`TrackDB`] instance, using column names to resolve the values
     pub fn try_from_row_named(row: &Row<'_>) -> Result<Self, rusqlite::Error> {
-        // NOTE: all the names in "get" below are the *column names* as defined in migrations/002.sql#table_tracks (pseudo link)
+        // NOTE: all the names in "get" below are the *column names* as defined in migrations/002.sql#table_tracks (pseudo link) and added in later migrations
         let d_u64: u64 = row.get("duration")?;
         let last_position_u64: u64 = row.get("last_position")?;
         Ok(TrackDB {
@@ -62,8 +86,28 @@ impl TrackDB {
             directory: row.get("directory")?,
             last_modified: row.get("last_modified")?,
             last_position: Duration::from_secs(last_position_u64),
+            replaygain_track_gain: row.get("replaygain_track_gain")?,
+            replaygain_track_peak: row.get("replaygain_track_peak")?,
+            replaygain_album_gain: row.get("replaygain_album_gain")?,
+            replaygain_album_peak: row.get("replaygain_album_peak")?,
+            added_at: row.get("added_at")?,
+            year: row.get("year")?,
+            album_artist: row.get("album_artist")?,
+            composer: row.get("composer")?,
+            track_number: row.get("track_number")?,
+            disc_number: row.get("disc_number")?,
         })
     }
+
+    /// Key to sort the tracks of a album in album order, by disc and then track number
+    ///
+    /// Tracks without a disc number count as the first disc, tracks without a track number come last
+    pub fn album_order_key(&self) -> (u32, u32) {
+        (
+            self.disc_number.unwrap_or(1),
+            self.track_number.unwrap_or(u32::MAX),
+        )
+    }
 }
 
 /// A struct representing a [`Track`](Track) in the database to be inserted
@@ -84,6 +128,13 @@ pub struct TrackDBInsertable<'a> {
     pub directory: &'a str,
     pub last_modified: String,
     pub last_position: Duration,
+    pub replaygain: ReplayGain,
+    pub added_at: u64,
+    pub year: Option<u32>,
+    pub album_artist: Option<&'a str>,
+    pub composer: Option<&'a str>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
 }
 
 const_str! {
@@ -113,6 +164,16 @@ impl<'a> From<&'a Track> for TrackDBInsertable<'a> {
                 .as_secs()
                 .to_string(),
             last_position: Duration::default(),
+            replaygain: *value.replaygain(),
+            added_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            year: value.year(),
+            album_artist: value.album_artist(),
+            composer: value.composer(),
+            track_number: value.track_number(),
+            disc_number: value.disc_number(),
         }
     }
 }
@@ -122,8 +183,8 @@ impl TrackDBInsertable<'_> {
     #[inline]
     pub fn insert_track(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
         con.execute(
-            "INSERT INTO tracks (artist, title, album, genre, file, duration, name, ext, directory, last_modified, last_position) 
-            values (:artist, :title, :album, :genre, :file, :duration, :name, :ext, :directory, :last_modified, :last_position)",
+            "INSERT INTO tracks (artist, title, album, genre, file, duration, name, ext, directory, last_modified, last_position, replaygain_track_gain, replaygain_track_peak, replaygain_album_gain, replaygain_album_peak, added_at, year, album_artist, composer, track_number, disc_number) 
+            values (:artist, :title, :album, :genre, :file, :duration, :name, :ext, :directory, :last_modified, :last_position, :replaygain_track_gain, :replaygain_track_peak, :replaygain_album_gain, :replaygain_album_peak, :added_at, :year, :album_artist, :composer, :track_number, :disc_number)",
             named_params![
                 ":artist": &self.artist,
                 ":title": &self.title,
@@ -136,7 +197,54 @@ impl TrackDBInsertable<'_> {
                 ":directory": &self.directory,
                 ":last_modified": &self.last_modified,
                 ":last_position": &self.last_position.as_secs().to_string(),
+                ":replaygain_track_gain": &self.replaygain.track_gain,
+                ":replaygain_track_peak": &self.replaygain.track_peak,
+                ":replaygain_album_gain": &self.replaygain.album_gain,
+                ":replaygain_album_peak": &self.replaygain.album_peak,
+                ":added_at": &self.added_at,
+                ":year": &self.year,
+                ":album_artist": &self.album_artist,
+                ":composer": &self.composer,
+                ":track_number": &self.track_number,
+                ":disc_number": &self.disc_number,
             ],
         )
     }
+
+    /// Update the metadata of the row with the same `file`, or insert it if there is none
+    ///
+    /// Unlike [`Self::insert_track`], this keeps `last_position` and `added_at` of existing rows.
+    pub fn upsert_track(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
+        let updated = con.execute(
+            "UPDATE tracks SET artist = :artist, title = :title, album = :album, genre = :genre, duration = :duration, name = :name, ext = :ext, directory = :directory, last_modified = :last_modified, replaygain_track_gain = :replaygain_track_gain, replaygain_track_peak = :replaygain_track_peak, replaygain_album_gain = :replaygain_album_gain, replaygain_album_peak = :replaygain_album_peak, year = :year, album_artist = :album_artist, composer = :composer, track_number = :track_number, disc_number = :disc_number
+            WHERE file = :file",
+            named_params![
+                ":artist": &self.artist,
+                ":title": &self.title,
+                ":album": &self.album,
+                ":genre": &self.genre,
+                ":file": &self.file,
+                ":duration": &self.duration.as_secs(),
+                ":name": &self.name,
+                ":ext": &self.ext,
+                ":directory": &self.directory,
+                ":last_modified": &self.last_modified,
+                ":replaygain_track_gain": &self.replaygain.track_gain,
+                ":replaygain_track_peak": &self.replaygain.track_peak,
+                ":replaygain_album_gain": &self.replaygain.album_gain,
+                ":replaygain_album_peak": &self.replaygain.album_peak,
+                ":year": &self.year,
+                ":album_artist": &self.album_artist,
+                ":composer": &self.composer,
+                ":track_number": &self.track_number,
+                ":disc_number": &self.disc_number,
+            ],
+        )?;
+
+        if updated > 0 {
+            return Ok(updated);
+        }
+
+        self.insert_track(con)
+    }
 }
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use ahash::AHashMap;
+use rusqlite::{named_params, params, Connection};
+
+use super::PodcastDBId;
+use crate::chapter::Chapter;
+
+/// Get the chapters of all episodes of a podcast, by episode id
+pub fn get_chapters(
+    podcast_id: PodcastDBId,
+    con: &Connection,
+) -> Result<AHashMap<PodcastDBId, Vec<Chapter>>, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(
+        "SELECT chapters.episode_id, chapters.start, chapters.title FROM chapters
+            INNER JOIN episodes ON episodes.id = chapters.episode_id
+            WHERE episodes.podcast_id = ?
+            ORDER BY chapters.start;",
+    )?;
+
+    let mut chapters: AHashMap<PodcastDBId, Vec<Chapter>> = AHashMap::new();
+    let rows = stmt.query_map(params![podcast_id], |row| {
+        let start: u64 = row.get("start")?;
+        Ok((
+            row.get::<_, PodcastDBId>("episode_id")?,
+            Chapter {
+                start: Duration::from_millis(start),
+                title: row.get("title")?,
+            },
+        ))
+    })?;
+    for (episode_id, chapter) in rows.flatten() {
+        chapters.entry(episode_id).or_default().push(chapter);
+    }
+
+    Ok(chapters)
+}
+
+/// Replace all chapters of a episode with the given ones
+pub fn replace_chapters(
+    episode_id: PodcastDBId,
+    chapters: &[Chapter],
+    con: &Connection,
+) -> Result<(), rusqlite::Error> {
+    con.prepare_cached("DELETE FROM chapters WHERE episode_id = ?;")?
+        .execute(params![episode_id])?;
+
+    let mut stmt = con.prepare_cached(
+        "INSERT INTO chapters (episode_id, start, title)
+            VALUES (:epid, :start, :title);",
+    )?;
+    for chapter in chapters {
+        stmt.execute(named_params![
+            ":epid": episode_id,
+            ":start": u64::try_from(chapter.start.as_millis()).unwrap_or(u64::MAX),
+            ":title": chapter.title,
+        ])?;
+    }
+
+    Ok(())
+}
@@ -22,6 +22,8 @@ pub struct EpisodeDB {
     pub hidden: bool,
     pub last_position: Option<i64>,
     pub image_url: Option<String>,
+    pub chapters_url: Option<String>,
+    pub transcript_url: Option<String>,
 }
 
 impl EpisodeDB {
@@ -41,6 +43,8 @@ impl EpisodeDB {
             hidden: row.get("hidden")?,
             last_position: row.get("last_position")?,
             image_url: row.get("image_url")?,
+            chapters_url: row.get("chapters_url")?,
+            transcript_url: row.get("transcript_url")?,
         })
     }
 
@@ -60,6 +64,8 @@ impl EpisodeDB {
             hidden: row.get("hidden")?,
             last_position: row.get("last_position")?,
             image_url: row.get("image_url")?,
+            chapters_url: row.get("chapters_url")?,
+            transcript_url: row.get("transcript_url")?,
         })
     }
 }
@@ -82,6 +88,8 @@ pub struct EpisodeDBInsertable<'a> {
     pub hidden: bool,
     pub last_position: Option<i64>,
     pub image_url: Option<&'a str>,
+    pub chapters_url: Option<&'a str>,
+    pub transcript_url: Option<&'a str>,
 }
 
 impl<'a> EpisodeDBInsertable<'a> {
@@ -99,6 +107,8 @@ impl<'a> EpisodeDBInsertable<'a> {
             hidden: false,
             last_position: Some(0),
             image_url: value.image_url.as_deref(),
+            chapters_url: value.chapters_url.as_deref(),
+            transcript_url: value.transcript_url.as_deref(),
         }
     }
 
@@ -107,8 +117,8 @@ impl<'a> EpisodeDBInsertable<'a> {
     pub fn insert_episode(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
         let mut stmt = con.prepare_cached(
             "INSERT INTO episodes (podcast_id, title, url, guid,
-                description, pubdate, duration, played, hidden, last_position, image_url)
-                VALUES (:podid, :title, :url, :guid, :description, :pubdate, :duration, :played, :hidden, :last_position, :image_url);",
+                description, pubdate, duration, played, hidden, last_position, image_url, chapters_url, transcript_url)
+                VALUES (:podid, :title, :url, :guid, :description, :pubdate, :duration, :played, :hidden, :last_position, :image_url, :chapters_url, :transcript_url);",
         )?;
         stmt.execute(named_params![
             ":podid": self.pod_id,
@@ -122,6 +132,8 @@ impl<'a> EpisodeDBInsertable<'a> {
             ":hidden": self.hidden,
             ":last_position": self.last_position,
             ":image_url": self.image_url,
+            ":chapters_url": self.chapters_url,
+            ":transcript_url": self.transcript_url,
         ])
     }
 
@@ -141,7 +153,8 @@ impl<'a> EpisodeDBInsertable<'a> {
         let mut stmt = con.prepare_cached(
             "UPDATE episodes SET title = :title, url = :url,
                     guid = :guid, description = :description, pubdate = :pubdate,
-                    duration = :duration, image_url = :image_url WHERE id = :epid;",
+                    duration = :duration, image_url = :image_url, chapters_url = :chapters_url,
+                    transcript_url = :transcript_url WHERE id = :epid;",
         )?;
         stmt.execute(named_params![
             ":title": self.title,
@@ -151,6 +164,8 @@ impl<'a> EpisodeDBInsertable<'a> {
             ":pubdate": self.pubdate.map(|v| v.timestamp()),
             ":duration": self.duration,
             ":image_url": self.duration,
+            ":chapters_url": self.chapters_url,
+            ":transcript_url": self.transcript_url,
             ":epid": id,
         ])
     }
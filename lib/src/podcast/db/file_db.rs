@@ -1,8 +1,9 @@
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use rusqlite::{named_params, params, Connection, Row};
 
-use super::PodcastDBId;
+use super::{convert_date, PodcastDBId};
 
 /// A struct representing a episode file (downloaded) in the database
 #[derive(Debug, Clone)]
@@ -10,6 +11,7 @@ pub struct FileDB {
     pub id: PodcastDBId,
     pub episode_id: PodcastDBId,
     pub path: PathBuf,
+    pub downloaded: Option<DateTime<Utc>>,
 }
 
 impl FileDB {
@@ -21,6 +23,7 @@ impl FileDB {
             id: row.get("id")?,
             episode_id: row.get("episode_id")?,
             path,
+            downloaded: convert_date(&row.get("downloaded")),
         })
     }
 
@@ -32,6 +35,7 @@ impl FileDB {
             id: row.get("fileid")?,
             episode_id: row.get("episode_id")?,
             path,
+            downloaded: convert_date(&row.get("downloaded")),
         })
     }
 }
@@ -45,23 +49,29 @@ pub struct FileDBInsertable<'a> {
     // pub id: PodcastDBId,
     pub episode_id: PodcastDBId,
     pub path: &'a Path,
+    pub downloaded: DateTime<Utc>,
 }
 
 impl<'a> FileDBInsertable<'a> {
     pub fn new(episode_id: PodcastDBId, path: &'a Path) -> Self {
-        Self { episode_id, path }
+        Self {
+            episode_id,
+            path,
+            downloaded: Utc::now(),
+        }
     }
 
     /// Insert the current [`FileDBInsertable`] into the `files` table
     #[inline]
     pub fn insert_file(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
         let mut stmt = con.prepare_cached(
-            "INSERT INTO files (episode_id, path)
-                VALUES (:epid, :path);",
+            "INSERT INTO files (episode_id, path, downloaded)
+                VALUES (:epid, :path, :downloaded);",
         )?;
         stmt.execute(named_params![
             ":epid": self.episode_id,
-            ":path": self.path.to_string_lossy()
+            ":path": self.path.to_string_lossy(),
+            ":downloaded": self.downloaded.timestamp(),
         ])
     }
 }
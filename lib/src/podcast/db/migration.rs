@@ -3,7 +3,7 @@ use rusqlite::{params, Connection};
 use semver::Version;
 
 /// The Current Database schema version this application is meant to run against
-pub(super) const DB_VERSION: u32 = 1;
+pub(super) const DB_VERSION: u32 = 5;
 /// The Lowest Database schema version this application supports migration up against
 ///
 /// Expection being "0" as that indicates a fresh database
@@ -68,6 +68,30 @@ fn apply_migrations(conn: &Connection, mut user_version: u32) -> Result<()> {
         user_version = set_user_version(conn, 1)?;
     }
 
+    if user_version == 1 {
+        conn.execute_batch(include_str!("./migrations/002.sql"))
+            .context("PodcastDatabase version 2 could not be applied")?;
+        user_version = set_user_version(conn, 2)?;
+    }
+
+    if user_version == 2 {
+        conn.execute_batch(include_str!("./migrations/003.sql"))
+            .context("PodcastDatabase version 3 could not be applied")?;
+        user_version = set_user_version(conn, 3)?;
+    }
+
+    if user_version == 3 {
+        conn.execute_batch(include_str!("./migrations/004.sql"))
+            .context("PodcastDatabase version 4 could not be applied")?;
+        user_version = set_user_version(conn, 4)?;
+    }
+
+    if user_version == 4 {
+        conn.execute_batch(include_str!("./migrations/005.sql"))
+            .context("PodcastDatabase version 5 could not be applied")?;
+        user_version = set_user_version(conn, 5)?;
+    }
+
     Ok(())
 }
 
@@ -135,7 +159,7 @@ mod tests {
 
         assert_eq!(0, get_user_version(&conn).unwrap());
         migrate(&conn).unwrap();
-        assert_eq!(1, get_user_version(&conn).unwrap());
+        assert_eq!(DB_VERSION, get_user_version(&conn).unwrap());
 
         let all_tracks: Vec<String> = {
             let mut prep = conn.prepare("SELECT name FROM sqlite_schema WHERE type ='table' AND name NOT LIKE 'sqlite_%';").unwrap();
@@ -145,6 +169,61 @@ mod tests {
                 .collect()
         };
 
-        assert_eq!(&all_tracks, &["podcasts", "episodes", "files", "version"]);
+        assert_eq!(
+            &all_tracks,
+            &["podcasts", "episodes", "files", "version", "chapters"]
+        );
+    }
+
+    #[test]
+    fn should_migrate_from_v1() {
+        let conn = gen_database();
+
+        conn.execute_batch(include_str!("./migrations/001.sql"))
+            .unwrap();
+        set_user_version(&conn, 1).unwrap();
+        conn.execute(
+            "INSERT INTO podcasts (id, title, url, last_checked) VALUES (1, 'pod', 'http://pod', 0);",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO episodes (id, podcast_id, title, url) VALUES (1, 1, 'ep', 'http://ep');",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO files (episode_id, path) VALUES (1, '/tmp/ep.mp3');",
+            [],
+        )
+        .unwrap();
+
+        migrate(&conn).unwrap();
+        assert_eq!(DB_VERSION, get_user_version(&conn).unwrap());
+
+        let auto_download: u32 = conn
+            .query_row(
+                "SELECT auto_download FROM podcasts WHERE id = 1;",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(auto_download, 0);
+        let downloaded: Option<i64> = conn
+            .query_row(
+                "SELECT downloaded FROM files WHERE episode_id = 1;",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert!(downloaded.is_some());
+        let (speed, skip_intro): (Option<i32>, u64) = conn
+            .query_row(
+                "SELECT speed, skip_intro FROM podcasts WHERE id = 1;",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((speed, skip_intro), (None, 0));
     }
 }
@@ -0,0 +1,9 @@
+-- per-feed policy for the background updater in the server, see "PodcastPolicy"
+ALTER TABLE podcasts ADD COLUMN refresh_interval INTEGER;
+ALTER TABLE podcasts ADD COLUMN auto_download INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE podcasts ADD COLUMN keep_days INTEGER;
+ALTER TABLE podcasts ADD COLUMN keep_max INTEGER;
+
+-- when a file was downloaded, existing downloads count as downloaded at the time of the migration
+ALTER TABLE files ADD COLUMN downloaded INTEGER;
+UPDATE files SET downloaded = strftime('%s', 'now');
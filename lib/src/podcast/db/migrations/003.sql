@@ -0,0 +1,11 @@
+-- Podcasting 2.0 chapters of the episodes
+ALTER TABLE episodes ADD COLUMN chapters_url TEXT;
+
+-- the chapters fetched from "episodes.chapters_url", "start" is in milliseconds
+CREATE TABLE IF NOT EXISTS chapters (
+    id INTEGER PRIMARY KEY NOT NULL,
+    episode_id INTEGER NOT NULL,
+    start INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    FOREIGN KEY (episode_id) REFERENCES episodes(id) ON DELETE CASCADE
+);
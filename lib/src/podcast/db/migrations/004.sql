@@ -0,0 +1,2 @@
+-- Podcasting 2.0 transcript of the episodes, the transcript itself is cached next to the downloaded file
+ALTER TABLE episodes ADD COLUMN transcript_url TEXT;
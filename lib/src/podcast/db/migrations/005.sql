@@ -0,0 +1,5 @@
+-- per-feed playback settings applied by the server, see "PodcastPlaybackSettings"
+-- "speed" is in tenths like the global speed, NULL to use the global speed; "skip_intro" and "skip_outro" are in seconds
+ALTER TABLE podcasts ADD COLUMN speed INTEGER;
+ALTER TABLE podcasts ADD COLUMN skip_intro INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE podcasts ADD COLUMN skip_outro INTEGER NOT NULL DEFAULT 0;
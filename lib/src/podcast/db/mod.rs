@@ -11,10 +11,12 @@ use regex::Regex;
 use rusqlite::{params, Connection};
 use semver::Version;
 
-use super::{Episode, EpisodeNoId, Podcast, PodcastNoId};
+use super::{Episode, EpisodeNoId, Podcast, PodcastNoId, PodcastPlaybackSettings, PodcastPolicy};
+use crate::chapter::Chapter;
 use crate::track::Track;
 use podcast_db::{PodcastDB, PodcastDBInsertable};
 
+mod chapter_db;
 mod episode_db;
 mod file_db;
 mod migration;
@@ -55,7 +57,7 @@ impl Database {
         let mut db_path = path.to_path_buf();
         std::fs::create_dir_all(&db_path).context("Unable to create subdirectory for database.")?;
         db_path.push("data.db");
-        let conn = Connection::open(&db_path)?;
+        let conn = Self::open_connection(&db_path)?;
 
         migration::migrate(&conn).context("Database creation / migration")?;
 
@@ -69,10 +71,25 @@ impl Database {
         })
     }
 
+    /// Opens a connection to the database at `path`.
+    ///
+    /// The server and the tui both write to the database, so this waits for the other one
+    /// instead of failing while the database is locked.
+    fn open_connection(path: &Path) -> Result<Connection> {
+        let conn = Connection::open(path).context("Error connecting to database.")?;
+        conn.busy_timeout(Duration::from_secs(5))
+            .context("Could not set database busy timeout.")?;
+        // let readers not block the writer and the other way around
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+            .context("Could not set database journal mode.")?;
+
+        Ok(conn)
+    }
+
     /// Inserts a new podcast and list of podcast episodes into the
     /// database.
     pub fn insert_podcast(&self, podcast: &PodcastNoId) -> Result<u64> {
-        let mut conn = Connection::open(&self.path).context("Error connecting to database.")?;
+        let mut conn = Self::open_connection(&self.path)?;
         let tx = conn.transaction()?;
 
         PodcastDBInsertable::from(podcast).insert_podcast(&tx)?;
@@ -131,6 +148,35 @@ impl Database {
         Ok(())
     }
 
+    /// Sets the rules for automatically refreshing and downloading a podcast.
+    pub fn set_policy(&self, podcast_id: PodcastDBId, policy: &PodcastPolicy) -> Result<()> {
+        podcast_db::update_policy(podcast_id, policy, &self.conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the speed and intro / outro skipping used when playing episodes of a podcast.
+    pub fn set_playback_settings(
+        &self,
+        podcast_id: PodcastDBId,
+        settings: &PodcastPlaybackSettings,
+    ) -> Result<()> {
+        podcast_db::update_playback_settings(podcast_id, settings, &self.conn)?;
+
+        Ok(())
+    }
+
+    /// Gets the playback settings of the podcast the episode with the given url belongs to,
+    /// `None` if the url is not a episode in the database.
+    pub fn get_playback_settings_for_episode(
+        &self,
+        episode_url: &str,
+    ) -> Result<Option<PodcastPlaybackSettings>> {
+        let settings = podcast_db::get_playback_settings_for_episode(episode_url, &self.conn)?;
+
+        Ok(settings)
+    }
+
     /// Updates an existing podcast in the database, where metadata is
     /// changed if necessary, and episodes are updated (modified episodes
     /// are updated, new episodes are inserted).
@@ -163,7 +209,7 @@ impl Database {
             }
         }
 
-        let mut conn = Connection::open(&self.path).context("Error connecting to database.")?;
+        let mut conn = Self::open_connection(&self.path)?;
         let tx = conn.transaction()?;
 
         let mut inserted = 0;
@@ -242,6 +288,8 @@ impl Database {
             && new_ep.guid == old_ep.guid
             && new_ep.description == old_ep.description
             && new_ep.duration == old_ep.duration
+            && new_ep.chapters_url == old_ep.chapters_url
+            && new_ep.transcript_url == old_ep.transcript_url
             && pd_match)
         {
             return true;
@@ -264,7 +312,7 @@ impl Database {
         episode_id_vec: &[PodcastDBId],
         played: bool,
     ) -> Result<()> {
-        let mut conn = Connection::open(&self.path).context("Error connecting to database.")?;
+        let mut conn = Self::open_connection(&self.path)?;
         let tx = conn.transaction()?;
 
         for episode_id in episode_id_vec {
@@ -313,6 +361,8 @@ impl Database {
                     last_checked: podcast.last_checked,
                     episodes,
                     image_url: podcast.image_url,
+                    policy: podcast.policy,
+                    playback: podcast.playback,
                 })
             })
             .collect::<Result<_, rusqlite::Error>>()?;
@@ -339,10 +389,14 @@ impl Database {
             )?
         };
 
+        let mut chapters = chapter_db::get_chapters(pod_id, &self.conn)?;
+
         let episodes = stmt
             .query_map(params![pod_id], |row| {
                 let episode = EpisodeDB::try_from_row_named_alias_id(row)?;
-                let file = FileDB::try_from_row_named_alias_id(row).ok();
+                let (path, downloaded) = FileDB::try_from_row_named_alias_id(row)
+                    .ok()
+                    .map_or((None, None), |v| (Some(v.path), v.downloaded));
 
                 Ok(Episode {
                     id: episode.id,
@@ -353,10 +407,14 @@ impl Database {
                     description: episode.description,
                     pubdate: episode.pubdate,
                     duration: episode.duration,
-                    path: file.map(|v| v.path),
+                    path,
+                    downloaded,
                     played: episode.played,
                     last_position: episode.last_position,
                     image_url: episode.image_url,
+                    chapters_url: episode.chapters_url,
+                    chapters: chapters.remove(&episode.id).unwrap_or_default(),
+                    transcript_url: episode.transcript_url,
                 })
             })?
             .flatten()
@@ -365,8 +423,26 @@ impl Database {
         Ok(episodes)
     }
 
+    /// Stores the chapters fetched for the episodes with the given url.
+    pub fn set_chapters(&self, episode_url: &str, chapters: &[Chapter]) -> Result<()> {
+        let mut conn = Self::open_connection(&self.path)?;
+        let tx = conn.transaction()?;
+
+        let episode_ids: Vec<PodcastDBId> = tx
+            .prepare_cached("SELECT id FROM episodes WHERE url = ?;")?
+            .query_map(params![episode_url], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for episode_id in episode_ids {
+            chapter_db::replace_chapters(episode_id, chapters, &tx)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
     /// Deletes all rows in all tables
     pub fn clear_db(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM chapters;", [])?;
         self.conn.execute("DELETE FROM files;", [])?;
         self.conn.execute("DELETE FROM episodes;", [])?;
         self.conn.execute("DELETE FROM podcasts;", [])?;
@@ -1,8 +1,10 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
-use rusqlite::{named_params, params, Connection, Row};
+use rusqlite::{named_params, params, Connection, OptionalExtension, Row};
 
 use super::{convert_date, PodcastDBId};
-use crate::podcast::PodcastNoId;
+use crate::podcast::{PodcastNoId, PodcastPlaybackSettings, PodcastPolicy};
 
 /// A struct representing a podcast feed in the database
 #[derive(Debug, Clone)]
@@ -15,6 +17,8 @@ pub struct PodcastDB {
     pub explicit: Option<bool>,
     pub last_checked: DateTime<Utc>,
     pub image_url: Option<String>,
+    pub policy: PodcastPolicy,
+    pub playback: PodcastPlaybackSettings,
 }
 
 impl PodcastDB {
@@ -32,6 +36,19 @@ impl PodcastDB {
             explicit: row.get("explicit")?,
             last_checked,
             image_url: row.get("image_url")?,
+            policy: PodcastPolicy {
+                refresh_interval: row
+                    .get::<_, Option<u64>>("refresh_interval")?
+                    .map(Duration::from_secs),
+                auto_download: row.get("auto_download")?,
+                keep_days: row.get("keep_days")?,
+                keep_max: row.get("keep_max")?,
+            },
+            playback: PodcastPlaybackSettings {
+                speed: row.get("speed")?,
+                skip_intro: Duration::from_secs(row.get("skip_intro")?),
+                skip_outro: Duration::from_secs(row.get("skip_outro")?),
+            },
         })
     }
 }
@@ -109,6 +126,65 @@ impl PodcastDBInsertable<'_> {
     }
 }
 
+/// Update the [`PodcastPolicy`] of a given podcast id in the `podcasts` table
+pub fn update_policy(
+    id: PodcastDBId,
+    policy: &PodcastPolicy,
+    con: &Connection,
+) -> Result<usize, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(
+        "UPDATE podcasts SET refresh_interval = :refresh_interval, auto_download = :auto_download,
+        keep_days = :keep_days, keep_max = :keep_max
+        WHERE id = :id;",
+    )?;
+    stmt.execute(named_params![
+        ":refresh_interval": policy.refresh_interval.map(|v| v.as_secs()),
+        ":auto_download": policy.auto_download,
+        ":keep_days": policy.keep_days,
+        ":keep_max": policy.keep_max,
+        ":id": id,
+    ])
+}
+
+/// Update the [`PodcastPlaybackSettings`] of a given podcast id in the `podcasts` table
+pub fn update_playback_settings(
+    id: PodcastDBId,
+    settings: &PodcastPlaybackSettings,
+    con: &Connection,
+) -> Result<usize, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(
+        "UPDATE podcasts SET speed = :speed, skip_intro = :skip_intro, skip_outro = :skip_outro
+        WHERE id = :id;",
+    )?;
+    stmt.execute(named_params![
+        ":speed": settings.speed,
+        ":skip_intro": settings.skip_intro.as_secs(),
+        ":skip_outro": settings.skip_outro.as_secs(),
+        ":id": id,
+    ])
+}
+
+/// Get the [`PodcastPlaybackSettings`] of the podcast the episode with the given url belongs to
+pub fn get_playback_settings_for_episode(
+    episode_url: &str,
+    con: &Connection,
+) -> Result<Option<PodcastPlaybackSettings>, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(
+        "SELECT podcasts.speed, podcasts.skip_intro, podcasts.skip_outro FROM podcasts
+            INNER JOIN episodes ON episodes.podcast_id = podcasts.id
+            WHERE episodes.url = ?
+            LIMIT 1;",
+    )?;
+    stmt.query_row(params![episode_url], |row| {
+        Ok(PodcastPlaybackSettings {
+            speed: row.get("speed")?,
+            skip_intro: Duration::from_secs(row.get("skip_intro")?),
+            skip_outro: Duration::from_secs(row.get("skip_outro")?),
+        })
+    })
+    .optional()
+}
+
 /// Delete a podcast by id
 ///
 /// This also deletes all associated episodes and files (not removing the actual files)!
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
 
+use crate::chapter::Chapter;
 use crate::utils::StringUtils;
 
 use super::{Menuable, EPISODE_DURATION_LENGTH, EPISODE_PUBDATE_LENGTH};
@@ -21,9 +22,17 @@ pub struct Episode {
     pub pubdate: Option<DateTime<Utc>>,
     pub duration: Option<i64>,
     pub path: Option<PathBuf>,
+    /// When the file at `path` was downloaded
+    pub downloaded: Option<DateTime<Utc>>,
     pub played: bool,
     pub last_position: Option<i64>,
     pub image_url: Option<String>,
+    /// Url of the Podcasting 2.0 chapters JSON
+    pub chapters_url: Option<String>,
+    /// The chapters fetched from `chapters_url`, empty if not fetched yet
+    pub chapters: Vec<Chapter>,
+    /// Url of the Podcasting 2.0 transcript, in one of the supported formats
+    pub transcript_url: Option<String>,
 }
 
 impl Episode {
@@ -110,4 +119,8 @@ pub struct EpisodeNoId {
     pub pubdate: Option<DateTime<Utc>>,
     pub duration: Option<i64>,
     pub image_url: Option<String>,
+    /// Url of the Podcasting 2.0 chapters JSON
+    pub chapters_url: Option<String>,
+    /// Url of the Podcasting 2.0 transcript, in one of the supported formats
+    pub transcript_url: Option<String>,
 }
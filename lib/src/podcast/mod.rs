@@ -6,15 +6,22 @@ pub mod db;
 pub mod episode;
 // repetetive name, but will do for now
 #[allow(clippy::module_inception)]
+mod playback_settings;
 mod podcast;
+mod policy;
+pub mod transcript;
 
+use crate::chapter::{self, Chapter};
 use crate::config::v2::server::PodcastSettings;
 use crate::taskpool::TaskPool;
 use crate::types::{Msg, PCMsg};
 use db::Database;
 use episode::{Episode, EpisodeNoId};
 #[allow(clippy::module_name_repetitions)]
+pub use playback_settings::PodcastPlaybackSettings;
 pub use podcast::{Podcast, PodcastNoId};
+pub use policy::PodcastPolicy;
+use transcript::TranscriptFormat;
 
 use anyhow::{bail, Context, Result};
 use bytes::Buf;
@@ -22,15 +29,17 @@ use chrono::{DateTime, Utc};
 use lazy_static::lazy_static;
 use opml::{Body, Head, Outline, OPML};
 use regex::Regex;
-use reqwest::ClientBuilder;
+use reqwest::header::{HeaderMap, CONTENT_TYPE, RANGE};
+use reqwest::{ClientBuilder, StatusCode};
 use rfc822_sanitizer::parse_from_rfc2822_with_fallback;
 use rss::{Channel, Item};
 use sanitize_filename::{sanitize_with_options, Options};
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Sender};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 // How many columns we need, minimum, before we display the
 // (unplayed/total) after the podcast title
@@ -80,6 +89,18 @@ impl PodcastFeed {
     }
 }
 
+/// Get the name of the directory the episodes of the podcast with `title` are downloaded to
+pub fn podcast_dir_name(title: &str) -> String {
+    sanitize_with_options(
+        title,
+        Options {
+            truncate: true,
+            windows: true, // for simplicity, we'll just use Windows-friendly paths for everyone
+            replacement: "",
+        },
+    )
+}
+
 /// Spawns a new task to check a feed and retrieve podcast data.
 ///
 /// If `tx_to_main` is closed, no errors will be throws and the task will continue
@@ -200,6 +221,30 @@ fn parse_episode_data(item: &Item) -> EpisodeNoId {
         image_url = itunes.image().map(std::string::ToString::to_string);
     }
 
+    // Podcasting 2.0 "<podcast:chapters url="..." type="application/json+chapters" />"
+    let chapters_url = item
+        .extensions()
+        .get("podcast")
+        .and_then(|v| v.get("chapters"))
+        .and_then(|v| v.first())
+        .and_then(|v| v.attrs().get("url"))
+        .cloned();
+
+    // Podcasting 2.0 "<podcast:transcript url="..." type="text/vtt" />", there may be one per format
+    let transcript_url = item
+        .extensions()
+        .get("podcast")
+        .and_then(|v| v.get("transcript"))
+        .and_then(|v| {
+            v.iter().find(|v| {
+                v.attrs()
+                    .get("type")
+                    .is_some_and(|v| TranscriptFormat::from_mime(v).is_some())
+            })
+        })
+        .and_then(|v| v.attrs().get("url"))
+        .cloned();
+
     EpisodeNoId {
         title,
         url,
@@ -208,9 +253,39 @@ fn parse_episode_data(item: &Item) -> EpisodeNoId {
         pubdate,
         duration,
         image_url,
+        chapters_url,
+        transcript_url,
     }
 }
 
+/// Fetch and parse the Podcasting 2.0 chapters JSON at `url`
+pub async fn fetch_chapters(url: &str) -> Result<Vec<Chapter>> {
+    let json = fetch_text(url).await?;
+
+    chapter::parse_json(&json)
+}
+
+/// Fetch the Podcasting 2.0 transcript at `url`, to be parsed with [`transcript::parse`]
+pub async fn fetch_transcript(url: &str) -> Result<String> {
+    fetch_text(url).await
+}
+
+async fn fetch_text(url: &str) -> Result<String> {
+    let agent = ClientBuilder::new()
+        .connect_timeout(Duration::from_secs(5))
+        .build()?;
+
+    let text = agent
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    Ok(text)
+}
+
 /// Given a string representing an episode duration, this attempts to
 /// convert to an integer representing the duration in seconds. Covers
 /// formats HH:MM:SS, MM:SS, and SS. If the duration cannot be converted
@@ -440,6 +515,132 @@ pub struct EpData {
     pub file_path: Option<PathBuf>,
 }
 
+/// Progress of a running episode download
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DownloadProgress {
+    /// Bytes downloaded so far, including the ones of a resumed earlier attempt
+    pub downloaded: u64,
+    /// Size of the whole file, if the server sent it
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// Get how much of the file is downloaded in percent, if the size is known
+    pub fn percent(&self) -> Option<u64> {
+        match self.total {
+            Some(total) if total > 0 => Some((self.downloaded.min(total) * 100) / total),
+            _ => None,
+        }
+    }
+}
+
+/// Handle to cancel a episode download started by [`download_list`]
+#[derive(Debug, Clone, Default)]
+pub struct DownloadCancel(CancellationToken);
+
+impl DownloadCancel {
+    /// Stop the download and remove the partially downloaded file
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+}
+
+/// Extension of files that are still being downloaded, a download with a existing file is resumed
+const PART_EXTENSION: &str = "part";
+
+/// Extension of the file that marks a `.part` file as being written, as both the server and the tui download episodes
+const LOCK_EXTENSION: &str = "part.lock";
+
+/// A lock is left over from a process that did not exit cleanly, if neither it nor the `.part` file
+/// were written for this long
+const STALE_LOCK: Duration = Duration::from_secs(120);
+
+/// How often the progress of a download is reported
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Exclusive right to write a `.part` file, which is released on drop
+#[derive(Debug)]
+struct PartLock(PathBuf);
+
+impl PartLock {
+    /// Try to lock `part_path`, `None` if another download holds the lock
+    fn acquire(part_path: &Path) -> Option<Self> {
+        let lock_path = part_path.with_extension(LOCK_EXTENSION);
+        let create = || {
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)
+        };
+
+        match create() {
+            Ok(_) => Some(Self(lock_path)),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                if !is_lock_stale(&lock_path, part_path) {
+                    return None;
+                }
+                warn!("Removing stale download lock \"{}\"", lock_path.display());
+                let _ = std::fs::remove_file(&lock_path);
+                create().ok().map(|_| Self(lock_path))
+            }
+            Err(err) => {
+                error!(
+                    "Could not create download lock \"{}\": {err}",
+                    lock_path.display()
+                );
+                None
+            }
+        }
+    }
+}
+
+impl Drop for PartLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Get whether the lock at `lock_path` exists, but neither it nor `part_path` were modified recently
+fn is_lock_stale(lock_path: &Path, part_path: &Path) -> bool {
+    let modified = |path: &Path| path.metadata().and_then(|v| v.modified()).ok();
+    let Some(lock_modified) = modified(lock_path) else {
+        return false;
+    };
+    let last_write = modified(part_path).map_or(lock_modified, |v| v.max(lock_modified));
+
+    last_write.elapsed().is_ok_and(|v| v >= STALE_LOCK)
+}
+
+/// Get the path of the `.part` file a episode is downloaded to in `dest`
+fn part_path(ep_data: &EpData, dest: &Path) -> PathBuf {
+    dest.join(format!("{}.{PART_EXTENSION}", episode_file_name(ep_data)))
+}
+
+/// Get the file name a episode is downloaded as, without extension
+fn episode_file_name(ep_data: &EpData) -> String {
+    let file_name = sanitize_with_options(
+        &ep_data.title,
+        Options {
+            truncate: true,
+            windows: true, // for simplicity, we'll just use Windows-friendly paths for everyone
+            replacement: "",
+        },
+    );
+
+    match ep_data.pubdate {
+        Some(pubdate) => format!("{file_name}_{}", pubdate.format("%Y%m%d_%H%M%S")),
+        None => file_name,
+    }
+}
+
+/// Get whether the episode is currently downloaded to `dest` by any process, like the server while this is the tui
+pub fn is_download_running(ep_data: &EpData, dest: &Path) -> bool {
+    let part_path = part_path(ep_data, dest);
+    let lock_path = part_path.with_extension(LOCK_EXTENSION);
+
+    lock_path.exists() && !is_lock_stale(&lock_path, &part_path)
+}
+
 /// This is the function the main controller uses to indicate new files to download.
 ///
 /// It uses the taskpool to start jobs for every episode to be downloaded.
@@ -447,99 +648,323 @@ pub struct EpData {
 ///
 /// If `tx_to_main` is closed, no errors will be throws and the task will continue
 pub fn download_list(
-    episodes: Vec<EpData>,
+    episodes: Vec<(EpData, DownloadCancel)>,
     dest: &Path,
     max_retries: usize,
     tp: &TaskPool,
     tx_to_main: &Sender<Msg>,
 ) {
     // parse episode details and push to queue
-    for ep in episodes {
+    for (ep, cancel) in episodes {
         let tx = tx_to_main.clone();
         let dest2 = dest.to_path_buf();
         tp.execute(async move {
             let _ = tx.send(Msg::Podcast(PCMsg::DLStart(ep.clone())));
-            let result = download_file(ep, dest2, max_retries).await;
+            let result = download_file(ep, dest2, max_retries, &cancel, &tx).await;
             let _ = tx.send(Msg::Podcast(result));
         });
     }
 }
 
+/// Why a download attempt failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DownloadError {
+    /// No or a error response, or the connection broke while downloading
+    Response,
+    FileCreate,
+    FileWrite,
+}
+
+/// What to do with the `.part` file, depending on the response to a possibly ranged request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PartFileAction {
+    /// Append to the existing partial file, which has this many bytes
+    Resume(u64),
+    /// Write the whole file from the start, overwriting any partial file
+    Restart,
+    /// The partial file does not fit the file on the server anymore, remove it and start over on the next attempt
+    Discard,
+}
+
+impl PartFileAction {
+    /// Decide how to continue with a partial file of `existing` bytes, `None` if the response is a error
+    fn from_response(existing: u64, status: StatusCode) -> Option<Self> {
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            return Some(Self::Discard);
+        }
+        if !status.is_success() {
+            return None;
+        }
+
+        // the server may ignore the range and send the whole file instead
+        if status == StatusCode::PARTIAL_CONTENT {
+            Some(Self::Resume(existing))
+        } else {
+            Some(Self::Restart)
+        }
+    }
+}
+
 /// Downloads a file to a local filepath, returning `DownloadMsg` variant
 /// indicating success or failure.
+///
+/// The file is streamed to a `.part` file first, which is resumed with a HTTP Range request
+/// if it already exists from a interrupted download.
 async fn download_file(
     mut ep_data: EpData,
     destination_path: PathBuf,
     mut max_retries: usize,
+    cancel: &DownloadCancel,
+    tx_to_main: &Sender<Msg>,
 ) -> PCMsg {
     let agent = ClientBuilder::new()
         .connect_timeout(Duration::from_secs(10))
         .build()
         .expect("reqwest client build failed");
 
-    let response: reqwest::Response = loop {
-        let response = agent.get(&ep_data.url).send().await;
-        if let Ok(resp) = response {
-            break resp;
-        }
-        max_retries -= 1;
-        if max_retries == 0 {
-            return PCMsg::DLResponseError(ep_data);
+    let file_name = episode_file_name(&ep_data);
+    let part_path = part_path(&ep_data, &destination_path);
+    // another process writing the same file at the same time would corrupt it
+    let Some(_lock) = PartLock::acquire(&part_path) else {
+        return PCMsg::DLAlreadyRunning(ep_data);
+    };
+
+    let ext = loop {
+        let on_progress = |progress| {
+            let _ = tx_to_main.send(Msg::Podcast(PCMsg::DLProgress(ep_data.clone(), progress)));
+        };
+        let attempt = download_attempt(&agent, &ep_data.url, &part_path, on_progress);
+        let result = tokio::select! {
+            result = attempt => result,
+            () = cancel.0.cancelled() => {
+                let _ = std::fs::remove_file(&part_path);
+                return PCMsg::DLCancelled(ep_data);
+            }
+        };
+
+        match result {
+            Ok(ext) => break ext,
+            // the next attempt resumes where this one stopped
+            Err(DownloadError::Response) if max_retries > 1 => max_retries -= 1,
+            Err(DownloadError::Response) => return PCMsg::DLResponseError(ep_data),
+            Err(DownloadError::FileCreate) => return PCMsg::DLFileCreateError(ep_data),
+            Err(DownloadError::FileWrite) => return PCMsg::DLFileWriteError(ep_data),
         }
     };
 
-    // figure out the file type
-    let ext = if let Some(content_type) = response
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
+    let file_path = destination_path.join(format!("{file_name}.{ext}"));
+    if std::fs::rename(&part_path, &file_path).is_err() {
+        return PCMsg::DLFileWriteError(ep_data);
+    }
+    ep_data.file_path = Some(file_path);
+
+    PCMsg::DLComplete(ep_data)
+}
+
+/// Download `url` to `part_path` in chunks, continuing a existing partial file if the server supports it
+///
+/// Returns the file extension to use for the finished file.
+async fn download_attempt<F: Fn(DownloadProgress)>(
+    agent: &reqwest::Client,
+    url: &str,
+    part_path: &Path,
+    on_progress: F,
+) -> Result<&'static str, DownloadError> {
+    let existing = std::fs::metadata(part_path).map_or(0, |v| v.len());
+
+    let mut request = agent.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={existing}-"));
+    }
+    let mut response = request.send().await.map_err(|_| DownloadError::Response)?;
+
+    let status = response.status();
+    let mut downloaded = match PartFileAction::from_response(existing, status) {
+        Some(PartFileAction::Resume(existing)) => existing,
+        Some(PartFileAction::Restart) => 0,
+        Some(PartFileAction::Discard) => {
+            std::fs::remove_file(part_path).map_err(|_| DownloadError::FileCreate)?;
+            return Err(DownloadError::Response);
+        }
+        None => {
+            error!("Download of \"{url}\" failed with status {status}");
+            return Err(DownloadError::Response);
+        }
+    };
+    let resumed = downloaded > 0;
+    let total = response.content_length().map(|len| len + downloaded);
+    let ext = file_extension(response.headers());
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part_path)
+        .map_err(|_| DownloadError::FileCreate)?;
+
+    on_progress(DownloadProgress { downloaded, total });
+    let mut last_progress = Instant::now();
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|_| DownloadError::Response)?
     {
-        match content_type {
-            "audio/x-m4a" | "audio/mp4" => "m4a",
-            "audio/x-matroska" => "mka",
-            "audio/flac" => "flac",
-            "video/quicktime" => "mov",
-            "video/mp4" => "mp4",
-            "video/x-m4v" => "m4v",
-            "video/x-matroska" => "mkv",
-            "video/webm" => "webm",
-            // "audio/mpeg" => "mp3",
-            // fallback
-            _ => "mp3",
+        file.write_all(&chunk)
+            .map_err(|_| DownloadError::FileWrite)?;
+        downloaded += chunk.len() as u64;
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(DownloadProgress { downloaded, total });
+            last_progress = Instant::now();
         }
-    } else {
+    }
+    file.flush().map_err(|_| DownloadError::FileWrite)?;
+
+    Ok(ext)
+}
+
+/// Figure out the file extension from the content type of a response
+fn file_extension(headers: &HeaderMap) -> &'static str {
+    let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
         error!("The response doesn't contain a content type, using \"mp3\" as fallback!");
-        "mp3"
+        return "mp3";
     };
 
-    let mut file_name = sanitize_with_options(
-        &ep_data.title,
-        Options {
-            truncate: true,
-            windows: true, // for simplicity, we'll just use Windows-friendly paths for everyone
-            replacement: "",
-        },
-    );
+    match content_type {
+        "audio/x-m4a" | "audio/mp4" => "m4a",
+        "audio/x-matroska" => "mka",
+        "audio/flac" => "flac",
+        "video/quicktime" => "mov",
+        "video/mp4" => "mp4",
+        "video/x-m4v" => "m4v",
+        "video/x-matroska" => "mkv",
+        "video/webm" => "webm",
+        // "audio/mpeg" => "mp3",
+        // fallback
+        _ => "mp3",
+    }
+}
 
-    if let Some(pubdate) = ep_data.pubdate {
-        file_name = format!("{file_name}_{}", pubdate.format("%Y%m%d_%H%M%S"));
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+    use rss::Channel;
+
+    use super::{parse_feed_data, DownloadProgress, PartFileAction, PartLock, LOCK_EXTENSION};
+
+    #[test]
+    fn should_get_download_percent() {
+        let progress = |downloaded, total| DownloadProgress { downloaded, total };
+
+        assert_eq!(progress(50, Some(200)).percent(), Some(25));
+        assert_eq!(progress(200, Some(200)).percent(), Some(100));
+        // more than announced by the server
+        assert_eq!(progress(300, Some(200)).percent(), Some(100));
+        // unknown or zero size
+        assert_eq!(progress(50, None).percent(), None);
+        assert_eq!(progress(50, Some(0)).percent(), None);
     }
 
-    let mut file_path = destination_path;
-    file_path.push(format!("{file_name}.{ext}"));
+    #[test]
+    fn should_lock_part_file_exclusively() {
+        let part_path =
+            std::env::temp_dir().join(format!("termusic-lock-test-{}.part", std::process::id()));
+        let lock_path = part_path.with_extension(LOCK_EXTENSION);
 
-    let Ok(mut dst) = File::create(&file_path) else {
-        return PCMsg::DLFileCreateError(ep_data);
-    };
+        let lock = PartLock::acquire(&part_path).unwrap();
+        assert!(lock_path.exists());
+        assert!(PartLock::acquire(&part_path).is_none());
 
-    ep_data.file_path = Some(file_path);
+        drop(lock);
+        assert!(!lock_path.exists());
+        assert!(PartLock::acquire(&part_path).is_some());
+    }
 
-    let Ok(bytes) = response.bytes().await else {
-        return PCMsg::DLFileCreateError(ep_data);
-    };
+    #[test]
+    fn should_decide_part_file_action() {
+        assert_eq!(
+            PartFileAction::from_response(100, StatusCode::PARTIAL_CONTENT),
+            Some(PartFileAction::Resume(100))
+        );
+        // the range was ignored
+        assert_eq!(
+            PartFileAction::from_response(100, StatusCode::OK),
+            Some(PartFileAction::Restart)
+        );
+        assert_eq!(
+            PartFileAction::from_response(0, StatusCode::OK),
+            Some(PartFileAction::Restart)
+        );
+        assert_eq!(
+            PartFileAction::from_response(100, StatusCode::RANGE_NOT_SATISFIABLE),
+            Some(PartFileAction::Discard)
+        );
+        assert_eq!(
+            PartFileAction::from_response(100, StatusCode::NOT_FOUND),
+            None
+        );
+    }
+
+    #[test]
+    fn should_parse_chapters_url() {
+        let feed = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Example</title>
+    <item>
+      <title>With chapters</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg" />
+      <podcast:chapters url="https://example.com/1.json" type="application/json+chapters" />
+    </item>
+    <item>
+      <title>Without chapters</title>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg" />
+    </item>
+  </channel>
+</rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = parse_feed_data(channel, "https://example.com/feed.xml");
+
+        let chapters_urls: Vec<Option<&str>> = podcast
+            .episodes
+            .iter()
+            .map(|v| v.chapters_url.as_deref())
+            .collect();
+        assert_eq!(chapters_urls, [Some("https://example.com/1.json"), None]);
+    }
 
-    match std::io::copy(&mut bytes.reader(), &mut dst) {
-        Ok(_) => PCMsg::DLComplete(ep_data),
-        Err(_) => PCMsg::DLFileWriteError(ep_data),
+    #[test]
+    fn should_parse_supported_transcript_url() {
+        let feed = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Example</title>
+    <item>
+      <title>With transcripts</title>
+      <enclosure url="https://example.com/1.mp3" length="1" type="audio/mpeg" />
+      <podcast:transcript url="https://example.com/1.html" type="text/html" />
+      <podcast:transcript url="https://example.com/1.vtt" type="text/vtt" />
+    </item>
+    <item>
+      <title>Only unsupported transcript</title>
+      <enclosure url="https://example.com/2.mp3" length="1" type="audio/mpeg" />
+      <podcast:transcript url="https://example.com/2.html" type="text/html" />
+    </item>
+  </channel>
+</rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = parse_feed_data(channel, "https://example.com/feed.xml");
+
+        let transcript_urls: Vec<Option<&str>> = podcast
+            .episodes
+            .iter()
+            .map(|v| v.transcript_url.as_deref())
+            .collect();
+        assert_eq!(transcript_urls, [Some("https://example.com/1.vtt"), None]);
     }
 }
@@ -0,0 +1,154 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+
+/// Per-feed settings applied by the server whenever a episode of the feed starts playing.
+///
+/// The default changes nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PodcastPlaybackSettings {
+    /// Playback speed in tenths (like `PlayerSettings.speed`), `None` to use the global speed
+    pub speed: Option<i32>,
+    /// Skip this much at the start of every episode, like a fixed-length intro
+    pub skip_intro: Duration,
+    /// Skip to the next track once only this much of a episode is left
+    pub skip_outro: Duration,
+}
+
+impl PodcastPlaybackSettings {
+    /// Lowest speed that can be set, in tenths
+    const MIN_SPEED: i32 = 1;
+    /// Highest speed that can be set, in tenths
+    const MAX_SPEED: i32 = 30;
+
+    /// Get whether a episode at `position` of `duration` is in the outro that should be skipped
+    pub fn is_in_outro(&self, position: Duration, duration: Duration) -> bool {
+        // a unknown duration is reported as 0
+        !self.skip_outro.is_zero()
+            && !duration.is_zero()
+            && position.saturating_add(self.skip_outro) >= duration
+    }
+}
+
+/// Format as `speed=1.5 intro=30 outro=0`, with the intro and outro in seconds, for the user to edit
+impl Display for PodcastPlaybackSettings {
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.speed {
+            Some(speed) => write!(f, "speed={:.1}", speed as f32 / 10.0)?,
+            None => write!(f, "speed=global")?,
+        }
+        write!(
+            f,
+            " intro={} outro={}",
+            self.skip_intro.as_secs(),
+            self.skip_outro.as_secs()
+        )
+    }
+}
+
+/// Parse the format of [`Display`], any value not given is set to the default
+impl FromStr for PodcastPlaybackSettings {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut settings = Self::default();
+
+        for pair in s.split_whitespace() {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected \"key=value\", found \"{pair}\""))?;
+
+            match key {
+                "speed" if value == "global" => settings.speed = None,
+                "speed" => {
+                    let speed: f32 = value
+                        .parse()
+                        .with_context(|| format!("invalid speed \"{value}\""))?;
+                    #[allow(clippy::cast_possible_truncation)]
+                    let speed = (speed * 10.0).round() as i32;
+                    if !(Self::MIN_SPEED..=Self::MAX_SPEED).contains(&speed) {
+                        bail!("speed has to be between 0.1 and 3.0");
+                    }
+                    settings.speed = Some(speed);
+                }
+                "intro" => {
+                    settings.skip_intro = Duration::from_secs(
+                        value
+                            .parse()
+                            .with_context(|| format!("invalid intro seconds \"{value}\""))?,
+                    );
+                }
+                "outro" => {
+                    settings.skip_outro = Duration::from_secs(
+                        value
+                            .parse()
+                            .with_context(|| format!("invalid outro seconds \"{value}\""))?,
+                    );
+                }
+                _ => bail!("unknown setting \"{key}\", expected \"speed\", \"intro\" or \"outro\""),
+            }
+        }
+
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::PodcastPlaybackSettings;
+
+    #[test]
+    fn should_roundtrip_string() {
+        let settings = PodcastPlaybackSettings {
+            speed: Some(15),
+            skip_intro: Duration::from_secs(30),
+            skip_outro: Duration::ZERO,
+        };
+
+        assert_eq!(settings.to_string(), "speed=1.5 intro=30 outro=0");
+        assert_eq!(
+            "speed=1.5 intro=30 outro=0"
+                .parse::<PodcastPlaybackSettings>()
+                .unwrap(),
+            settings
+        );
+        assert_eq!(
+            PodcastPlaybackSettings::default().to_string(),
+            "speed=global intro=0 outro=0"
+        );
+        assert_eq!(
+            "".parse::<PodcastPlaybackSettings>().unwrap(),
+            PodcastPlaybackSettings::default()
+        );
+    }
+
+    #[test]
+    fn should_not_parse_invalid() {
+        assert!("speed=5".parse::<PodcastPlaybackSettings>().is_err());
+        assert!("intro=-1".parse::<PodcastPlaybackSettings>().is_err());
+        assert!("outro".parse::<PodcastPlaybackSettings>().is_err());
+        assert!("volume=10".parse::<PodcastPlaybackSettings>().is_err());
+    }
+
+    #[test]
+    fn should_detect_outro() {
+        let settings = PodcastPlaybackSettings {
+            skip_outro: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let duration = Duration::from_secs(600);
+
+        assert!(!settings.is_in_outro(Duration::from_secs(539), duration));
+        assert!(settings.is_in_outro(Duration::from_secs(540), duration));
+        // unknown duration
+        assert!(!settings.is_in_outro(Duration::from_secs(540), Duration::ZERO));
+        assert!(!PodcastPlaybackSettings::default().is_in_outro(duration, duration));
+    }
+}
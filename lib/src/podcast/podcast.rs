@@ -6,7 +6,7 @@ use crate::utils::StringUtils;
 
 use super::{
     episode::{Episode, EpisodeNoId},
-    Menuable, PODCAST_UNPLAYED_TOTALS_LENGTH,
+    Menuable, PodcastPlaybackSettings, PodcastPolicy, PODCAST_UNPLAYED_TOTALS_LENGTH,
 };
 
 /// Struct holding data about an individual podcast feed. This includes a
@@ -23,6 +23,8 @@ pub struct Podcast {
     pub last_checked: DateTime<Utc>,
     pub episodes: Vec<Episode>,
     pub image_url: Option<String>,
+    pub policy: PodcastPolicy,
+    pub playback: PodcastPlaybackSettings,
 }
 
 impl Podcast {
@@ -0,0 +1,216 @@
+use std::cmp::Reverse;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::episode::Episode;
+
+/// Per-feed rules for the server to refresh the feed, download new episodes and remove old downloads
+/// without the user having to do anything.
+///
+/// The default does nothing automatically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PodcastPolicy {
+    /// Refresh the feed once it was last checked longer ago than this
+    pub refresh_interval: Option<Duration>,
+    /// Keep the newest this many unplayed episodes downloaded, `0` to disable
+    pub auto_download: u32,
+    /// Delete downloads of played episodes this many days after they were downloaded
+    pub keep_days: Option<u32>,
+    /// Keep at most this many downloads, removing played and then the oldest episodes first
+    pub keep_max: Option<u32>,
+}
+
+impl PodcastPolicy {
+    /// Get whether anything is done automatically for the feed
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Get whether the feed should be refreshed, given when it was last checked
+    pub fn is_refresh_due(&self, last_checked: DateTime<Utc>) -> bool {
+        self.is_refresh_due_at(last_checked, Utc::now())
+    }
+
+    fn is_refresh_due_at(&self, last_checked: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let Some(interval) = self.refresh_interval else {
+            return false;
+        };
+
+        // "to_std" fails if "last_checked" is in the future
+        (now - last_checked)
+            .to_std()
+            .is_ok_and(|elapsed| elapsed >= interval)
+    }
+
+    /// Get the episodes that should be downloaded, but are not yet, newest first
+    ///
+    /// The amount is limited to [`keep_max`](Self::keep_max), as the downloads would be removed again otherwise.
+    pub fn episodes_to_download<'a>(&self, episodes: &'a [Episode]) -> Vec<&'a Episode> {
+        let mut amount = self.auto_download as usize;
+        if let Some(keep_max) = self.keep_max {
+            amount = amount.min(keep_max as usize);
+        }
+
+        let mut unplayed: Vec<&Episode> = episodes
+            .iter()
+            .filter(|ep| !ep.played && !ep.url.is_empty())
+            .collect();
+        unplayed.sort_by_key(|ep| Reverse(ep.pubdate));
+
+        unplayed
+            .into_iter()
+            .take(amount)
+            .filter(|ep| ep.path.is_none())
+            .collect()
+    }
+
+    /// Get the downloaded episodes whose files should be removed
+    pub fn episodes_to_remove<'a>(&self, episodes: &'a [Episode]) -> Vec<&'a Episode> {
+        self.episodes_to_remove_at(episodes, Utc::now())
+    }
+
+    fn episodes_to_remove_at<'a>(
+        &self,
+        episodes: &'a [Episode],
+        now: DateTime<Utc>,
+    ) -> Vec<&'a Episode> {
+        let mut downloaded: Vec<&Episode> =
+            episodes.iter().filter(|ep| ep.path.is_some()).collect();
+        // the ones to keep first: unplayed before played, then newest first
+        downloaded.sort_by_key(|ep| (ep.played, Reverse(ep.pubdate)));
+
+        let mut remove = Vec::new();
+        // "try_days" can only fail for far more days than fit into a u32
+        if let Some(max_age) = self
+            .keep_days
+            .and_then(|days| TimeDelta::try_days(i64::from(days)))
+        {
+            downloaded.retain(|ep| {
+                let expired = ep.played && ep.downloaded.is_some_and(|at| now - at >= max_age);
+                if expired {
+                    remove.push(*ep);
+                }
+                !expired
+            });
+        }
+        if let Some(keep_max) = self.keep_max {
+            remove.extend(downloaded.into_iter().skip(keep_max as usize));
+        }
+
+        remove
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::PodcastPolicy;
+    use crate::podcast::episode::Episode;
+
+    fn date(days: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(days * 24 * 60 * 60, 0).unwrap()
+    }
+
+    /// Create a episode published on day `id`, downloaded on the same day if `downloaded`
+    fn episode(id: i64, played: bool, downloaded: bool) -> Episode {
+        Episode {
+            id,
+            url: format!("http://example.com/{id}.mp3"),
+            pubdate: Some(date(id)),
+            played,
+            path: downloaded.then(|| PathBuf::from(format!("/tmp/{id}.mp3"))),
+            downloaded: downloaded.then(|| date(id)),
+            ..Default::default()
+        }
+    }
+
+    fn ids(episodes: &[&Episode]) -> Vec<i64> {
+        episodes.iter().map(|ep| ep.id).collect()
+    }
+
+    #[test]
+    fn should_refresh_after_interval() {
+        let policy = PodcastPolicy {
+            refresh_interval: Some(Duration::from_secs(24 * 60 * 60)),
+            ..Default::default()
+        };
+
+        assert!(!policy.is_refresh_due_at(date(10), date(10)));
+        assert!(policy.is_refresh_due_at(date(10), date(11)));
+        // last check in the future, like after changing the clock
+        assert!(!policy.is_refresh_due_at(date(12), date(11)));
+
+        assert!(!PodcastPolicy::default().is_refresh_due_at(date(0), date(100)));
+    }
+
+    #[test]
+    fn should_download_newest_unplayed() {
+        let policy = PodcastPolicy {
+            auto_download: 2,
+            ..Default::default()
+        };
+        let episodes = [
+            episode(1, false, false),
+            episode(4, true, false),
+            episode(3, false, true),
+            episode(2, false, false),
+        ];
+
+        // episode 3 already is downloaded and counts towards the limit
+        assert_eq!(ids(&policy.episodes_to_download(&episodes)), vec![2]);
+        assert!(PodcastPolicy::default()
+            .episodes_to_download(&episodes)
+            .is_empty());
+
+        let policy = PodcastPolicy {
+            auto_download: 3,
+            keep_max: Some(1),
+            ..Default::default()
+        };
+        assert!(policy.episodes_to_download(&episodes).is_empty());
+    }
+
+    #[test]
+    fn should_remove_expired_played() {
+        let policy = PodcastPolicy {
+            keep_days: Some(5),
+            ..Default::default()
+        };
+        let episodes = [
+            episode(1, true, true),
+            episode(2, false, true),
+            episode(8, true, true),
+            episode(3, true, false),
+        ];
+
+        assert_eq!(
+            ids(&policy.episodes_to_remove_at(&episodes, date(10))),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn should_remove_over_max() {
+        let policy = PodcastPolicy {
+            keep_max: Some(2),
+            ..Default::default()
+        };
+        let episodes = [
+            episode(1, false, true),
+            episode(2, true, true),
+            episode(3, true, true),
+            episode(4, false, true),
+            episode(5, false, false),
+        ];
+
+        let mut remove = ids(&policy.episodes_to_remove_at(&episodes, date(10)));
+        remove.sort_unstable();
+        assert_eq!(remove, vec![2, 3]);
+    }
+}
@@ -0,0 +1,316 @@
+//! Transcripts of podcast episodes, linked from the feed via
+//! [`podcast:transcript`](https://github.com/Podcastindex-org/podcast-namespace/blob/main/transcripts/transcripts.md).
+//!
+//! Transcripts are parsed into a [`Lyric`], so that they can be shown in sync with playback like LRC lyrics.
+//! Once fetched, they are cached next to the downloaded episode file.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::songtag::lrc::{Lyric, UnsyncedCaption};
+
+lazy_static! {
+    /// `WebVTT` voice spans like "<v Speaker Name>" or "<v.loud Speaker>"
+    static ref RE_VTT_VOICE: Regex = Regex::new(r"<v(?:\.[^ >]*)? ([^>]+)>").unwrap();
+    /// Any other formatting tags like "<i>", "</b>" or "<00:00:01.000>"
+    static ref RE_TAGS: Regex = Regex::new(r"<[^>]*>").unwrap();
+}
+
+/// JSON segments are merged into one line until the line spans this many milliseconds
+const JSON_LINE_SPAN_MS: i64 = 5000;
+
+/// The transcript formats that can be shown in sync with playback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl TranscriptFormat {
+    const ALL: [Self; 3] = [Self::Srt, Self::Vtt, Self::Json];
+
+    /// Get the format from the mime type given in the `type` attribute of a `podcast:transcript` tag
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        match mime.trim().to_ascii_lowercase().as_str() {
+            "application/srt" | "application/x-subrip" | "text/srt" => Some(Self::Srt),
+            "text/vtt" => Some(Self::Vtt),
+            "application/json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the transcript itself
+    pub fn detect(text: &str) -> Self {
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        if text.starts_with("WEBVTT") {
+            Self::Vtt
+        } else if text.starts_with('{') {
+            Self::Json
+        } else {
+            Self::Srt
+        }
+    }
+
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Parse a transcript in any of the [`TranscriptFormat`]s
+pub fn parse(text: &str) -> Result<Lyric> {
+    let mut captions = match TranscriptFormat::detect(text) {
+        TranscriptFormat::Srt | TranscriptFormat::Vtt => parse_cues(text),
+        TranscriptFormat::Json => parse_json(text)?,
+    };
+    captions.sort_by_key(UnsyncedCaption::time_stamp);
+
+    if captions.is_empty() {
+        anyhow::bail!("transcript does not contain any text");
+    }
+
+    Ok(Lyric {
+        offset: 0,
+        lang_extension: None,
+        unsynced_captions: captions,
+    })
+}
+
+/// Parse the cues of a SRT or `WebVTT` transcript, which both are blocks separated by empty lines:
+///
+/// ```txt
+/// 1
+/// 00:00:01,000 --> 00:00:04,000
+/// Text of the cue
+/// ```
+///
+/// Blocks without a timing line, like the `WebVTT` header or notes, are skipped.
+fn parse_cues(text: &str) -> Vec<UnsyncedCaption> {
+    let text = text.replace("\r\n", "\n");
+    let mut captions = Vec::new();
+
+    for block in text.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some(start) = timing.split("-->").next().and_then(parse_timestamp) else {
+            continue;
+        };
+
+        let text = lines
+            .map(|line| RE_VTT_VOICE.replace_all(line, "$1: "))
+            .map(|line| RE_TAGS.replace_all(&line, "").trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !text.is_empty() {
+            captions.push(UnsyncedCaption::new(start, text));
+        }
+    }
+
+    captions
+}
+
+/// Parse a timestamp like `01:02:03,456` (SRT) or `02:03.456` (`WebVTT`) into milliseconds
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim().replace(',', ".");
+    let (hms, millis) = value.split_once('.').unwrap_or((value.as_str(), "0"));
+
+    let mut secs: i64 = 0;
+    for part in hms.split(':') {
+        secs = secs * 60 + part.parse::<i64>().ok()?;
+    }
+    // only use the first 3 digits, as that is what both formats specify
+    let millis: i64 = format!("{millis:0<3}").get(..3)?.parse().ok()?;
+
+    Some(secs * 1000 + millis)
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTranscript {
+    segments: Vec<JsonSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonSegment {
+    speaker: Option<String>,
+    /// Start of the segment in seconds
+    start_time: f64,
+    body: String,
+}
+
+/// Parse a JSON transcript, merging the segments (which are often single words) into lines
+///
+/// A new line is started when the speaker changes or the current line spans more than [`JSON_LINE_SPAN_MS`].
+fn parse_json(text: &str) -> Result<Vec<UnsyncedCaption>> {
+    let parsed: JsonTranscript = serde_json::from_str(text).context("parse transcript json")?;
+
+    let mut captions = Vec::new();
+    let mut line: Option<(i64, Option<String>, String)> = None;
+    for segment in parsed.segments {
+        if !segment.start_time.is_finite() || segment.start_time < 0.0 {
+            continue;
+        }
+        let body = segment.body.trim();
+        if body.is_empty() {
+            continue;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let start = (segment.start_time * 1000.0) as i64;
+
+        if let Some((line_start, speaker, text)) = &mut line {
+            let same_speaker = segment.speaker.is_none() || segment.speaker == *speaker;
+            if same_speaker && start - *line_start < JSON_LINE_SPAN_MS {
+                text.push(' ');
+                text.push_str(body);
+                continue;
+            }
+        }
+
+        if let Some((line_start, _, text)) = line.take() {
+            captions.push(UnsyncedCaption::new(line_start, text));
+        }
+        let text = match &segment.speaker {
+            Some(speaker) => format!("{speaker}: {body}"),
+            None => body.to_string(),
+        };
+        line = Some((start, segment.speaker, text));
+    }
+    if let Some((line_start, _, text)) = line {
+        captions.push(UnsyncedCaption::new(line_start, text));
+    }
+
+    Ok(captions)
+}
+
+/// Get the path of the cached transcript for the episode downloaded to `episode_path`
+fn cache_path(episode_path: &Path, format: TranscriptFormat) -> PathBuf {
+    episode_path.with_extension(format!("transcript.{}", format.extension()))
+}
+
+/// Read and parse the cached transcript of the episode downloaded to `episode_path`, if there is one
+pub fn read_cached(episode_path: &Path) -> Option<Lyric> {
+    TranscriptFormat::ALL.into_iter().find_map(|format| {
+        let text = std::fs::read_to_string(cache_path(episode_path, format)).ok()?;
+        parse(&text).ok()
+    })
+}
+
+/// Cache the transcript `text` next to the episode downloaded to `episode_path`
+pub fn write_cache(episode_path: &Path, text: &str) -> Result<()> {
+    let path = cache_path(episode_path, TranscriptFormat::detect(text));
+    std::fs::write(&path, text).with_context(|| format!("write transcript \"{}\"", path.display()))
+}
+
+/// Remove the cached transcript of the episode downloaded to `episode_path`, like when the download is removed
+pub fn remove_cached(episode_path: &Path) {
+    for format in TranscriptFormat::ALL {
+        let _ = std::fs::remove_file(cache_path(episode_path, format));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn lines(lyric: &Lyric) -> Vec<(i64, String)> {
+        lyric
+            .unsynced_captions
+            .iter()
+            .map(|v| (v.time_stamp(), v.text().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_srt() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:04,000\r\nHello <i>and</i>\r\nwelcome\r\n\r\n2\r\n01:00:05,5 --> 01:00:07,000\r\nBye\r\n";
+
+        let lyric = parse(srt).unwrap();
+        assert_eq!(
+            lines(&lyric),
+            [
+                (1000, "Hello and welcome".to_string()),
+                (3_605_500, "Bye".to_string())
+            ]
+        );
+        assert_eq!(
+            lyric.get_text(Duration::from_secs(3600)),
+            Some("Hello and welcome".to_string())
+        );
+    }
+
+    #[test]
+    fn should_parse_vtt() {
+        let vtt = "WEBVTT\n\nNOTE some comment\n\nintro\n00:01.000 --> 00:04.000 align:start\n<v Alice>Hello\n\n00:00:05.250 --> 00:00:07.000\n<v.loud Bob>Hi</v>";
+
+        let lyric = parse(vtt).unwrap();
+        assert_eq!(
+            lines(&lyric),
+            [
+                (1000, "Alice: Hello".to_string()),
+                (5250, "Bob: Hi".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_json() {
+        let json = r#"{
+            "version": "1.0.0",
+            "segments": [
+                {"speaker": "Alice", "startTime": 0.5, "endTime": 0.9, "body": "Hello"},
+                {"speaker": "Alice", "startTime": 1.0, "endTime": 1.5, "body": "there"},
+                {"speaker": "Bob", "startTime": 2.0, "endTime": 2.5, "body": "Hi"},
+                {"speaker": "Bob", "startTime": 8.0, "endTime": 8.5, "body": "again"}
+            ]
+        }"#;
+
+        let lyric = parse(json).unwrap();
+        assert_eq!(
+            lines(&lyric),
+            [
+                (500, "Alice: Hello there".to_string()),
+                (2000, "Bob: Hi".to_string()),
+                (8000, "Bob: again".to_string())
+            ]
+        );
+        assert!(parse("{}").is_err());
+        assert!(parse("not a transcript").is_err());
+    }
+
+    #[test]
+    fn should_get_format() {
+        assert_eq!(
+            TranscriptFormat::from_mime("application/x-subrip"),
+            Some(TranscriptFormat::Srt)
+        );
+        assert_eq!(
+            TranscriptFormat::from_mime("text/vtt"),
+            Some(TranscriptFormat::Vtt)
+        );
+        assert_eq!(TranscriptFormat::from_mime("text/html"), None);
+
+        assert_eq!(
+            cache_path(
+                Path::new("/podcasts/show/episode.mp3"),
+                TranscriptFormat::Vtt
+            ),
+            Path::new("/podcasts/show/episode.transcript.vtt")
+        );
+    }
+}
@@ -66,6 +66,7 @@ pub struct Lyric {
 pub struct UnsyncedCaption {
     /// Timestamp in milliseconds
     time_stamp: i64,
+    /// The lyric line shown from `time_stamp` on
     text: String,
 }
 
@@ -184,6 +185,20 @@ impl Lyric {
 }
 
 impl UnsyncedCaption {
+    /// Create a caption starting at `time_stamp` milliseconds
+    pub fn new(time_stamp: i64, text: String) -> Self {
+        Self { time_stamp, text }
+    }
+
+    /// Get the start of the caption in milliseconds
+    pub const fn time_stamp(&self) -> i64 {
+        self.time_stamp
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
     fn parse_line(line: &mut String) -> Result<Self, ()> {
         //[00:12.00]Line 1 lyrics
         // !line.starts_with('[') | !line.contains(']')
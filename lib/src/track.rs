@@ -1,4 +1,3 @@
-use crate::podcast::episode::Episode;
 /**
  * MIT License
  *
@@ -22,6 +21,10 @@ use crate::podcast::episode::Episode;
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE US OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use crate::chapter::{self, Chapter};
+use crate::config::v2::server::{ReplayGainMode, ReplayGainSettings};
+use crate::podcast::episode::Episode;
+use crate::podcast::transcript;
 use crate::songtag::lrc::Lyric;
 use crate::utils::get_parent_folder;
 use anyhow::{bail, Context, Result};
@@ -30,7 +33,7 @@ use lofty::config::{ParseOptions, WriteOptions};
 use lofty::id3::v2::{Frame, Id3v2Tag, UnsynchronizedTextFrame};
 use lofty::picture::{Picture, PictureType};
 use lofty::prelude::{Accessor, AudioFile, ItemKey, TagExt, TaggedFileExt};
-use lofty::tag::{ItemValue, Tag as LoftyTag, TagItem};
+use lofty::tag::{ItemValue, Tag as LoftyTag, TagItem, TagType};
 use lofty::{file::FileType, mpeg::MpegFile, probe::Probe, TextEncoding};
 use std::convert::From;
 use std::ffi::OsStr;
@@ -69,15 +72,27 @@ pub struct Track {
     picture: Option<Picture>,
     album_photo: Option<String>,
     file_type: Option<FileType>,
-    // Date
-    // Track
+    /// Release year
+    year: Option<u32>,
+    /// Number of the track on its disc
+    number: Option<u32>,
     genre: Option<String>,
-    // Composer
+    /// Artist of the whole album, which may differ from the track's artist on compilations
+    album_artist: Option<String>,
+    composer: Option<String>,
     // Performer
-    // Disc
+    /// Number of the disc in a multi-disc album
+    disc_number: Option<u32>,
     // Comment
     pub media_type: MediaType,
     pub podcast_localfile: Option<String>,
+    chapters: Vec<Chapter>,
+    /// Url of the chapters JSON of a podcast episode, if the chapters have not been fetched yet
+    chapters_url: Option<String>,
+    /// Url of the transcript of a podcast episode, if it has not been fetched yet
+    transcript_url: Option<String>,
+    /// `ReplayGain` / `R128` values read from the tags
+    replaygain: ReplayGain,
 }
 
 impl PartialEq for Track {
@@ -86,6 +101,96 @@ impl PartialEq for Track {
     }
 }
 
+/// `ReplayGain` values of a track, gains are in dB
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayGain {
+    pub track_gain: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub album_gain: Option<f32>,
+    pub album_peak: Option<f32>,
+}
+
+/// The reference level `R128` tags are relative to is 5 dB lower than the `ReplayGain` reference level
+const R128_TO_REPLAYGAIN_DB: f32 = 5.0;
+
+impl ReplayGain {
+    /// Read the `ReplayGain` values from the given tag, falling back to the `R128` tags (as used by Opus) if there are no `ReplayGain` tags
+    fn from_tag(tag: &LoftyTag) -> Self {
+        let track_gain = tag
+            .get_string(&ItemKey::ReplayGainTrackGain)
+            .and_then(parse_replaygain_value)
+            .or_else(|| {
+                tag.get_string(&ItemKey::Unknown("R128_TRACK_GAIN".to_string()))
+                    .and_then(parse_r128_gain)
+            });
+        let album_gain = tag
+            .get_string(&ItemKey::ReplayGainAlbumGain)
+            .and_then(parse_replaygain_value)
+            .or_else(|| {
+                tag.get_string(&ItemKey::Unknown("R128_ALBUM_GAIN".to_string()))
+                    .and_then(parse_r128_gain)
+            });
+
+        Self {
+            track_gain,
+            track_peak: tag
+                .get_string(&ItemKey::ReplayGainTrackPeak)
+                .and_then(parse_replaygain_value),
+            album_gain,
+            album_peak: tag
+                .get_string(&ItemKey::ReplayGainAlbumPeak)
+                .and_then(parse_replaygain_value),
+        }
+    }
+
+    /// Get the factor the samples should be multiplied with for the given settings
+    ///
+    /// If the selected kind of gain is not available, the other kind is used; if none is available, only the preamp is applied.
+    #[must_use]
+    pub fn factor(&self, settings: &ReplayGainSettings) -> f32 {
+        let (gain, peak) = match settings.mode {
+            ReplayGainMode::Off => return 1.0,
+            ReplayGainMode::Track => (
+                self.track_gain.or(self.album_gain),
+                self.track_peak.or(self.album_peak),
+            ),
+            ReplayGainMode::Album => (
+                self.album_gain.or(self.track_gain),
+                self.album_peak.or(self.track_peak),
+            ),
+        };
+
+        let gain_db = gain.unwrap_or_default() + settings.preamp;
+        let mut factor = 10f32.powf(gain_db / 20.0);
+
+        if settings.prevent_clipping {
+            if let Some(peak) = peak.filter(|v| *v > 0.0) {
+                factor = factor.min(1.0 / peak);
+            }
+        }
+
+        factor
+    }
+}
+
+/// Parse a `ReplayGain` tag value like "-6.54 dB" or "0.988403"
+fn parse_replaygain_value(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let value = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .unwrap_or(value);
+
+    value.trim().parse().ok()
+}
+
+/// Parse a `R128` gain tag value (a Q7.8 fixed point number) and convert it to be relative to the `ReplayGain` reference level
+fn parse_r128_gain(value: &str) -> Option<f32> {
+    let value: i16 = value.trim().parse().ok()?;
+
+    Some(f32::from(value) / 256.0 + R128_TO_REPLAYGAIN_DB)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MediaType {
     Music,
@@ -104,6 +209,26 @@ impl Track {
             }
         }
 
+        let mut chapters = ep.chapters.clone();
+        if chapters.is_empty() {
+            if let Some(tag) = podcast_localfile
+                .as_ref()
+                .and_then(|v| id3::Tag::read_from_path(v).ok())
+            {
+                chapters = chapter::from_id3(&tag);
+            }
+        }
+        // only fetch the chapters if they are not known yet
+        let chapters_url = ep.chapters_url.clone().filter(|_| chapters.is_empty());
+
+        // the transcript is shown like synced lyrics, it only needs to be fetched if it is not cached yet
+        let parsed_lyric = ep
+            .path
+            .as_deref()
+            .filter(|_| podcast_localfile.is_some())
+            .and_then(transcript::read_cached);
+        let transcript_url = ep.transcript_url.clone().filter(|_| parsed_lyric.is_none());
+
         Self {
             artist: Some("Episode".to_string()),
             album: None,
@@ -117,13 +242,22 @@ impl Track {
             last_modified: SystemTime::now(),
             lyric_frames,
             lyric_selected_index: 0,
-            parsed_lyric: None,
+            parsed_lyric,
             picture: None,
             album_photo: ep.image_url.clone(),
             file_type: None,
             genre: None,
             media_type: MediaType::Podcast,
             podcast_localfile,
+            chapters,
+            chapters_url,
+            transcript_url,
+            replaygain: ReplayGain::default(),
+            year: None,
+            number: None,
+            album_artist: None,
+            composer: None,
+            disc_number: None,
         }
     }
 
@@ -150,7 +284,13 @@ impl Track {
                 song.album = tag.album().map(std::borrow::Cow::into_owned);
                 song.title = tag.title().map(std::borrow::Cow::into_owned);
                 song.genre = tag.genre().map(std::borrow::Cow::into_owned);
+                song.year = tag.year();
+                song.number = tag.track();
+                song.disc_number = tag.disk();
+                song.album_artist = tag.get_string(&ItemKey::AlbumArtist).map(str::to_string);
+                song.composer = tag.get_string(&ItemKey::Composer).map(str::to_string);
                 song.media_type = MediaType::Music;
+                song.replaygain = ReplayGain::from_tag(tag);
 
                 if for_db {
                     return Ok(song);
@@ -164,6 +304,10 @@ impl Track {
                         // let file = MPEGFile::read_from(&mut reader, false)?;
                         let file = MpegFile::read_from(&mut reader, ParseOptions::new())?;
 
+                        if let Ok(tag) = id3::Tag::read_from_path(path) {
+                            song.chapters = chapter::from_id3(&tag);
+                        }
+
                         if let Some(id3v2_tag) = file.id3v2() {
                             for lyrics_frame in id3v2_tag.unsync_text() {
                                 let mut language =
@@ -270,6 +414,15 @@ impl Track {
             genre,
             media_type: MediaType::Music,
             podcast_localfile: None,
+            chapters: Vec::new(),
+            chapters_url: None,
+            transcript_url: None,
+            replaygain: ReplayGain::default(),
+            year: None,
+            number: None,
+            album_artist: None,
+            composer: None,
+            disc_number: None,
         }
     }
 
@@ -333,6 +486,38 @@ impl Track {
         None
     }
 
+    /// Get the chapters of the track, sorted by their start
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    /// Set the chapters of the track, like after they were fetched from [`chapters_url`](Self::chapters_url)
+    pub fn set_chapters(&mut self, chapters: Vec<Chapter>) {
+        self.chapters = chapters;
+        self.chapters_url = None;
+    }
+
+    /// Get the url to fetch the chapters from, if they are not loaded yet
+    pub fn chapters_url(&self) -> Option<&str> {
+        self.chapters_url.as_deref()
+    }
+
+    /// Set the transcript of a podcast episode as the lyric, like after it was fetched from [`transcript_url`](Self::transcript_url)
+    pub fn set_transcript(&mut self, transcript: Lyric) {
+        self.parsed_lyric = Some(transcript);
+        self.transcript_url = None;
+    }
+
+    /// Stop fetching the transcript, like when the fetched one could not be parsed
+    pub fn clear_transcript_url(&mut self) {
+        self.transcript_url = None;
+    }
+
+    /// Get the url to fetch the transcript from, if it is not loaded yet
+    pub fn transcript_url(&self) -> Option<&str> {
+        self.transcript_url.as_deref()
+    }
+
     pub fn lyric_frames_is_empty(&self) -> bool {
         self.lyric_frames.is_empty()
     }
@@ -387,6 +572,52 @@ impl Track {
         self.genre = Some(genre.to_string());
     }
 
+    /// Optionally return the release year of the song
+    pub fn year(&self) -> Option<u32> {
+        self.year
+    }
+
+    pub fn set_year(&mut self, year: Option<u32>) {
+        self.year = year;
+    }
+
+    /// Optionally return the number of the song on its disc
+    pub fn track_number(&self) -> Option<u32> {
+        self.number
+    }
+
+    pub fn set_track_number(&mut self, track_number: Option<u32>) {
+        self.number = track_number;
+    }
+
+    /// Optionally return the number of the disc the song is on
+    pub fn disc_number(&self) -> Option<u32> {
+        self.disc_number
+    }
+
+    pub fn set_disc_number(&mut self, disc_number: Option<u32>) {
+        self.disc_number = disc_number;
+    }
+
+    /// Optionally return the artist of the whole album
+    pub fn album_artist(&self) -> Option<&str> {
+        self.album_artist.as_deref()
+    }
+
+    /// Set the album artist, a empty value removes it
+    pub fn set_album_artist(&mut self, album_artist: &str) {
+        self.album_artist = non_empty(album_artist);
+    }
+
+    pub fn composer(&self) -> Option<&str> {
+        self.composer.as_deref()
+    }
+
+    /// Set the composer, a empty value removes it
+    pub fn set_composer(&mut self, composer: &str) {
+        self.composer = non_empty(composer);
+    }
+
     /// Optionally return the title of the song
     /// If `None` it wasn't able to read the tags
     pub fn title(&self) -> Option<&str> {
@@ -429,6 +660,10 @@ impl Track {
         }
     }
 
+    pub const fn replaygain(&self) -> &ReplayGain {
+        &self.replaygain
+    }
+
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
     }
@@ -437,8 +672,10 @@ impl Track {
         match self.file_type {
             Some(FileType::Mpeg) => {
                 if let Some(file_path) = self.file() {
-                    let mut tag = Id3v2Tag::default();
-                    self.update_tag(&mut tag);
+                    // build a generic tag first, as not all values have accessors on the id3v2 tag
+                    let mut generic_tag = LoftyTag::new(TagType::Id3v2);
+                    self.update_tag(&mut generic_tag);
+                    let mut tag = Id3v2Tag::from(generic_tag);
 
                     if !self.lyric_frames_is_empty() {
                         if let Some(lyric_frames) = self.lyric_frames() {
@@ -547,7 +784,7 @@ impl Track {
         self.picture = Some(picture);
     }
 
-    fn update_tag<T: Accessor>(&self, tag: &mut T) {
+    fn update_tag(&self, tag: &mut LoftyTag) {
         tag.set_artist(
             self.artist()
                 .map_or_else(|| String::from("Unknown Artist"), str::to_string),
@@ -560,9 +797,42 @@ impl Track {
 
         tag.set_album(self.album().map_or_else(String::new, str::to_string));
         tag.set_genre(self.genre().map_or_else(String::new, str::to_string));
+
+        // unset values remove the existing tag, so that a emptied field is not kept
+        match self.year {
+            Some(year) => tag.set_year(year),
+            None => tag.remove_year(),
+        }
+        match self.number {
+            Some(number) => tag.set_track(number),
+            None => tag.remove_track(),
+        }
+        match self.disc_number {
+            Some(disc_number) => tag.set_disk(disc_number),
+            None => tag.remove_disk(),
+        }
+        match self.album_artist() {
+            Some(album_artist) => {
+                tag.insert_text(ItemKey::AlbumArtist, album_artist.to_string());
+            }
+            None => tag.remove_key(&ItemKey::AlbumArtist),
+        }
+        match self.composer() {
+            Some(composer) => {
+                tag.insert_text(ItemKey::Composer, composer.to_string());
+            }
+            None => tag.remove_key(&ItemKey::Composer),
+        }
     }
 }
 
+/// Get `value` without surrounding whitespace, `None` if that is empty
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.trim())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
 fn create_lyrics(tag: &mut LoftyTag, lyric_frames: &mut Vec<Lyrics>) {
     let lyrics = tag.take(&ItemKey::Lyrics);
     for lyric in lyrics {
@@ -576,3 +846,48 @@ fn create_lyrics(tag: &mut LoftyTag, lyric_frames: &mut Vec<Lyrics>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_replaygain_values() {
+        assert_eq!(parse_replaygain_value("-6.54 dB"), Some(-6.54));
+        assert_eq!(parse_replaygain_value("+1.20dB"), Some(1.2));
+        assert_eq!(parse_replaygain_value("0.988403"), Some(0.988_403));
+        assert_eq!(parse_replaygain_value("invalid"), None);
+
+        // -6 dB relative to the R128 reference level is -1 dB relative to the ReplayGain reference level
+        assert_eq!(parse_r128_gain("-1536"), Some(-1.0));
+    }
+
+    #[test]
+    fn should_calculate_replaygain_factor() {
+        let replaygain = ReplayGain {
+            track_gain: Some(6.0),
+            track_peak: Some(0.8),
+            album_gain: Some(-6.0),
+            album_peak: None,
+        };
+        let mut settings = ReplayGainSettings {
+            mode: ReplayGainMode::Off,
+            preamp: 0.0,
+            prevent_clipping: false,
+        };
+        assert!((replaygain.factor(&settings) - 1.0).abs() < f32::EPSILON);
+
+        settings.mode = ReplayGainMode::Album;
+        assert!((replaygain.factor(&settings) - 0.501).abs() < 0.001);
+
+        settings.mode = ReplayGainMode::Track;
+        assert!((replaygain.factor(&settings) - 1.995).abs() < 0.001);
+
+        settings.prevent_clipping = true;
+        assert!((replaygain.factor(&settings) - 1.25).abs() < f32::EPSILON);
+
+        // untagged tracks only get the preamp
+        settings.preamp = -6.0;
+        assert!((ReplayGain::default().factor(&settings) - 0.501).abs() < 0.001);
+    }
+}
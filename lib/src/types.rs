@@ -1,6 +1,6 @@
 use crate::config::v2::tui::{keys::KeyBinding, theme::styles::ColorTermusic};
 use crate::invidious::{Instance, YoutubeVideo};
-use crate::podcast::{EpData, PodcastFeed, PodcastNoId};
+use crate::podcast::{DownloadProgress, EpData, PodcastFeed, PodcastNoId};
 use crate::songtag::SongTag;
 use anyhow::{anyhow, Result};
 use image::DynamicImage;
@@ -14,6 +14,7 @@ pub enum Msg {
     DeleteConfirmCloseOk,
     DeleteConfirmShow,
     Download(DLMsg),
+    Equalizer(EQMsg),
     ErrorPopupClose,
     GeneralSearch(GSMsg),
     HelpPopupShow,
@@ -33,11 +34,14 @@ pub enum Msg {
     PlayerSpeedDown,
     PlayerSeekForward,
     PlayerSeekBackward,
+    PlayerChapterNext,
+    PlayerChapterPrevious,
     Playlist(PLMsg),
     Podcast(PCMsg),
     QuitPopupCloseCancel,
     QuitPopupCloseOk,
     QuitPopupShow,
+    SavedPlaylists(SPMsg),
     SavePlaylistPopupShow,
     SavePlaylistPopupCloseCancel,
     SavePlaylistPopupUpdate(String),
@@ -77,6 +81,37 @@ pub enum DLMsg {
     FetchPhotoErr(String),
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EQMsg {
+    PopupShow,
+    PopupClose,
+    ToggleEnabled,
+    NextPreset,
+    /// Raise the gain of the band at the index
+    GainUp(usize),
+    /// Lower the gain of the band at the index
+    GainDown(usize),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SPMsg {
+    PopupShow,
+    PopupClose,
+    /// Replace the queue with the saved playlist at the index
+    Load(usize),
+    /// Add the saved playlist at the index to the end of the queue
+    Append(usize),
+    /// Add the queue to the end of the saved playlist at the index
+    AddQueue(usize),
+    Delete(usize),
+    /// Ask for a name to save the queue as a new playlist
+    CreateShow,
+    /// Ask for a new name for the saved playlist at the index
+    RenameShow(usize),
+    NameInputCancel,
+    NameInputOk(String),
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LyricMsg {
     LyricTextAreaBlurUp,
@@ -91,6 +126,8 @@ pub enum ConfigEditorMsg {
     PodcastSimulDownloadBlurUp,
     PodcastMaxRetriesBlurDown,
     PodcastMaxRetriesBlurUp,
+    PlaylistColumnsBlurDown,
+    PlaylistColumnsBlurUp,
 
     AlbumPhotoAlignBlurDown,
     AlbumPhotoAlignBlurUp,
@@ -341,7 +378,10 @@ pub enum DBMsg {
     AddPlaylist(usize),
     CriteriaBlurDown,
     CriteriaBlurUp,
+    CycleSort,
     SearchResult(usize),
+    /// Go back up one level after drilling down
+    SearchResultBack,
     SearchResultBlurDown,
     SearchResultBlurUp,
     SearchTrack(usize),
@@ -358,6 +398,10 @@ pub enum PCMsg {
     PodcastAddPopupShow,
     PodcastAddPopupCloseOk(String),
     PodcastAddPopupCloseCancel,
+    /// Edit the playback settings of the selected feed
+    PlaybackSettingsPopupShow,
+    PlaybackSettingsPopupCloseOk(String),
+    PlaybackSettingsPopupCloseCancel,
     SyncData((i64, PodcastNoId)),
     NewData(PodcastNoId),
     Error(String, PodcastFeed),
@@ -370,12 +414,20 @@ pub enum PCMsg {
     PodcastRefreshAll,
     FetchPodcastStart(String),
     EpisodeDownload(usize),
+    /// Cancel the running download of the episode at INDEX
+    EpisodeDownloadCancel(usize),
     DLStart(EpData),
+    DLProgress(EpData, DownloadProgress),
     DLComplete(EpData),
+    DLCancelled(EpData),
     DLResponseError(EpData),
     DLFileCreateError(EpData),
     DLFileWriteError(EpData),
+    /// The episode is already being downloaded by another process, like the server
+    DLAlreadyRunning(EpData),
     EpisodeDeleteFile(usize),
+    /// The transcript TEXT of the episode with the url FILE was fetched
+    TranscriptFetched(String, String),
     FeedDeleteShow,
     FeedDeleteCloseOk,
     FeedDeleteCloseCancel,
@@ -419,6 +471,14 @@ pub enum PLMsg {
     AddRandomAlbum,
     /// Start choosing random tracks to be added to the playlist
     AddRandomTracks,
+    /// Move INDEX to be played directly after the current track
+    PlayNext(usize),
+    /// Add INDEX to the end of the "up next" tracks
+    AddUpNext(usize),
+    /// Undo the last change to the playlist
+    Undo,
+    /// Redo the last undone change to the playlist
+    Redo,
 }
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GSMsg {
@@ -480,6 +540,16 @@ pub enum TFMsg {
     InputAlbumBlurUp,
     InputGenreBlurDown,
     InputGenreBlurUp,
+    InputAlbumArtistBlurDown,
+    InputAlbumArtistBlurUp,
+    InputComposerBlurDown,
+    InputComposerBlurUp,
+    InputYearBlurDown,
+    InputYearBlurUp,
+    InputTrackNumberBlurDown,
+    InputTrackNumberBlurUp,
+    InputDiscNumberBlurDown,
+    InputDiscNumberBlurUp,
     SelectLyricBlurDown,
     SelectLyricBlurUp,
     TableLyricOptionsBlurDown,
@@ -498,6 +568,7 @@ pub enum Id {
     DeleteConfirmInputPopup,
     DownloadSpinner,
     Episode,
+    EqualizerPopup,
     ErrorPopup,
     GeneralSearchInput,
     GeneralSearchTable,
@@ -510,6 +581,7 @@ pub enum Id {
     Playlist,
     Podcast,
     PodcastAddPopup,
+    PodcastPlaybackSettingsPopup,
     PodcastSearchTablePopup,
     FeedDeleteConfirmRadioPopup,
     FeedDeleteConfirmInputPopup,
@@ -518,6 +590,8 @@ pub enum Id {
     SavePlaylistPopup,
     SavePlaylistLabel,
     SavePlaylistConfirm,
+    SavedPlaylistsPopup,
+    SavedPlaylistNamePopup,
     TagEditor(IdTagEditor),
     YoutubeSearchInputPopup,
     YoutubeSearchTablePopup,
@@ -531,6 +605,11 @@ pub enum IdTagEditor {
     InputTitle,
     InputAlbum,
     InputGenre,
+    InputAlbumArtist,
+    InputComposer,
+    InputYear,
+    InputTrackNumber,
+    InputDiscNumber,
     SelectLyric,
     TableLyricOptions,
     TextareaLyric,
@@ -566,6 +645,7 @@ pub enum IdConfigEditor {
 
     PlaylistBackground,
     PlaylistBorder,
+    PlaylistColumns,
     PlaylistDisplaySymbol,
     PlaylistForeground,
     PlaylistHighlight,
@@ -0,0 +1,95 @@
+//! Optional shared-secret token authentication for the gRPC communication
+//!
+//! The token is sent as `authorization: Bearer <token>` metadata on every request.
+
+use anyhow::{Context, Result};
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// The metadata key the token is sent in
+pub const AUTH_METADATA_KEY: &str = "authorization";
+
+/// Client-side interceptor that adds the token to every request, if a token is set
+#[derive(Debug, Clone, Default)]
+pub struct TokenInterceptor {
+    value: Option<MetadataValue<Ascii>>,
+}
+
+impl TokenInterceptor {
+    /// Create a new interceptor, errors if the token contains characters not valid in metadata
+    pub fn new(token: Option<&str>) -> Result<Self> {
+        let value = token
+            .map(|token| format!("Bearer {token}").parse())
+            .transpose()
+            .context("token contains invalid characters")?;
+
+        Ok(Self { value })
+    }
+}
+
+impl Interceptor for TokenInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(ref value) = self.value {
+            request
+                .metadata_mut()
+                .insert(AUTH_METADATA_KEY, value.clone());
+        }
+
+        Ok(request)
+    }
+}
+
+/// Server-side check that the request has the expected token
+///
+/// # Errors
+///
+/// [`Status::unauthenticated`] if the token is missing or does not match
+pub fn check_token(expected: &str, request: &Request<()>) -> Result<(), Status> {
+    let given = request
+        .metadata()
+        .get(AUTH_METADATA_KEY)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match given {
+        Some(given) if constant_time_eq(given.as_bytes(), expected.as_bytes()) => Ok(()),
+        Some(_) => Err(Status::unauthenticated("Invalid token")),
+        None => Err(Status::unauthenticated("Missing token")),
+    }
+}
+
+/// Compare without returning early, so the token cannot be guessed by timing the responses
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_accept_matching_token() {
+        let mut interceptor = TokenInterceptor::new(Some("secret")).unwrap();
+        let request = interceptor.call(Request::new(())).unwrap();
+
+        assert!(check_token("secret", &request).is_ok());
+    }
+
+    #[test]
+    fn should_reject_missing_or_wrong_token() {
+        let mut interceptor = TokenInterceptor::new(None).unwrap();
+        let request = interceptor.call(Request::new(())).unwrap();
+        let err = check_token("secret", &request).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        let mut interceptor = TokenInterceptor::new(Some("wrong")).unwrap();
+        let request = interceptor.call(Request::new(())).unwrap();
+        let err = check_token("secret", &request).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
+}
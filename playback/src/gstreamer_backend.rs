@@ -36,6 +36,7 @@ use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
+use termusiclib::config::v2::server::{ReplayGainMode, ReplayGainSettings};
 use termusiclib::config::ServerOverlay;
 use termusiclib::track::{MediaType, Track};
 
@@ -183,6 +184,8 @@ pub struct GStreamerBackend {
     gapless: bool,
     message_tx: async_channel::Sender<PlayerInternalCmd>,
     media_title: Arc<Mutex<String>>,
+    /// The `rgvolume` element, only present if ReplayGain was enabled on startup
+    replaygain: Option<Element>,
     _bus_watch_guard: BusWatchGuard,
 }
 
@@ -260,7 +263,34 @@ impl GStreamerBackend {
         gst::Element::link_many([&tempo, &sink]).expect("link many failed");
         tempo.sync_state_with_parent().expect("sync state failed");
 
-        let pad = tempo
+        // "rgvolume" cannot be disabled, so only add it if ReplayGain is wanted
+        let replaygain_settings = config.settings.player.replaygain;
+        let replaygain = if replaygain_settings.mode == ReplayGainMode::Off {
+            None
+        } else {
+            match gst::ElementFactory::make("rgvolume")
+                .name("replaygain")
+                .build()
+            {
+                Ok(rgvolume) => {
+                    set_replaygain_properties(&rgvolume, &replaygain_settings);
+                    bin.add(&rgvolume).expect("add rgvolume failed");
+                    rgvolume.link(&tempo).expect("link rgvolume failed");
+                    rgvolume
+                        .sync_state_with_parent()
+                        .expect("sync state failed");
+                    Some(rgvolume)
+                }
+                Err(err) => {
+                    error!("ReplayGain is enabled, but \"rgvolume\" could not be created: {err}");
+                    None
+                }
+            }
+        };
+
+        let pad = replaygain
+            .as_ref()
+            .unwrap_or(&tempo)
             .static_pad("sink")
             .expect("Failed to get a static pad from equalizer.");
 
@@ -399,6 +429,7 @@ impl GStreamerBackend {
             gapless,
             message_tx,
             media_title,
+            replaygain,
             _bus_watch_guard: bus_watch,
         };
 
@@ -416,6 +447,32 @@ impl GStreamerBackend {
 
         this
     }
+
+    /// Change the ReplayGain settings
+    ///
+    /// Enabling or disabling ReplayGain requires a restart, as the element is only added on startup
+    pub fn set_replaygain(&self, settings: ReplayGainSettings) {
+        match (&self.replaygain, settings.mode) {
+            (Some(_), ReplayGainMode::Off)
+            | (None, ReplayGainMode::Track | ReplayGainMode::Album) => {
+                warn!("Enabling or disabling ReplayGain requires a restart with the GStreamer backend");
+            }
+            (Some(rgvolume), _) => set_replaygain_properties(rgvolume, &settings),
+            (None, ReplayGainMode::Off) => (),
+        }
+    }
+}
+
+/// Apply the ReplayGain settings to the `rgvolume` element
+fn set_replaygain_properties(rgvolume: &Element, settings: &ReplayGainSettings) {
+    let preamp = f64::from(settings.preamp);
+    rgvolume.set_property("album-mode", settings.mode == ReplayGainMode::Album);
+    rgvolume.set_property("pre-amp", preamp);
+    // "pre-amp" is only applied to tagged streams
+    rgvolume.set_property("fallback-gain", preamp);
+    // a headroom of 0 limits the gain so that the peak does not clip, 60 is the maximum
+    let headroom: f64 = if settings.prevent_clipping { 0.0 } else { 60.0 };
+    rgvolume.set_property("headroom", headroom);
 }
 
 #[async_trait]
@@ -550,6 +607,11 @@ impl PlayerTrait for GStreamerBackend {
         set_uri_from_track(&self.playbin, track);
     }
 
+    fn requeue_next(&mut self, track: &Track) {
+        // the playbin only uses the last set uri once the current track ends
+        self.enqueue_next(track);
+    }
+
     fn media_info(&self) -> MediaInfo {
         let media_title_r = self.media_title.lock();
         if media_title_r.is_empty() {
@@ -46,6 +46,201 @@ pub mod player {
             }
         }
     }
+
+    impl From<&termusiclib::track::Track> for PlaylistTrack {
+        fn from(value: &termusiclib::track::Track) -> Self {
+            use termusiclib::track::MediaType;
+
+            Self {
+                file: value.file().unwrap_or_default().to_string(),
+                title: value.title().unwrap_or_default().to_string(),
+                artist: value.artist().unwrap_or_default().to_string(),
+                album: value.album().unwrap_or_default().to_string(),
+                duration: Some(value.duration().into()),
+                media_type: match value.media_type {
+                    MediaType::Music => 0,
+                    MediaType::Podcast => 1,
+                    MediaType::LiveRadio => 2,
+                },
+            }
+        }
+    }
+
+    impl From<&termusiclib::library_db::TrackDB> for PlaylistTrack {
+        fn from(value: &termusiclib::library_db::TrackDB) -> Self {
+            Self {
+                file: value.file.clone(),
+                title: value.title.clone(),
+                artist: value.artist.clone(),
+                album: value.album.clone(),
+                duration: Some(value.duration.into()),
+                // the library database only contains music
+                media_type: 0,
+            }
+        }
+    }
+
+    impl From<&termusiclib::config::v2::server::EqualizerBand> for EqualizerBand {
+        fn from(value: &termusiclib::config::v2::server::EqualizerBand) -> Self {
+            use termusiclib::config::v2::server::EqualizerFilter;
+
+            Self {
+                kind: match value.kind {
+                    EqualizerFilter::Peaking => 0,
+                    EqualizerFilter::LowShelf => 1,
+                    EqualizerFilter::HighShelf => 2,
+                },
+                frequency: value.frequency,
+                gain: value.gain,
+                q: value.q,
+            }
+        }
+    }
+
+    impl From<EqualizerBand> for termusiclib::config::v2::server::EqualizerBand {
+        fn from(value: EqualizerBand) -> Self {
+            use termusiclib::config::v2::server::EqualizerFilter;
+
+            Self {
+                kind: match value.kind {
+                    1 => EqualizerFilter::LowShelf,
+                    2 => EqualizerFilter::HighShelf,
+                    /* 0 | */ _ => EqualizerFilter::Peaking,
+                },
+                frequency: value.frequency,
+                gain: value.gain,
+                q: value.q,
+            }
+        }
+    }
+
+    impl From<&termusiclib::config::v2::server::EqualizerSettings> for Equalizer {
+        fn from(value: &termusiclib::config::v2::server::EqualizerSettings) -> Self {
+            Self {
+                enabled: value.enabled,
+                preset: value.preset.clone(),
+                presets: value.presets.keys().cloned().collect(),
+                bands: value
+                    .preset_bands()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(Into::into)
+                    .collect(),
+            }
+        }
+    }
+
+    impl From<&crate::SavedPlaylistInfo> for SavedPlaylistInfo {
+        fn from(value: &crate::SavedPlaylistInfo) -> Self {
+            Self {
+                name: value.name.clone(),
+                track_count: value.track_count as u64,
+            }
+        }
+    }
+
+    impl From<SavedPlaylistInfo> for crate::SavedPlaylistInfo {
+        fn from(value: SavedPlaylistInfo) -> Self {
+            Self {
+                name: value.name,
+                track_count: usize::try_from(value.track_count).unwrap_or(usize::MAX),
+            }
+        }
+    }
+
+    impl From<crate::UpdateEvents> for StreamUpdates {
+        fn from(value: crate::UpdateEvents) -> Self {
+            use crate::UpdateEvents;
+            use stream_updates::Type;
+
+            let r#type = match value {
+                UpdateEvents::MissedEvents { amount } => {
+                    Type::MissedEvents(UpdateMissedEvents { amount })
+                }
+                UpdateEvents::TrackChanged(info) => Type::TrackChanged(UpdateTrackChanged {
+                    current_track_index: info.current_track_index,
+                    title: info.title.unwrap_or_default(),
+                    progress: info.progress.map(Into::into),
+                }),
+                UpdateEvents::StatusChanged { status } => {
+                    Type::StatusChanged(UpdateStatusChanged {
+                        status: status.as_u32(),
+                    })
+                }
+                UpdateEvents::VolumeChanged { volume } => {
+                    Type::VolumeChanged(UpdateVolumeChanged {
+                        volume: u32::from(volume),
+                    })
+                }
+                UpdateEvents::SpeedChanged { speed } => {
+                    Type::SpeedChanged(UpdateSpeedChanged { speed })
+                }
+                UpdateEvents::GaplessChanged { gapless } => {
+                    Type::GaplessChanged(UpdateGaplessChanged { gapless })
+                }
+                UpdateEvents::PlaylistChanged => Type::PlaylistChanged(UpdatePlaylistChanged {}),
+                UpdateEvents::RadioTitleChanged { title } => {
+                    Type::RadioTitleChanged(UpdateRadioTitleChanged { title })
+                }
+                UpdateEvents::Progress(progress) => Type::Progress(UpdateProgress {
+                    progress: Some(progress.into()),
+                }),
+                UpdateEvents::LibraryChanged => Type::LibraryChanged(UpdateLibraryChanged {}),
+                UpdateEvents::SavedPlaylistsChanged => {
+                    Type::SavedPlaylistsChanged(UpdateSavedPlaylistsChanged {})
+                }
+                UpdateEvents::PodcastsChanged => Type::PodcastsChanged(UpdatePodcastsChanged {}),
+                UpdateEvents::ChapterChanged { title } => {
+                    Type::ChapterChanged(UpdateChapterChanged { title })
+                }
+            };
+
+            Self {
+                r#type: Some(r#type),
+            }
+        }
+    }
+
+    impl TryFrom<StreamUpdates> for crate::UpdateEvents {
+        type Error = anyhow::Error;
+
+        fn try_from(value: StreamUpdates) -> Result<Self, Self::Error> {
+            use stream_updates::Type;
+
+            let Some(r#type) = value.r#type else {
+                anyhow::bail!("StreamUpdates did not contain a event type");
+            };
+
+            let ev = match r#type {
+                Type::MissedEvents(ev) => Self::MissedEvents { amount: ev.amount },
+                Type::TrackChanged(ev) => Self::TrackChanged(crate::TrackChangedInfo {
+                    current_track_index: ev.current_track_index,
+                    title: Some(ev.title).filter(|v| !v.is_empty()),
+                    progress: ev.progress.map(Into::into),
+                }),
+                Type::StatusChanged(ev) => Self::StatusChanged {
+                    status: crate::Status::from_u32(ev.status),
+                },
+                Type::VolumeChanged(ev) => Self::VolumeChanged {
+                    // clamped to u16::MAX, also send is a u16, but protobuf does not support u16 directly
+                    volume: ev.volume.min(u32::from(u16::MAX)) as u16,
+                },
+                Type::SpeedChanged(ev) => Self::SpeedChanged { speed: ev.speed },
+                Type::GaplessChanged(ev) => Self::GaplessChanged {
+                    gapless: ev.gapless,
+                },
+                Type::PlaylistChanged(_) => Self::PlaylistChanged,
+                Type::RadioTitleChanged(ev) => Self::RadioTitleChanged { title: ev.title },
+                Type::Progress(ev) => Self::Progress(ev.progress.unwrap_or_default().into()),
+                Type::LibraryChanged(_) => Self::LibraryChanged,
+                Type::SavedPlaylistsChanged(_) => Self::SavedPlaylistsChanged,
+                Type::PodcastsChanged(_) => Self::PodcastsChanged,
+                Type::ChapterChanged(ev) => Self::ChapterChanged { title: ev.title },
+            };
+
+            Ok(ev)
+        }
+    }
 }
 
 #[cfg(feature = "gst")]
@@ -55,22 +250,29 @@ mod mpv_backend;
 #[cfg(feature = "rusty")]
 mod rusty_backend;
 
+pub mod auth;
 mod discord;
 mod mpris;
 pub mod playlist;
+pub mod saved_playlists;
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 pub use playlist::{Playlist, Status};
+pub use saved_playlists::{SavedPlaylistCmd, SavedPlaylistInfo, SavedPlaylists};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
+use termusiclib::chapter::{self, Chapter};
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
+use termusiclib::config::v2::server::{EqualizerSettings, ReplayGainSettings};
 use termusiclib::config::{new_shared_server_settings, ServerOverlay, SharedServerSettings};
 use termusiclib::library_db::DataBase;
 use termusiclib::podcast::db::Database as DBPod;
+use termusiclib::podcast::{self, PodcastPlaybackSettings};
 use termusiclib::track::{MediaType, Track};
 use termusiclib::utils::get_app_config_path;
 use tokio::runtime::Handle;
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 #[macro_use]
@@ -108,6 +310,8 @@ pub enum Backend {
 
 pub type PlayerCmdReciever = UnboundedReceiver<PlayerCmd>;
 pub type PlayerCmdSender = UnboundedSender<PlayerCmd>;
+/// Sender for [`UpdateEvents`], each subscribed client has its own receiver
+pub type StreamTX = broadcast::Sender<UpdateEvents>;
 
 impl Backend {
     /// Create a new Backend based on `backend`([`BackendSelect`])
@@ -183,15 +387,117 @@ impl Backend {
             Backend::GStreamer(v) => v,
         }
     }
+
+    /// Apply new ReplayGain settings to the backend
+    pub fn set_replaygain(&self, settings: ReplayGainSettings) {
+        match self {
+            #[cfg(feature = "mpv")]
+            Backend::Mpv(v) => v.set_replaygain(settings),
+            #[cfg(feature = "rusty")]
+            Backend::Rusty(v) => v.set_replaygain(settings),
+            #[cfg(feature = "gst")]
+            Backend::GStreamer(v) => v.set_replaygain(settings),
+        }
+    }
+
+    /// Apply new equalizer settings to the backend, only supported by the rusty backend
+    pub fn set_equalizer(&self, settings: &EqualizerSettings) {
+        match self {
+            #[cfg(feature = "mpv")]
+            Backend::Mpv(_) => {
+                if settings.enabled {
+                    warn!("The equalizer is only supported by the rusty backend");
+                }
+            }
+            #[cfg(feature = "rusty")]
+            Backend::Rusty(v) => v.set_equalizer(settings.active_bands().to_vec()),
+            #[cfg(feature = "gst")]
+            Backend::GStreamer(_) => {
+                if settings.enabled {
+                    warn!("The equalizer is only supported by the rusty backend");
+                }
+            }
+        }
+    }
+
+    /// Apply a new crossfade duration to the backend, only supported by the rusty backend
+    pub fn set_crossfade(&self, duration: Duration) {
+        match self {
+            #[cfg(feature = "mpv")]
+            Backend::Mpv(_) => {
+                if !duration.is_zero() {
+                    warn!("Crossfade is only supported by the rusty backend");
+                }
+            }
+            #[cfg(feature = "rusty")]
+            Backend::Rusty(v) => v.set_crossfade(duration),
+            #[cfg(feature = "gst")]
+            Backend::GStreamer(_) => {
+                if !duration.is_zero() {
+                    warn!("Crossfade is only supported by the rusty backend");
+                }
+            }
+        }
+    }
+
+    /// Whether the backend can crossfade between tracks
+    #[must_use]
+    pub fn supports_crossfade(&self) -> bool {
+        match self {
+            #[cfg(feature = "mpv")]
+            Backend::Mpv(_) => false,
+            #[cfg(feature = "rusty")]
+            Backend::Rusty(_) => true,
+            #[cfg(feature = "gst")]
+            Backend::GStreamer(_) => false,
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum PlayerCmd {
     AboutToFinish,
+    /// Seek to the start of the next chapter of the current track
+    ChapterNext,
+    /// Seek to the start of the current chapter, or the previous one if the current one just started
+    ChapterPrevious,
+    /// The chapters of the podcast episode `file` have been fetched from its chapters url
+    ChaptersFetched {
+        file: String,
+        chapters: Vec<Chapter>,
+    },
     CycleLoop,
     Eos,
     GetProgress,
     PlaySelected,
+    /// Play the track at the given playlist index
+    PlayIndex(usize),
+    /// Insert the given paths or urls at the index, appending if the index is past the end
+    PlaylistAddTracks {
+        at_index: usize,
+        tracks: Vec<String>,
+    },
+    /// Insert the given paths or urls directly after the current track
+    PlaylistPlayNext(Vec<String>),
+    /// Add the given paths or urls to the end of the "up next" tracks
+    PlaylistAddUpNext(Vec<String>),
+    PlaylistRemoveTrack(usize),
+    PlaylistSwapTracks(usize, usize),
+    PlaylistMoveTrack {
+        from: usize,
+        to: usize,
+    },
+    PlaylistClear,
+    /// Add the paths or urls of a saved playlist to the end, clearing the playlist first if `replace` is set
+    PlaylistLoad {
+        tracks: Vec<String>,
+        replace: bool,
+    },
+    PlaylistShuffle,
+    /// Undo the last change to the playlist
+    PlaylistUndo,
+    /// Redo the last undone change to the playlist
+    PlaylistRedo,
     SkipPrevious,
     Pause,
     Play,
@@ -199,8 +505,18 @@ pub enum PlayerCmd {
     Quit,
     ReloadConfig,
     ReloadPlaylist,
+    /// Only used by clients to request a saved playlist operation from the server
+    SavedPlaylist(SavedPlaylistCmd),
     SeekBackward,
     SeekForward,
+    /// Seek to a absolute position in the current track
+    SeekTo(Duration),
+    /// Switch the audio output device, `None` for the default device
+    SetOutputDevice(Option<String>),
+    /// Replace the equalizer settings
+    SetEqualizer(EqualizerSettings),
+    SetSpeed(Speed),
+    SetVolume(Volume),
     SkipNext,
     SpeedDown,
     SpeedUp,
@@ -211,6 +527,67 @@ pub enum PlayerCmd {
     VolumeUp,
 }
 
+/// Events pushed to all clients subscribed via `SubscribeServerUpdates`
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateEvents {
+    /// The receiver was too slow and missed `amount` events
+    MissedEvents {
+        amount: u64,
+    },
+    /// A new track started playing
+    TrackChanged(TrackChangedInfo),
+    /// The Playback status changed (like running -> paused)
+    StatusChanged {
+        status: Status,
+    },
+    VolumeChanged {
+        volume: Volume,
+    },
+    SpeedChanged {
+        speed: Speed,
+    },
+    GaplessChanged {
+        gapless: bool,
+    },
+    /// The tracks in the playlist have been modified
+    PlaylistChanged,
+    /// The title of the currently playing radio stream changed
+    RadioTitleChanged {
+        title: String,
+    },
+    /// Regular progress tick
+    Progress(PlayerProgress),
+    /// Tracks in the library database were added, updated or removed
+    LibraryChanged,
+    /// A saved playlist was created, modified, renamed or deleted
+    SavedPlaylistsChanged,
+    /// Podcast feeds were refreshed or episodes downloaded / removed in the podcast database
+    PodcastsChanged,
+    /// The chapter of the current track changed, empty if there is no chapter
+    ChapterChanged {
+        title: String,
+    },
+}
+
+/// Data for [`UpdateEvents::TrackChanged`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackChangedInfo {
+    /// Index of the new track in the playlist
+    pub current_track_index: u64,
+    /// Title of the new track, if there is one
+    pub title: Option<String>,
+    /// Progress of the new track, if known at the time
+    pub progress: Option<PlayerProgress>,
+}
+
+/// The currently playing music track, to be recorded in the play history once it changes
+#[derive(Debug, Clone)]
+struct CurrentPlay {
+    file: String,
+    duration: Duration,
+    started_at: SystemTime,
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub struct GeneralPlayer {
     pub backend: Backend,
@@ -222,6 +599,10 @@ pub struct GeneralPlayer {
     pub db: DataBase,
     pub db_podcast: DBPod,
     pub cmd_tx: PlayerCmdSender,
+    pub stream_tx: StreamTX,
+    current_play: Option<CurrentPlay>,
+    /// The playback settings of the podcast the current episode belongs to, `None` if the current track is not a episode
+    podcast_playback: Option<PodcastPlaybackSettings>,
 }
 
 impl GeneralPlayer {
@@ -235,6 +616,7 @@ impl GeneralPlayer {
         backend: BackendSelect,
         config: ServerOverlay,
         cmd_tx: PlayerCmdSender,
+        stream_tx: StreamTX,
     ) -> Result<Self> {
         let backend = Backend::new_select(backend, &config, cmd_tx.clone());
 
@@ -265,7 +647,10 @@ impl GeneralPlayer {
             db,
             db_podcast,
             cmd_tx,
+            stream_tx,
             current_track_updated: false,
+            current_play: None,
+            podcast_playback: None,
         })
     }
 
@@ -275,8 +660,12 @@ impl GeneralPlayer {
     ///
     /// - if connecting to the database fails
     /// - if config path creation fails
-    pub fn new(config: ServerOverlay, cmd_tx: PlayerCmdSender) -> Result<Self> {
-        Self::new_backend(BackendSelect::Default, config, cmd_tx)
+    pub fn new(
+        config: ServerOverlay,
+        cmd_tx: PlayerCmdSender,
+        stream_tx: StreamTX,
+    ) -> Result<Self> {
+        Self::new_backend(BackendSelect::Default, config, cmd_tx, stream_tx)
     }
 
     /// Reload the config from file, on fail continue to use the old
@@ -290,6 +679,14 @@ impl GeneralPlayer {
         let parsed = ServerConfigVersionedDefaulted::from_config_path()?.into_settings();
         config.settings = parsed;
 
+        self.backend
+            .set_replaygain(config.settings.player.replaygain);
+        self.backend
+            .set_equalizer(&config.settings.player.equalizer);
+        self.backend.set_crossfade(Duration::from_millis(
+            config.settings.player.crossfade_ms.into(),
+        ));
+
         if config.settings.player.use_mediacontrols && self.mpris.is_none() {
             // start mpris if new config has it enabled, but is not active yet
             let mut mpris = mpris::Mpris::new(self.cmd_tx.clone());
@@ -325,6 +722,26 @@ impl GeneralPlayer {
         Ok(())
     }
 
+    /// Send a [`UpdateEvents`] to all currently subscribed clients
+    pub fn send_stream_ev(&self, ev: UpdateEvents) {
+        // sending only fails if there are no receivers, in which case there is nobody to notify
+        let _ = self.stream_tx.send(ev);
+    }
+
+    /// Send [`UpdateEvents::TrackChanged`] for the current track
+    fn send_stream_track_changed(&self) {
+        let title = self
+            .playlist
+            .current_track()
+            .and_then(|track| track.title().map(ToString::to_string));
+
+        self.send_stream_ev(UpdateEvents::TrackChanged(TrackChangedInfo {
+            current_track_index: self.playlist.get_current_track_index() as u64,
+            title,
+            progress: self.get_progress(),
+        }));
+    }
+
     fn get_player(&self) -> &dyn PlayerTrait {
         self.backend.as_player()
     }
@@ -340,12 +757,57 @@ impl GeneralPlayer {
         new_gapless
     }
 
+    /// Switch the audio output device at runtime, `None` for the default device
+    ///
+    /// Only supported by the rusty backend.
+    /// Requires that the function is called on a thread with a entered tokio runtime
+    ///
+    /// # Errors
+    ///
+    /// if the current backend does not support selecting a output device
+    #[cfg_attr(not(feature = "rusty"), allow(unused_variables))]
+    pub fn set_output_device(&mut self, device: Option<String>) -> Result<()> {
+        #[cfg(feature = "rusty")]
+        #[allow(irrefutable_let_patterns)]
+        if let Backend::Rusty(ref mut backend) = self.backend {
+            let position = backend.position();
+            backend.set_output_device(device);
+
+            // the old sink is dropped with the old device, so the current track needs to be started again
+            if !self.playlist.is_stopped() {
+                if let Some(track) = self.playlist.current_track().cloned() {
+                    self.playlist.set_next_track(None);
+                    Handle::current().block_on(backend.add_and_play(&track));
+                    if let Some(position) = position {
+                        backend.seek_to(position);
+                    }
+                    if self.playlist.is_paused() {
+                        backend.pause();
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+
+        anyhow::bail!("Selecting a output device is only supported by the rusty backend");
+    }
+
+    /// Apply and store the new equalizer settings
+    pub fn set_equalizer(&mut self, settings: EqualizerSettings) {
+        self.backend.set_equalizer(&settings);
+        self.config.write().settings.player.equalizer = settings;
+    }
+
     /// Requires that the function is called on a thread with a entered tokio runtime
     pub fn start_play(&mut self) {
         if self.playlist.is_stopped() | self.playlist.is_paused() {
             self.playlist.set_status(Status::Running);
         }
 
+        // a play that was not finished by a skip or stop has played to the end
+        self.record_play(false);
+
         self.playlist.proceed();
 
         if let Some(track) = self.playlist.current_track() {
@@ -353,6 +815,17 @@ impl GeneralPlayer {
 
             info!("Starting Track {:#?}", track);
 
+            self.current_play = match (track.media_type, track.file()) {
+                (MediaType::Music, Some(file)) => Some(CurrentPlay {
+                    file: file.to_string(),
+                    duration: track.duration(),
+                    started_at: SystemTime::now(),
+                }),
+                _ => None,
+            };
+            self.podcast_playback = self.get_podcast_playback(&track);
+            self.apply_podcast_speed();
+
             if self.playlist.has_next_track() {
                 self.playlist.set_next_track(None);
                 self.current_track_updated = true;
@@ -363,6 +836,9 @@ impl GeneralPlayer {
                     backend.message_on_end();
                 }
                 self.add_and_play_mpris_discord();
+                self.skip_podcast_intro();
+                self.send_stream_track_changed();
+                self.fetch_current_chapters();
                 return;
             }
 
@@ -373,13 +849,163 @@ impl GeneralPlayer {
             Handle::current().block_on(wait);
 
             self.add_and_play_mpris_discord();
-            self.player_restore_last_position();
+            if !self.player_restore_last_position() {
+                self.skip_podcast_intro();
+            }
             #[cfg(feature = "rusty")]
             #[allow(irrefutable_let_patterns)]
             if let Backend::Rusty(ref mut backend) = self.backend {
                 backend.message_on_end();
             }
+            self.send_stream_track_changed();
+            self.fetch_current_chapters();
+        }
+    }
+
+    /// Get the playback settings of the podcast `track` belongs to, if it is a episode
+    fn get_podcast_playback(&self, track: &Track) -> Option<PodcastPlaybackSettings> {
+        if track.media_type != MediaType::Podcast {
+            return None;
+        }
+
+        match self
+            .db_podcast
+            .get_playback_settings_for_episode(track.file()?)
+        {
+            Ok(settings) => settings,
+            Err(err) => {
+                error!(
+                    "Getting podcast playback settings failed, Error: {:#?}",
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    /// Set the speed of the current podcast, or the global speed if there is none
+    fn apply_podcast_speed(&mut self) {
+        let global_speed = self.config.read().settings.player.speed;
+        let speed = self
+            .podcast_playback
+            .and_then(|v| v.speed)
+            .unwrap_or(global_speed);
+
+        if self.speed() != speed {
+            info!("Setting speed to {speed} for the current track");
+            self.set_speed(speed);
+        }
+    }
+
+    /// Get whether the current speed is set by the current podcast instead of the global speed
+    ///
+    /// Speed changes while this is the case should not be saved to the config.
+    pub fn is_podcast_speed_active(&self) -> bool {
+        self.podcast_playback.is_some_and(|v| v.speed.is_some())
+    }
+
+    /// Seek past the intro of the current episode, if its podcast has one set
+    fn skip_podcast_intro(&mut self) {
+        let Some(skip_intro) = self.podcast_playback.map(|v| v.skip_intro) else {
+            return;
+        };
+        if skip_intro.is_zero() {
+            return;
+        }
+
+        info!("Skipping podcast intro of {}s", skip_intro.as_secs());
+        self.seek_to(skip_intro);
+    }
+
+    /// Get whether the current episode reached the outro that its podcast wants to skip
+    ///
+    /// This only returns `true` once per episode, so that the outro can still be played by seeking back into it.
+    pub fn is_podcast_outro_reached(&mut self) -> bool {
+        let Some(settings) = self.podcast_playback.as_mut() else {
+            return false;
+        };
+        let Some(progress) = self.get_player().get_progress() else {
+            return false;
+        };
+        let (Some(position), Some(duration)) = (progress.position, progress.total_duration) else {
+            return false;
+        };
+
+        if !settings.is_in_outro(position, duration) {
+            return false;
+        }
+        settings.skip_outro = Duration::ZERO;
+
+        true
+    }
+
+    /// Fetch the chapters of the current track in the background, if it has a chapters url
+    ///
+    /// Once fetched, [`PlayerCmd::ChaptersFetched`] is sent.
+    /// Requires that the function is called on a thread with a entered tokio runtime
+    fn fetch_current_chapters(&self) {
+        let Some(track) = self.playlist.current_track() else {
+            return;
+        };
+        let (Some(url), Some(file)) = (track.chapters_url(), track.file()) else {
+            return;
+        };
+        let url = url.to_string();
+        let file = file.to_string();
+        let cmd_tx = self.cmd_tx.clone();
+
+        Handle::current().spawn(async move {
+            match podcast::fetch_chapters(&url).await {
+                Ok(chapters) => {
+                    let _ = cmd_tx.send(PlayerCmd::ChaptersFetched { file, chapters });
+                }
+                Err(err) => warn!("Fetching chapters from \"{url}\" failed: {err:#}"),
+            }
+        });
+    }
+
+    /// Store the fetched chapters of the podcast episode `file` and apply them if it is still playing
+    pub fn set_chapters(&mut self, file: &str, chapters: Vec<Chapter>) {
+        if let Err(err) = self.db_podcast.set_chapters(file, &chapters) {
+            error!("Saving chapters failed, Error: {:#?}", err);
         }
+
+        if let Some(track) = self.playlist.current_track_as_mut() {
+            if track.file() == Some(file) {
+                track.set_chapters(chapters);
+            }
+        }
+    }
+
+    /// Get the title of the chapter currently playing, if the current track has chapters
+    pub fn current_chapter_title(&self) -> Option<String> {
+        let chapters = self.playlist.current_track()?.chapters();
+        let position = self.get_player().position()?;
+
+        chapter::current_index(chapters, position).map(|idx| chapters[idx].title.clone())
+    }
+
+    /// Seek to the start of the next chapter, returns whether there was one
+    pub fn chapter_next(&mut self) -> bool {
+        self.seek_chapter(chapter::next_start)
+    }
+
+    /// Seek to the start of the current or previous chapter, returns whether there was one
+    pub fn chapter_previous(&mut self) -> bool {
+        self.seek_chapter(chapter::previous_start)
+    }
+
+    fn seek_chapter(&mut self, find: fn(&[Chapter], Duration) -> Option<Duration>) -> bool {
+        let Some(track) = self.playlist.current_track() else {
+            return false;
+        };
+        let position = self.get_player().position().unwrap_or_default();
+        let Some(start) = find(track.chapters(), position) else {
+            return false;
+        };
+
+        self.seek_to(start);
+        true
     }
 
     fn add_and_play_mpris_discord(&mut self) {
@@ -409,7 +1035,43 @@ impl GeneralPlayer {
         info!("Next track enqueued: {:#?}", track);
     }
 
+    /// Replace the enqueued next track, after it was removed from the playlist
+    pub fn requeue_next_from_playlist(&mut self) {
+        let track = match self.playlist.fetch_next_track() {
+            Some(t) => t.clone(),
+            None => return,
+        };
+
+        self.playlist.set_next_track(Some(&track));
+        self.get_player_mut().requeue_next(&track);
+
+        info!("Next track enqueued again: {:#?}", track);
+    }
+
+    /// Record the current play in the play history, if there is one
+    ///
+    /// `skipped` should be `true` if the track did not play to the end.
+    pub fn record_play(&mut self, skipped: bool) {
+        let Some(play) = self.current_play.take() else {
+            return;
+        };
+
+        let listened = if skipped {
+            self.get_player().position().unwrap_or_default()
+        } else {
+            play.duration
+        };
+
+        if let Err(err) = self
+            .db
+            .add_play(&play.file, play.started_at, listened, skipped)
+        {
+            error!("Recording play history failed, Error: {:#?}", err);
+        }
+    }
+
     pub fn next(&mut self) {
+        self.record_play(true);
         if self.playlist.current_track().is_some() {
             info!("skip route 1 which is in most cases.");
             self.playlist.set_next_track(None);
@@ -558,10 +1220,11 @@ impl GeneralPlayer {
         }
     }
 
-    pub fn player_restore_last_position(&mut self) {
+    /// Seek to the last position of the current track, if enabled, returns whether it was restored
+    pub fn player_restore_last_position(&mut self) -> bool {
         let Some(track) = self.playlist.current_track() else {
             info!("Not restoring Last position as there is no current track");
-            return;
+            return false;
         };
 
         let mut restored = false;
@@ -582,9 +1245,12 @@ impl GeneralPlayer {
                     }
                 }
                 MediaType::Podcast => {
+                    // episodes that were not started yet have a last position of 0
                     if let Ok(last_pos) = self.db_podcast.get_last_position(track) {
-                        self.get_player_mut().seek_to(last_pos);
-                        restored = true;
+                        if !last_pos.is_zero() {
+                            self.get_player_mut().seek_to(last_pos);
+                            restored = true;
+                        }
                     }
                 }
                 MediaType::LiveRadio => (),
@@ -603,6 +1269,8 @@ impl GeneralPlayer {
                 }
             }
         }
+
+        restored
     }
 }
 
@@ -651,6 +1319,7 @@ impl PlayerTrait for GeneralPlayer {
     }
 
     fn stop(&mut self) {
+        self.record_play(true);
         self.playlist.set_status(Status::Stopped);
         self.playlist.set_next_track(None);
         self.playlist.clear_current_track();
@@ -681,11 +1350,30 @@ impl PlayerTrait for GeneralPlayer {
         self.get_player_mut().enqueue_next(track);
     }
 
+    fn requeue_next(&mut self, track: &Track) {
+        self.get_player_mut().requeue_next(track);
+    }
+
     fn media_info(&self) -> MediaInfo {
         self.get_player().media_info()
     }
 }
 
+/// Get the names of all available audio output devices
+///
+/// Only the rusty backend supports selecting a output device, so this is empty without it.
+#[must_use]
+pub fn list_output_devices() -> Vec<String> {
+    #[cfg(feature = "rusty")]
+    {
+        rusty_backend::output_device_names()
+    }
+    #[cfg(not(feature = "rusty"))]
+    {
+        Vec::new()
+    }
+}
+
 /// The primitive in which time (current position / total duration) will be stored as
 pub type PlayerTimeUnit = Duration;
 
@@ -792,6 +1480,8 @@ pub trait PlayerTrait {
     }
     /// Add the given URI to be played, but do not skip currently playing track
     fn enqueue_next(&mut self, track: &Track);
+    /// Replace the track added with [`PlayerTrait::enqueue_next`], like when it was removed from the playlist
+    fn requeue_next(&mut self, track: &Track);
     /// Get info of the current media
     fn media_info(&self) -> MediaInfo;
 }
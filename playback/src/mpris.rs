@@ -4,7 +4,8 @@ use std::sync::mpsc::{self, Receiver};
 use termusiclib::track::Track;
 
 use crate::{
-    GeneralPlayer, PlayerCmd, PlayerProgress, PlayerTimeUnit, PlayerTrait, Status, Volume,
+    GeneralPlayer, PlayerCmd, PlayerProgress, PlayerTimeUnit, PlayerTrait, Status, UpdateEvents,
+    Volume,
 };
 
 pub struct Mpris {
@@ -210,7 +211,8 @@ impl GeneralPlayer {
                 // default float to int casting will truncate values to the decimal point
                 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
                 let uvol = (volume.clamp(0.0, 1.0) * 100.0) as u16;
-                self.set_volume(uvol);
+                let new_volume = self.set_volume(uvol);
+                self.send_stream_ev(UpdateEvents::VolumeChanged { volume: new_volume });
             }
             MediaControlEvent::Quit => {
                 // ignore error if sending failed
@@ -35,6 +35,7 @@ use std::cmp;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Arc;
 use std::time::Duration;
+use termusiclib::config::v2::server::{ReplayGainMode, ReplayGainSettings};
 use termusiclib::config::ServerOverlay;
 use termusiclib::track::Track;
 
@@ -57,12 +58,15 @@ enum PlayerInternalCmd {
     // GetProgress,
     Play(String),
     QueueNext(String),
+    /// Remove the queued entries, keeping the current one
+    DequeueNext,
     Resume,
     Seek(i64),
     SeekAbsolute(Duration),
     Speed(i32),
     Stop,
     Volume(u16),
+    ReplayGain(ReplayGainSettings),
 }
 
 impl MpvBackend {
@@ -94,6 +98,7 @@ impl MpvBackend {
         let gapless_setting = if gapless { "yes" } else { "no" };
         mpv.set_property("gapless-audio", gapless_setting)
             .expect("gapless setting failed");
+        set_replaygain_properties(&mpv, &config.settings.player.replaygain);
 
         let cmd_tx_inside = command_tx.clone();
         // let mut time_pos: i64 = 0;
@@ -196,6 +201,10 @@ impl MpvBackend {
                                     .ok();
                                 // .expect("Error loading file");
                             }
+                            PlayerInternalCmd::DequeueNext => {
+                                // removes every entry except the currently playing one
+                                mpv.command("playlist-clear", &[]).ok();
+                            }
                             PlayerInternalCmd::Volume(volume) => {
                                 mpv.set_property("volume", i64::from(volume)).ok();
                                 // .expect("Error increase volume");
@@ -209,6 +218,9 @@ impl MpvBackend {
                             PlayerInternalCmd::Speed(speed) => {
                                 mpv.set_property("speed", f64::from(speed) / 10.0).ok();
                             }
+                            PlayerInternalCmd::ReplayGain(settings) => {
+                                set_replaygain_properties(&mpv, &settings);
+                            }
                             PlayerInternalCmd::Stop => {
                                 mpv.command("stop", &[""]).ok();
                             }
@@ -264,6 +276,28 @@ impl MpvBackend {
             media_title,
         }
     }
+
+    /// Change the ReplayGain settings, mpv reads the tags itself
+    pub fn set_replaygain(&self, settings: ReplayGainSettings) {
+        self.command_tx
+            .send(PlayerInternalCmd::ReplayGain(settings))
+            .ok();
+    }
+}
+
+/// Apply the ReplayGain settings to the equivalent mpv properties
+fn set_replaygain_properties(mpv: &Mpv, settings: &ReplayGainSettings) {
+    let mode = match settings.mode {
+        ReplayGainMode::Off => "no",
+        ReplayGainMode::Track => "track",
+        ReplayGainMode::Album => "album",
+    };
+    mpv.set_property("replaygain", mode).ok();
+    mpv.set_property("replaygain-preamp", f64::from(settings.preamp))
+        .ok();
+    // "replaygain-clip" means "allow clipping"
+    mpv.set_property("replaygain-clip", !settings.prevent_clipping)
+        .ok();
 }
 
 /// Format a duration in "SS.mm" format
@@ -369,6 +403,13 @@ impl PlayerTrait for MpvBackend {
             .expect("failed to queue next");
     }
 
+    fn requeue_next(&mut self, track: &Track) {
+        self.command_tx
+            .send(PlayerInternalCmd::DequeueNext)
+            .expect("failed to dequeue next");
+        self.enqueue_next(track);
+    }
+
     fn media_info(&self) -> MediaInfo {
         let media_title_r = self.media_title.lock();
         if media_title_r.is_empty() {
@@ -4,8 +4,11 @@ use rand::seq::SliceRandom;
 use rand::thread_rng;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use termusiclib::config::v2::server::LoopMode;
 use termusiclib::config::SharedServerSettings;
@@ -58,6 +61,10 @@ impl std::fmt::Display for Status {
 pub struct Playlist {
     tracks: Vec<Track>,
     current_track_index: usize,
+    /// Amount of tracks directly after the current track that are queued as "up next"
+    ///
+    /// Those are played before continuing with the rest of the playlist, regardless of the loop mode.
+    up_next: usize,
     next_track_index: usize,
     played_index: Vec<usize>,
     current_track: Option<Track>,
@@ -66,13 +73,27 @@ pub struct Playlist {
     loop_mode: LoopMode,
     config: SharedServerSettings,
     need_proceed_to_next: bool,
+    /// States before the last changes to the tracks, the most recent last
+    undo_history: Vec<PlaylistState>,
+    /// States that were undone, the most recently undone last
+    redo_history: Vec<PlaylistState>,
+}
+
+/// The order of the tracks at some point, to undo or redo changes
+///
+/// Only the files are stored to not keep copies of every track (and its cover) around.
+#[derive(Debug, Clone)]
+struct PlaylistState {
+    files: Vec<String>,
+    current_track_index: usize,
+    up_next: usize,
 }
 
 impl Playlist {
     /// # Errors
     /// errors could happen when reading files
     pub fn new(config: SharedServerSettings) -> Result<Self> {
-        let (current_track_index, tracks) = Self::load()?;
+        let (current_track_index, up_next, tracks) = Self::load()?;
         // TODO: shouldnt "loop_mode" be combined with the config ones?
         let loop_mode = config.read().settings.player.loop_mode;
         let current_track = None;
@@ -84,11 +105,14 @@ impl Playlist {
             status: Status::Stopped,
             loop_mode,
             current_track_index,
+            up_next,
             current_track,
             played_index: Vec::new(),
             config,
             next_track_index: 0,
             need_proceed_to_next: false,
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
         })
     }
 
@@ -109,11 +133,13 @@ impl Playlist {
     ///
     /// Path in `$config$/playlist.log`
     ///
+    /// Returns the current track index, the amount of "up next" tracks and the tracks.
+    ///
     /// # Errors
     /// errors could happen when reading file
     /// # Panics
     /// panics when error loading podcasts from db
-    pub fn load() -> Result<(usize, Vec<Track>)> {
+    pub fn load() -> Result<(usize, usize, Vec<Track>)> {
         let path = get_playlist_path()?;
 
         let file = if let Ok(f) = File::open(path.as_path()) {
@@ -125,7 +151,8 @@ impl Playlist {
         let reader = BufReader::new(file);
         let mut lines = reader
             .lines()
-            .map(|line| line.unwrap_or_else(|_| "Error".to_string()));
+            .map(|line| line.unwrap_or_else(|_| "Error".to_string()))
+            .peekable();
 
         let mut current_track_index = 0;
         if let Some(index_line) = lines.next() {
@@ -134,45 +161,66 @@ impl Playlist {
             }
         }
 
-        let mut playlist_items = Vec::new();
+        let mut up_next = 0;
+        if let Some(amount) = lines
+            .peek()
+            .and_then(|line| line.strip_prefix(UP_NEXT_PREFIX))
+        {
+            up_next = amount.trim().parse().unwrap_or_default();
+            lines.next();
+        }
+
+        let playlist_items = Self::tracks_from_saved(lines)?;
+        let up_next = up_next.min(playlist_items.len().saturating_sub(current_track_index + 1));
+
+        Ok((current_track_index, up_next, playlist_items))
+    }
+
+    /// Create [`Track`]s from saved paths and urls
+    ///
+    /// Urls of podcast episodes in the podcast database become episodes, all other urls radio streams.
+    /// Paths that cannot be read are skipped.
+    ///
+    /// # Errors
+    /// if the podcast database cannot be read
+    pub fn tracks_from_saved<I: IntoIterator<Item = String>>(items: I) -> Result<Vec<Track>> {
+        let mut tracks = Vec::new();
         let db_path = get_app_config_path()?;
         let db_podcast = DBPod::new(&db_path)?;
         let podcasts = db_podcast
             .get_podcasts()
             .with_context(|| "failed to get podcasts from db.")?;
-        for line in lines {
+        for line in items {
             if let Ok(track) = Track::read_from_path(&line, false) {
-                playlist_items.push(track);
+                tracks.push(track);
                 continue;
             };
             if line.starts_with("http") {
-                let mut is_podcast = false;
-                'outer: for pod in &podcasts {
-                    for ep in &pod.episodes {
-                        if ep.url == line.as_str() {
-                            is_podcast = true;
-                            let track = Track::from_episode(ep);
-                            playlist_items.push(track);
-                            break 'outer;
-                        }
-                    }
-                }
-                if !is_podcast {
-                    let track = Track::new_radio(&line);
-                    playlist_items.push(track);
+                let episode = podcasts
+                    .iter()
+                    .flat_map(|pod| &pod.episodes)
+                    .find(|ep| ep.url == line.as_str());
+                match episode {
+                    Some(ep) => tracks.push(Track::from_episode(ep)),
+                    None => tracks.push(Track::new_radio(&line)),
                 }
             }
         }
 
-        Ok((current_track_index, playlist_items))
+        Ok(tracks)
     }
 
     /// # Errors
     /// Errors could happen when reading files
     pub fn reload_tracks(&mut self) -> Result<()> {
-        let (current_track_index, tracks) = Self::load()?;
+        let (current_track_index, up_next, tracks) = Self::load()?;
+        // changes done by clients that save the playlist file themself
+        if tracks != self.tracks {
+            self.remember();
+        }
         self.tracks = tracks;
         self.current_track_index = current_track_index;
+        self.up_next = up_next;
         Ok(())
     }
 
@@ -190,6 +238,10 @@ impl Playlist {
         let mut bytes = Vec::new();
         bytes.extend(format!("{}", self.current_track_index).as_bytes());
         bytes.extend("\n".as_bytes());
+        if self.up_next > 0 {
+            bytes.extend(format!("{UP_NEXT_PREFIX}{}", self.up_next).as_bytes());
+            bytes.extend("\n".as_bytes());
+        }
         for i in &self.tracks {
             if let Some(f) = i.file() {
                 bytes.extend(f.as_bytes());
@@ -205,14 +257,18 @@ impl Playlist {
 
     pub fn next(&mut self) {
         self.played_index.push(self.current_track_index);
-        if self.config.read().settings.player.gapless && self.has_next_track() {
-            self.current_track_index = self.next_track_index;
-            return;
-        }
-        self.current_track_index = self.get_next_track_index();
+        let index = if self.config.read().settings.player.gapless && self.has_next_track() {
+            self.next_track_index
+        } else {
+            self.get_next_track_index()
+        };
+        self.change_current(index);
     }
 
     fn get_next_track_index(&self) -> usize {
+        if self.up_next > 0 {
+            return self.current_track_index + 1;
+        }
         let mut next_track_index = self.current_track_index;
         match self.loop_mode {
             LoopMode::Single => {}
@@ -233,7 +289,7 @@ impl Playlist {
     pub fn previous(&mut self) {
         if !self.played_index.is_empty() {
             if let Some(index) = self.played_index.pop() {
-                self.current_track_index = index;
+                self.change_current(index);
                 return;
             }
         }
@@ -241,17 +297,65 @@ impl Playlist {
             LoopMode::Single => {}
             LoopMode::Playlist => {
                 if self.current_track_index == 0 {
-                    self.current_track_index = self.len() - 1;
+                    self.change_current(self.len().saturating_sub(1));
                 } else {
-                    self.current_track_index -= 1;
+                    self.change_current(self.current_track_index - 1);
                 }
             }
             LoopMode::Random => {
-                self.current_track_index = self.get_random_index();
+                let index = self.get_random_index();
+                self.change_current(index);
             }
         }
     }
 
+    /// Make the track at `index` the current track, keeping the "up next" tracks directly after it
+    ///
+    /// Changing to the first "up next" track consumes it, changing to a later one plays it
+    /// and keeps the remaining ones queued.
+    fn change_current(&mut self, index: usize) {
+        let queue = self.up_next_range();
+        if queue.is_empty() || index == self.current_track_index || index >= self.len() {
+            self.current_track_index = index;
+            return;
+        }
+
+        if queue.contains(&index) {
+            let track = self.tracks.remove(index);
+            self.tracks.insert(queue.start, track);
+            self.current_track_index = queue.start;
+            self.up_next -= 1;
+            return;
+        }
+
+        let queued: Vec<Track> = self.tracks.drain(queue.clone()).collect();
+        let index = if index >= queue.end {
+            index - queued.len()
+        } else {
+            index
+        };
+        self.tracks.splice(index + 1..index + 1, queued);
+        self.current_track_index = index;
+    }
+
+    /// The indexes of the "up next" tracks
+    fn up_next_range(&self) -> Range<usize> {
+        let start = (self.current_track_index + 1).min(self.len());
+        start..(start + self.up_next).min(self.len())
+    }
+
+    /// The amount of tracks queued as "up next"
+    #[must_use]
+    pub fn up_next_len(&self) -> usize {
+        self.up_next
+    }
+
+    /// Whether the track at `index` is queued as "up next"
+    #[must_use]
+    pub fn is_up_next(&self, index: usize) -> bool {
+        self.up_next_range().contains(&index)
+    }
+
     #[must_use]
     pub fn len(&self) -> usize {
         self.tracks.len()
@@ -264,6 +368,7 @@ impl Playlist {
 
     pub fn swap_down(&mut self, index: usize) {
         if index < self.len() - 1 {
+            self.remember();
             let track = self.tracks.remove(index);
             self.tracks.insert(index + 1, track);
             // handle index
@@ -277,6 +382,7 @@ impl Playlist {
 
     pub fn swap_up(&mut self, index: usize) {
         if index > 0 {
+            self.remember();
             let track = self.tracks.remove(index);
             self.tracks.insert(index - 1, track);
             // handle index
@@ -288,6 +394,42 @@ impl Playlist {
         }
     }
 
+    /// Swap the tracks at `index_a` and `index_b`, does nothing if either index is out of bounds
+    pub fn swap(&mut self, index_a: usize, index_b: usize) {
+        if index_a >= self.len() || index_b >= self.len() {
+            return;
+        }
+        self.remember();
+        self.tracks.swap(index_a, index_b);
+        // handle index
+        if self.current_track_index == index_a {
+            self.current_track_index = index_b;
+        } else if self.current_track_index == index_b {
+            self.current_track_index = index_a;
+        }
+    }
+
+    /// Move the track at `from` to `to`, shifting all tracks in between
+    ///
+    /// Does nothing if either index is out of bounds
+    pub fn move_track(&mut self, from: usize, to: usize) {
+        if from >= self.len() || to >= self.len() {
+            return;
+        }
+        self.remember();
+        let track = self.tracks.remove(from);
+        self.tracks.insert(to, track);
+        // handle index
+        let current = self.current_track_index;
+        if current == from {
+            self.current_track_index = to;
+        } else if from < current && current <= to {
+            self.current_track_index -= 1;
+        } else if to <= current && current < from {
+            self.current_track_index += 1;
+        }
+    }
+
     pub fn get_current_track(&mut self) -> Option<String> {
         let mut result = None;
         if let Some(track) = self.current_track() {
@@ -394,26 +536,176 @@ impl Playlist {
 
     pub fn add_episode(&mut self, ep: &Episode) {
         let track = Track::from_episode(ep);
+        self.remember();
         self.tracks.push(track);
     }
 
     /// # Errors
     /// Error happens when track cannot be read from local file
     pub fn add_playlist<T: AsRef<str>>(&mut self, vec: &[T]) -> Result<()> {
+        self.add_playlist_at(vec, self.len())
+    }
+
+    /// Insert the given items starting at `at_index`, appending them if `at_index` is past the end
+    ///
+    /// The current track index is adjusted to still point to the same track.
+    ///
+    /// # Errors
+    /// Error happens when track cannot be read from local file
+    pub fn add_playlist_at<T: AsRef<str>>(&mut self, vec: &[T], at_index: usize) -> Result<()> {
+        let (tracks, result) = Self::tracks_from_items(vec);
+        self.insert_tracks(tracks, at_index);
+
+        result
+    }
+
+    /// Insert the given items directly after the current track, so that they are played next
+    ///
+    /// The items are put in front of the "up next" tracks.
+    ///
+    /// # Errors
+    /// Error happens when track cannot be read from local file
+    pub fn add_playlist_next<T: AsRef<str>>(&mut self, vec: &[T]) -> Result<()> {
+        let (tracks, result) = Self::tracks_from_items(vec);
+        if !tracks.is_empty() {
+            self.remember();
+        }
+        self.queue_tracks(tracks, true);
+
+        result
+    }
+
+    /// Add the given items to the end of the "up next" tracks
+    ///
+    /// # Errors
+    /// Error happens when track cannot be read from local file
+    pub fn add_playlist_up_next<T: AsRef<str>>(&mut self, vec: &[T]) -> Result<()> {
+        let (tracks, result) = Self::tracks_from_items(vec);
+        if !tracks.is_empty() {
+            self.remember();
+        }
+        self.queue_tracks(tracks, false);
+
+        result
+    }
+
+    /// Move the track at `index` to directly after the current track, so that it is played next
+    ///
+    /// Does nothing if `index` is out of bounds or the current track.
+    pub fn move_to_next(&mut self, index: usize) {
+        self.requeue(index, true);
+    }
+
+    /// Move the track at `index` to the end of the "up next" tracks
+    ///
+    /// Does nothing if `index` is out of bounds or the current track.
+    pub fn move_to_up_next(&mut self, index: usize) {
+        self.requeue(index, false);
+    }
+
+    fn requeue(&mut self, index: usize, front: bool) {
+        if index >= self.len() || index == self.current_track_index {
+            return;
+        }
+        let track = self.tracks[index].clone();
+        self.remember();
+        // the track is only moved, so a track enqueued for gapless playback stays enqueued
+        let next_track = self.next_track.take();
+        self.remove_at(index);
+        self.queue_tracks(vec![track], front);
+        self.next_track = next_track;
+        self.locate_next_track();
+    }
+
+    /// Queue the tracks as "up next", either in front or at the end of the already queued tracks
+    fn queue_tracks(&mut self, tracks: Vec<Track>, front: bool) {
+        let amount = tracks.len();
+        if self.is_empty() {
+            // the first track becomes the current one
+            self.insert_at(tracks, 0);
+            self.up_next = amount.saturating_sub(1);
+            return;
+        }
+        let queue = self.up_next_range();
+        let at_index = if front { queue.start } else { queue.end };
+        self.insert_at(tracks, at_index);
+        self.up_next += amount;
+    }
+
+    /// Insert the tracks starting at `at_index`, appending them if `at_index` is past the end
+    ///
+    /// The current track index is adjusted to still point to the same track.
+    /// Tracks inserted in between "up next" tracks are queued as well.
+    pub fn insert_tracks(&mut self, tracks: Vec<Track>, at_index: usize) {
+        if tracks.is_empty() {
+            return;
+        }
+        self.remember();
+        self.insert_at(tracks, at_index);
+    }
+
+    /// Replace all tracks, like [`Self::clear`] followed by [`Self::insert_tracks`] but as one change to undo
+    pub fn replace_tracks(&mut self, tracks: Vec<Track>) {
+        self.remember();
+        self.clear_tracks();
+        self.insert_at(tracks, 0);
+    }
+
+    fn insert_at(&mut self, tracks: Vec<Track>, at_index: usize) {
+        let start = at_index.min(self.len());
+        let shifts_current = !self.is_empty() && start <= self.current_track_index;
+        let queue = self.up_next_range();
+        let joins_queue = start > queue.start && start < queue.end;
+        let amount = tracks.len();
+
+        self.tracks.splice(start..start, tracks);
+
+        if shifts_current {
+            self.current_track_index += amount;
+        }
+        if joins_queue {
+            self.up_next += amount;
+        }
+        // keep pointing at the track that is already enqueued for gapless playback
+        if self.has_next_track() && start <= self.next_track_index {
+            self.next_track_index += amount;
+        }
+    }
+
+    /// Create [`Track`]s from paths or urls, stopping at the first error
+    ///
+    /// Returns the tracks created until then, together with the error.
+    fn tracks_from_items<T: AsRef<str>>(vec: &[T]) -> (Vec<Track>, Result<()>) {
+        let mut tracks = Vec::new();
+
         for item in vec.iter().map(AsRef::as_ref) {
-            if item.starts_with("http") {
-                let track = Track::new_radio(item);
-                self.tracks.push(track);
-            } else if !filetype_supported(item) {
-                // TODO: add error on fail
-                error!("unsupported filetype: {:#?}", item);
-                continue;
-            } else if PathBuf::from(item).exists() {
-                let track = Track::read_from_path(item, false)?;
-                self.tracks.push(track);
+            match Self::track_from_item(item) {
+                Ok(Some(track)) => tracks.push(track),
+                Ok(None) => (),
+                Err(err) => return (tracks, Err(err)),
             }
         }
-        Ok(())
+
+        (tracks, Ok(()))
+    }
+
+    /// Create a [`Track`] from a path or url
+    ///
+    /// Returns [`None`] if the item is not supported or does not exist
+    fn track_from_item(item: &str) -> Result<Option<Track>> {
+        if item.starts_with("http") {
+            return Ok(Some(Track::new_radio(item)));
+        }
+        if !filetype_supported(item) {
+            // TODO: add error on fail
+            error!("unsupported filetype: {:#?}", item);
+            return Ok(None);
+        }
+        if PathBuf::from(item).exists() {
+            return Ok(Some(Track::read_from_path(item, false)?));
+        }
+
+        Ok(None)
     }
 
     #[must_use]
@@ -422,6 +714,29 @@ impl Playlist {
     }
 
     pub fn remove(&mut self, index: usize) {
+        if index >= self.len() {
+            return;
+        }
+        self.remember();
+        self.remove_at(index);
+    }
+
+    fn remove_at(&mut self, index: usize) {
+        if self.is_up_next(index) || (index == 0 && self.current_track_index == 0) {
+            // the removed track was queued, or the first queued track becomes the current one
+            self.up_next = self.up_next.saturating_sub(1);
+        }
+        if self.has_next_track() {
+            match index.cmp(&self.next_track_index) {
+                Ordering::Less => self.next_track_index -= 1,
+                // the track enqueued for gapless playback is gone, the backend has to enqueue another one
+                Ordering::Equal => {
+                    self.next_track = None;
+                    self.next_track_index = 0;
+                }
+                Ordering::Greater => (),
+            }
+        }
         self.tracks.remove(index);
         // Handle index
         if index <= self.current_track_index {
@@ -434,17 +749,152 @@ impl Playlist {
     }
 
     pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.remember();
+        }
+        self.clear_tracks();
+    }
+
+    fn clear_tracks(&mut self) {
         self.tracks.clear();
         self.current_track_index = 0;
+        self.up_next = 0;
     }
 
+    /// Shuffle the tracks, the "up next" tracks stay in order after the current track
     pub fn shuffle(&mut self) {
         if let Some(current_track_file) = self.get_current_track() {
+            self.remember();
+            let queued: Vec<Track> = self.tracks.drain(self.up_next_range()).collect();
             self.tracks.shuffle(&mut thread_rng());
             if let Some(index) = self.find_index_from_file(&current_track_file) {
                 self.current_track_index = index;
             }
+            let start = (self.current_track_index + 1).min(self.len());
+            self.tracks.splice(start..start, queued);
+
+            self.locate_next_track();
+        }
+    }
+
+    /// Find the track enqueued for gapless playback again after the tracks were rearranged
+    fn locate_next_track(&mut self) {
+        let next_file = self
+            .next_track()
+            .and_then(Track::file)
+            .map(ToOwned::to_owned);
+        if let Some(index) = next_file.and_then(|file| self.find_index_from_file(&file)) {
+            self.next_track_index = index;
+        }
+    }
+
+    /// Remember the current tracks in the undo history, has to be called before changing them
+    ///
+    /// This discards everything that could be redone.
+    fn remember(&mut self) {
+        self.redo_history.clear();
+        let state = self.state();
+        Self::push_history(&mut self.undo_history, state);
+    }
+
+    fn push_history(history: &mut Vec<PlaylistState>, state: PlaylistState) {
+        history.push(state);
+        if history.len() > MAX_UNDO_HISTORY {
+            history.remove(0);
+        }
+    }
+
+    fn state(&self) -> PlaylistState {
+        PlaylistState {
+            files: self
+                .tracks
+                .iter()
+                .filter_map(Track::file)
+                .map(ToOwned::to_owned)
+                .collect(),
+            current_track_index: self.current_track_index,
+            up_next: self.up_next,
+        }
+    }
+
+    /// Undo the last change to the tracks (add, remove, swap, move, clear or shuffle)
+    ///
+    /// Returns `false` if there was nothing to undo.
+    ///
+    /// # Errors
+    /// if tracks that are not in the playlist anymore cannot be read again
+    pub fn undo(&mut self) -> Result<bool> {
+        let Some(state) = self.undo_history.pop() else {
+            return Ok(false);
+        };
+        let current = self.state();
+        if let Err(err) = self.restore(&state) {
+            self.undo_history.push(state);
+            return Err(err);
         }
+        Self::push_history(&mut self.redo_history, current);
+
+        Ok(true)
+    }
+
+    /// Redo the last undone change to the tracks
+    ///
+    /// Returns `false` if there was nothing to redo.
+    ///
+    /// # Errors
+    /// if tracks that are not in the playlist anymore cannot be read again
+    pub fn redo(&mut self) -> Result<bool> {
+        let Some(state) = self.redo_history.pop() else {
+            return Ok(false);
+        };
+        let current = self.state();
+        if let Err(err) = self.restore(&state) {
+            self.redo_history.push(state);
+            return Err(err);
+        }
+        Self::push_history(&mut self.undo_history, current);
+
+        Ok(true)
+    }
+
+    /// Change the tracks to the given state, keeping the currently playing track current if it is still there
+    fn restore(&mut self, state: &PlaylistState) -> Result<()> {
+        let present: HashSet<&str> = self.tracks.iter().filter_map(Track::file).collect();
+        let missing: Vec<String> = state
+            .files
+            .iter()
+            .filter(|file| !present.contains(file.as_str()))
+            .cloned()
+            .collect();
+        // tracks that were removed since have to be read again
+        let read = if missing.is_empty() {
+            Vec::new()
+        } else {
+            Self::tracks_from_saved(missing)?
+        };
+
+        let current_track_file = self.get_current_track();
+        let known: HashMap<String, Track> = self
+            .tracks
+            .drain(..)
+            .chain(read)
+            .filter_map(|track| Some((track.file()?.to_owned(), track)))
+            .collect();
+        self.tracks = state
+            .files
+            .iter()
+            .filter_map(|file| known.get(file).cloned())
+            .collect();
+
+        self.current_track_index = current_track_file
+            .and_then(|file| self.find_index_from_file(&file))
+            .unwrap_or_else(|| state.current_track_index.min(self.len().saturating_sub(1)));
+        self.up_next = state
+            .up_next
+            .min(self.len().saturating_sub(self.current_track_index + 1));
+        self.locate_next_track();
+
+        Ok(())
     }
 
     fn find_index_from_file(&self, item: &str) -> Option<usize> {
@@ -504,6 +954,12 @@ impl Playlist {
         self.current_track_index = index;
     }
 
+    /// Change the current track to the one at `index`, like [`Self::set_current_track_index`]
+    /// but keeping the "up next" tracks queued after it
+    pub fn jump_to(&mut self, index: usize) {
+        self.change_current(index);
+    }
+
     #[must_use]
     pub fn next_track(&self) -> Option<&Track> {
         self.next_track.as_ref()
@@ -523,6 +979,12 @@ impl Playlist {
 }
 
 const PLAYLIST_SAVE_FILENAME: &str = "playlist.log";
+/// Maximum amount of changes that can be undone
+const MAX_UNDO_HISTORY: usize = 50;
+/// Prefix of the optional line after the current track index that stores the amount of "up next" tracks
+///
+/// Older versions skip it, as it is not a readable path.
+const UP_NEXT_PREFIX: &str = "#up_next ";
 
 fn get_playlist_path() -> Result<PathBuf> {
     let mut path = get_app_config_path()?;
@@ -530,3 +992,232 @@ fn get_playlist_path() -> Result<PathBuf> {
 
     Ok(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Playlist;
+    use pretty_assertions::assert_eq;
+    use termusiclib::config::v2::server::LoopMode;
+
+    /// Create a playlist with radio tracks, as those do not need to exist on disk
+    fn playlist_with(urls: &[&str]) -> Playlist {
+        let mut playlist = Playlist::default();
+        playlist.add_playlist(urls).unwrap();
+        playlist
+    }
+
+    fn files(playlist: &Playlist) -> Vec<&str> {
+        playlist
+            .tracks()
+            .iter()
+            .map(|v| v.file().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn should_insert_and_keep_current_track() {
+        let mut playlist = playlist_with(&["http://a", "http://b"]);
+        playlist.set_current_track_index(1);
+
+        playlist.add_playlist_at(&["http://c"], 0).unwrap();
+        assert_eq!(files(&playlist), ["http://c", "http://a", "http://b"]);
+        assert_eq!(playlist.get_current_track_index(), 2);
+
+        // past the end appends
+        playlist.add_playlist_at(&["http://d"], 100).unwrap();
+        assert_eq!(
+            files(&playlist),
+            ["http://c", "http://a", "http://b", "http://d"]
+        );
+        assert_eq!(playlist.get_current_track_index(), 2);
+    }
+
+    #[test]
+    fn should_move_and_keep_current_track() {
+        let mut playlist = playlist_with(&["http://a", "http://b", "http://c"]);
+        playlist.set_current_track_index(1);
+
+        playlist.move_track(0, 2);
+        assert_eq!(files(&playlist), ["http://b", "http://c", "http://a"]);
+        assert_eq!(playlist.get_current_track_index(), 0);
+
+        playlist.move_track(0, 1);
+        assert_eq!(files(&playlist), ["http://c", "http://b", "http://a"]);
+        assert_eq!(playlist.get_current_track_index(), 1);
+
+        playlist.swap(1, 2);
+        assert_eq!(files(&playlist), ["http://c", "http://a", "http://b"]);
+        assert_eq!(playlist.get_current_track_index(), 2);
+
+        // out of bounds does nothing
+        playlist.move_track(0, 3);
+        playlist.swap(3, 0);
+        playlist.remove(3);
+        assert_eq!(files(&playlist), ["http://c", "http://a", "http://b"]);
+    }
+
+    #[test]
+    fn should_play_up_next_before_the_rest() {
+        let mut playlist = playlist_with(&["http://a", "http://b", "http://c"]);
+        playlist.loop_mode = LoopMode::Random;
+
+        playlist
+            .add_playlist_up_next(&["http://x", "http://y"])
+            .unwrap();
+        playlist.add_playlist_next(&["http://z"]).unwrap();
+        assert_eq!(
+            files(&playlist),
+            ["http://a", "http://z", "http://x", "http://y", "http://b", "http://c"]
+        );
+        assert_eq!(playlist.up_next_len(), 3);
+        assert!(playlist.is_up_next(3));
+        assert!(!playlist.is_up_next(4));
+
+        // consumed in order, even in random mode
+        playlist.next();
+        assert_eq!(playlist.get_current_track_index(), 1);
+        assert_eq!(playlist.up_next_len(), 2);
+
+        // the remaining ones stay queued after a jump
+        playlist.jump_to(5);
+        assert_eq!(
+            files(&playlist),
+            ["http://a", "http://z", "http://b", "http://c", "http://x", "http://y"]
+        );
+        assert_eq!(playlist.get_current_track_index(), 3);
+        assert_eq!(playlist.up_next_len(), 2);
+
+        playlist.move_to_next(0);
+        assert_eq!(
+            files(&playlist),
+            ["http://z", "http://b", "http://c", "http://a", "http://x", "http://y"]
+        );
+        assert_eq!(playlist.get_current_track_index(), 2);
+        assert_eq!(playlist.up_next_len(), 3);
+
+        playlist.remove(4);
+        assert_eq!(playlist.up_next_len(), 2);
+    }
+
+    #[test]
+    fn should_keep_up_next_when_shuffling() {
+        let mut playlist = playlist_with(&["http://a", "http://b", "http://c", "http://d"]);
+        playlist
+            .add_playlist_up_next(&["http://x", "http://y"])
+            .unwrap();
+
+        playlist.shuffle();
+        let index = playlist.get_current_track_index();
+        assert_eq!(files(&playlist)[index], "http://a");
+        assert_eq!(
+            files(&playlist)[index + 1..index + 3],
+            ["http://x", "http://y"]
+        );
+        assert_eq!(playlist.up_next_len(), 2);
+    }
+
+    #[test]
+    fn should_play_gapless_enqueued_track_before_up_next() {
+        let mut playlist = playlist_with(&["http://a", "http://b", "http://c"]);
+        let next = playlist.fetch_next_track().cloned();
+        playlist.set_next_track(next.as_ref());
+
+        // queued after the next track was already enqueued in the backend
+        playlist.add_playlist_up_next(&["http://x"]).unwrap();
+
+        playlist.next();
+        playlist.set_next_track(None);
+        assert_eq!(
+            files(&playlist),
+            ["http://a", "http://b", "http://x", "http://c"]
+        );
+        assert_eq!(playlist.get_current_track_index(), 1);
+
+        playlist.next();
+        assert_eq!(playlist.get_current_track_index(), 2);
+        assert_eq!(playlist.up_next_len(), 0);
+    }
+
+    #[test]
+    fn should_forget_gapless_enqueued_track_when_removed() {
+        let mut playlist = playlist_with(&["http://a", "http://b", "http://c"]);
+        let next = playlist.fetch_next_track().cloned();
+        playlist.set_next_track(next.as_ref());
+
+        // moving the enqueued track keeps it enqueued
+        playlist.move_to_up_next(1);
+        assert!(playlist.has_next_track());
+
+        playlist.remove(1);
+        assert!(!playlist.has_next_track());
+
+        let next = playlist.fetch_next_track().cloned();
+        assert_eq!(next.as_ref().unwrap().file(), Some("http://c"));
+        playlist.set_next_track(next.as_ref());
+
+        playlist.next();
+        assert_eq!(files(&playlist), ["http://a", "http://c"]);
+        assert_eq!(playlist.get_current_track_index(), 1);
+    }
+
+    #[test]
+    fn should_undo_and_redo_changes() {
+        let mut playlist = playlist_with(&["http://a", "http://b", "http://c"]);
+        playlist.set_current_track_index(1);
+
+        playlist.swap(0, 2);
+        playlist.move_track(2, 1);
+        assert_eq!(files(&playlist), ["http://c", "http://a", "http://b"]);
+        assert_eq!(playlist.get_current_track_index(), 2);
+
+        assert!(playlist.undo().unwrap());
+        assert_eq!(files(&playlist), ["http://c", "http://b", "http://a"]);
+        assert_eq!(playlist.get_current_track_index(), 1);
+        assert!(playlist.undo().unwrap());
+        assert_eq!(files(&playlist), ["http://a", "http://b", "http://c"]);
+
+        assert!(playlist.redo().unwrap());
+        assert_eq!(files(&playlist), ["http://c", "http://b", "http://a"]);
+
+        // a new change discards what could be redone
+        playlist.swap(0, 1);
+        assert!(!playlist.redo().unwrap());
+        assert_eq!(files(&playlist), ["http://b", "http://c", "http://a"]);
+    }
+
+    #[test]
+    fn should_undo_shuffle_and_keep_current_track() {
+        let urls: Vec<String> = (0..20).map(|v| format!("http://{v}")).collect();
+        let mut playlist = playlist_with(&urls.iter().map(String::as_str).collect::<Vec<_>>());
+        playlist.set_current_track_index(5);
+
+        playlist.shuffle();
+        assert!(playlist.undo().unwrap());
+        assert_eq!(files(&playlist), urls);
+        assert_eq!(playlist.get_current_track_index(), 5);
+
+        // the current track stays current, even if it moved since the change
+        playlist.shuffle();
+        let shuffled: Vec<String> = files(&playlist).into_iter().map(String::from).collect();
+        playlist.undo().unwrap();
+        playlist.set_current_track_index(0);
+        playlist.redo().unwrap();
+        assert_eq!(files(&playlist), shuffled);
+        assert_eq!(
+            playlist.get_current_track_index(),
+            shuffled.iter().position(|v| v == "http://0").unwrap()
+        );
+    }
+
+    #[test]
+    fn should_limit_undo_history() {
+        let mut playlist = playlist_with(&["http://a", "http://b"]);
+        for _ in 0..super::MAX_UNDO_HISTORY + 10 {
+            playlist.swap(0, 1);
+        }
+        for _ in 0..super::MAX_UNDO_HISTORY {
+            assert!(playlist.undo().unwrap());
+        }
+        assert!(!playlist.undo().unwrap());
+    }
+}
@@ -19,7 +19,8 @@ pub use decoder::Symphonia;
 pub use sink::Sink;
 pub use source::Source;
 use std::num::{NonZeroU16, NonZeroUsize};
-pub use stream::OutputStream;
+pub use stream::{output_device_names, OutputStream};
+use termusiclib::config::v2::server::{EqualizerBand, ReplayGainSettings};
 use termusiclib::config::ServerOverlay;
 use tokio::runtime::Handle;
 
@@ -69,6 +70,8 @@ pub enum PlayerInternalCmd {
     /// Enqueue a new track to be played, but do not skip current track
     /// (Track, gapless)
     QueueNext(Box<Track>, bool),
+    /// Remove the track enqueued with [`Self::QueueNext`], if it has not started yet
+    DequeueNext,
     Resume,
     SeekAbsolute(Duration),
     SeekRelative(i64),
@@ -78,6 +81,14 @@ pub enum PlayerInternalCmd {
     TogglePause,
     Volume(u16),
     Eos,
+    /// Switch to the output device with the given name, `None` for the default device
+    SetOutputDevice(Option<String>),
+    /// Change the ReplayGain settings, only applies to tracks queued afterwards
+    ReplayGain(ReplayGainSettings),
+    /// Replace the equalizer bands, empty to disable the equalizer
+    Equalizer(Vec<EqualizerBand>),
+    /// Change the crossfade duration, zero to disable crossfading
+    Crossfade(Duration),
 }
 pub struct RustyBackend {
     volume: Arc<AtomicU16>,
@@ -107,6 +118,10 @@ impl RustyBackend {
         let volume_local = volume.clone();
         let speed = config.settings.player.speed;
         let gapless = config.settings.player.gapless;
+        let output_device = config.settings.player.output_device.clone();
+        let replaygain = config.settings.player.replaygain;
+        let equalizer = config.settings.player.equalizer.active_bands().to_vec();
+        let crossfade = Duration::from_millis(config.settings.player.crossfade_ms.into());
         let position = Arc::new(Mutex::new(Duration::default()));
         let total_duration = Arc::new(Mutex::new(None));
         let total_duration_local = total_duration.clone();
@@ -132,6 +147,10 @@ impl RustyBackend {
                     position_local,
                     volume_local,
                     speed,
+                    output_device,
+                    replaygain,
+                    equalizer,
+                    crossfade,
                 ));
             })
             .expect("failed to spawn thread");
@@ -159,6 +178,26 @@ impl RustyBackend {
     pub fn message_on_end(&self) {
         self.command(PlayerInternalCmd::MessageOnEnd);
     }
+
+    /// Switch the output device, this will clear the current sink
+    pub fn set_output_device(&self, device: Option<String>) {
+        self.command(PlayerInternalCmd::SetOutputDevice(device));
+    }
+
+    /// Change the ReplayGain settings, the currently playing track keeps its gain
+    pub fn set_replaygain(&self, settings: ReplayGainSettings) {
+        self.command(PlayerInternalCmd::ReplayGain(settings));
+    }
+
+    /// Replace the equalizer bands, applies to the currently playing track too
+    pub fn set_equalizer(&self, bands: Vec<EqualizerBand>) {
+        self.command(PlayerInternalCmd::Equalizer(bands));
+    }
+
+    /// Change the crossfade duration, zero to disable crossfading
+    pub fn set_crossfade(&self, duration: Duration) {
+        self.command(PlayerInternalCmd::Crossfade(duration));
+    }
 }
 
 #[async_trait]
@@ -249,6 +288,11 @@ impl PlayerTrait for RustyBackend {
         ));
     }
 
+    fn requeue_next(&mut self, track: &Track) {
+        self.command(PlayerInternalCmd::DequeueNext);
+        self.enqueue_next(track);
+    }
+
     fn media_info(&self) -> MediaInfo {
         let media_title_r = self.media_title.lock();
         if media_title_r.is_empty() {
@@ -262,18 +306,21 @@ impl PlayerTrait for RustyBackend {
 }
 
 /// Append the `media_source` to the `sink`, while allowing different functions to run with `func` with a [`MediaTitleRx`]
+///
+/// The samples are multiplied by `gain`, see [`ReplayGain::factor`](termusiclib::track::ReplayGain::factor)
 fn append_to_sink_inner_media_title<F: FnOnce(&mut Symphonia, MediaTitleRx)>(
     media_source: Box<dyn MediaSource>,
     trace: &str,
     sink: &Sink,
     gapless: bool,
+    gain: f32,
     func: F,
 ) {
     let mss = MediaSourceStream::new(media_source, MediaSourceStreamOptions::default());
     match Symphonia::new_with_media_title(mss, gapless) {
         Ok((mut decoder, rx)) => {
             func(&mut decoder, rx);
-            sink.append(decoder);
+            sink.append(decoder.amplify(gain));
         }
         Err(e) => error!("error decoding '{trace}' is: {e:?}"),
     }
@@ -305,6 +352,7 @@ fn append_to_sink<MT: Fn(MediaTitleType) + Send + 'static>(
     trace: &str,
     sink: &Sink,
     gapless: bool,
+    gain: f32,
     total_duration_local: &ArcTotalDuration,
     media_title_fn: MT,
 ) {
@@ -313,6 +361,7 @@ fn append_to_sink<MT: Fn(MediaTitleType) + Send + 'static>(
         trace,
         sink,
         gapless,
+        gain,
         |decoder, mut media_title_rx| {
             std::mem::swap(
                 &mut *total_duration_local.lock(),
@@ -354,6 +403,7 @@ fn append_to_sink_queue<MT: Fn(MediaTitleType) + Send + 'static>(
     trace: &str,
     sink: &Sink,
     gapless: bool,
+    gain: f32,
     // total_duration_local: &ArcTotalDuration,
     next_duration_opt: &mut Option<Duration>,
     media_title_fn: MT,
@@ -363,6 +413,7 @@ fn append_to_sink_queue<MT: Fn(MediaTitleType) + Send + 'static>(
         trace,
         sink,
         gapless,
+        gain,
         |decoder, mut media_title_rx| {
             std::mem::swap(next_duration_opt, &mut decoder.total_duration());
             // rely on EOS message to set next duration
@@ -406,6 +457,32 @@ fn common_media_title_cb(media_title: Arc<Mutex<String>>) -> impl Fn(MediaTitleT
     }
 }
 
+/// Identifies a album by its artist and name, as different artists can have albums of the same name
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AlbumKey {
+    /// The album artist, or the track artist if not set
+    artist: String,
+    name: String,
+}
+
+impl AlbumKey {
+    /// Get the album of `track`, `None` if it has no album name
+    fn from_track(track: &Track) -> Option<Self> {
+        let name = track.album().filter(|v| !v.is_empty())?;
+        let artist = track.album_artist().or(track.artist()).unwrap_or_default();
+
+        Some(Self {
+            artist: artist.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Consecutive tracks of the same album are meant to flow into each other, so they are never crossfaded
+fn is_same_album(previous_album: Option<&AlbumKey>, next: &Track) -> bool {
+    previous_album.is_some_and(|previous| AlbumKey::from_track(next).as_ref() == Some(previous))
+}
+
 /// Player thread loop
 #[allow(
     clippy::cast_precision_loss,
@@ -425,16 +502,32 @@ async fn player_thread(
     position: Arc<Mutex<Duration>>,
     volume_inside: Arc<AtomicU16>,
     mut speed_inside: i32,
+    output_device: Option<String>,
+    mut replaygain: ReplayGainSettings,
+    mut equalizer: Vec<EqualizerBand>,
+    mut crossfade: Duration,
 ) {
     let mut is_radio = false;
+    // the album of the last started or enqueued track, to skip crossfading within albums
+    let mut current_album: Option<AlbumKey> = None;
+    // the album before the last "QueueNext", to compare against again when that track is dequeued
+    let mut album_before_next: Option<AlbumKey> = None;
+    // the enqueued track, if it will be crossfaded instead of being played gapless
+    let mut crossfade_next: Option<(Box<Track>, bool)> = None;
 
     // option to store enqueued's duration
     // note that the current implementation is only meant to have 1 enqueued next after the current playing song
     let mut next_duration_opt = None;
-    let (_stream, handle) = OutputStream::try_default().unwrap();
+    let (mut _stream, mut handle) = OutputStream::try_from_name(output_device.as_deref())
+        .or_else(|err| {
+            error!("Failed to open output device {output_device:?}, using default device: {err}");
+            OutputStream::try_default()
+        })
+        .unwrap();
     let mut sink = Sink::try_new(&handle, picmd_tx.clone(), pcmd_tx.clone()).unwrap();
     sink.set_speed(speed_inside as f32 / 10.0);
     sink.set_volume(f32::from(volume_inside.load(Ordering::SeqCst)) / 100.0);
+    sink.set_equalizer(equalizer.clone());
     loop {
         let Ok(cmd) = picmd_rx.recv() else {
             // only error can be a disconnect (no more senders)
@@ -443,6 +536,8 @@ async fn player_thread(
 
         match cmd {
             PlayerInternalCmd::Play(track, gapless) => {
+                crossfade_next = None;
+                current_album = AlbumKey::from_track(&track);
                 if let Err(err) = queue_next(
                     &track,
                     gapless,
@@ -452,6 +547,7 @@ async fn player_thread(
                     &mut next_duration_opt,
                     &media_title,
                     // &radio_downloaded,
+                    &replaygain,
                     false,
                 )
                 .await
@@ -463,6 +559,15 @@ async fn player_thread(
                 sink.toggle_playback();
             }
             PlayerInternalCmd::QueueNext(track, gapless) => {
+                let same_album = is_same_album(current_album.as_ref(), &track);
+                album_before_next =
+                    std::mem::replace(&mut current_album, AlbumKey::from_track(&track));
+                // the track is only started once the current one is about to end, see "Progress"
+                if !crossfade.is_zero() && !same_album && track.media_type != MediaType::LiveRadio {
+                    crossfade_next = Some((track, gapless));
+                    continue;
+                }
+
                 if let Err(err) = queue_next(
                     &track,
                     gapless,
@@ -472,6 +577,7 @@ async fn player_thread(
                     &mut next_duration_opt,
                     &media_title,
                     // &radio_downloaded,
+                    &replaygain,
                     true,
                 )
                 .await
@@ -479,6 +585,12 @@ async fn player_thread(
                     error!("Failed to queue next track: {:#?}", err);
                 }
             }
+            PlayerInternalCmd::DequeueNext => {
+                crossfade_next = None;
+                next_duration_opt = None;
+                current_album = album_before_next.take();
+                sink.clear_queued();
+            }
             PlayerInternalCmd::Resume => {
                 sink.play();
             }
@@ -487,15 +599,19 @@ async fn player_thread(
                 sink.set_speed(speed_inside as f32 / 10.0);
             }
             PlayerInternalCmd::Stop => {
+                crossfade_next = None;
                 sink = Sink::try_new(&handle, picmd_tx.clone(), pcmd_tx.clone()).unwrap();
                 sink.set_speed(speed_inside as f32 / 10.0);
                 sink.set_volume(f32::from(volume_inside.load(Ordering::SeqCst)) / 100.0);
+                sink.set_equalizer(equalizer.clone());
             }
             PlayerInternalCmd::Volume(volume) => {
                 sink.set_volume(f32::from(volume) / 100.0);
                 volume_inside.store(volume, Ordering::SeqCst);
             }
             PlayerInternalCmd::Skip => {
+                crossfade_next = None;
+                sink.finish_crossfade();
                 // the sink can be empty, if for example nothing could be enqueued, so a "skip_one" would be a no-op and never send EOS, which is required to go to the next track
                 if sink.empty() {
                     let _ = picmd_tx.send(PlayerInternalCmd::Eos);
@@ -513,20 +629,47 @@ async fn player_thread(
                 *position.lock() = new_position;
 
                 // About to finish signal is a simulation of gstreamer, and used for gapless
-                if !is_radio {
-                    if let Some(d) = *total_duration.lock() {
-                        let progress = new_position.as_secs_f64() / d.as_secs_f64();
-                        if progress >= 0.5
-                            && d.saturating_sub(new_position) < Duration::from_secs(2)
-                        {
-                            if let Err(e) = pcmd_tx.send(PlayerCmd::AboutToFinish) {
-                                error!("command AboutToFinish sent failed: {e}");
-                            }
-                        }
+                let Some(d) = (*total_duration.lock()).filter(|_| !is_radio) else {
+                    continue;
+                };
+                let progress = new_position.as_secs_f64() / d.as_secs_f64();
+                let remaining = d.saturating_sub(new_position);
+                // the next track needs to be known before the crossfade starts
+                if progress >= 0.5 && remaining < Duration::from_secs(2) + crossfade {
+                    if let Err(e) = pcmd_tx.send(PlayerCmd::AboutToFinish) {
+                        error!("command AboutToFinish sent failed: {e}");
                     }
                 }
+
+                if remaining > crossfade || sink.is_paused() {
+                    continue;
+                }
+                let Some((track, gapless)) = crossfade_next.take() else {
+                    continue;
+                };
+                // the outgoing track keeps reporting until it has ended, see "Eos"
+                match sink.start_crossfade(&handle, crossfade) {
+                    Ok(()) => info!("Crossfading into the next track"),
+                    Err(err) => error!("Failed to start crossfade, playing gapless: {err}"),
+                }
+                if let Err(err) = queue_next(
+                    &track,
+                    gapless,
+                    &sink,
+                    &mut is_radio,
+                    &total_duration,
+                    &mut next_duration_opt,
+                    &media_title,
+                    &replaygain,
+                    true,
+                )
+                .await
+                {
+                    error!("Failed to queue next track: {:#?}", err);
+                }
             }
             PlayerInternalCmd::SeekAbsolute(position) => {
+                sink.finish_crossfade();
                 sink.seek(position);
             }
             PlayerInternalCmd::MessageOnEnd => {
@@ -534,6 +677,7 @@ async fn player_thread(
             }
 
             PlayerInternalCmd::SeekRelative(offset) => {
+                sink.finish_crossfade();
                 let paused = sink.is_paused();
                 if paused {
                     sink.set_volume(0.0);
@@ -559,7 +703,54 @@ async fn player_thread(
                 }
             }
 
+            PlayerInternalCmd::SetOutputDevice(device) => {
+                // only replace the old stream once the new one could be opened, so that a failure keeps the old device
+                match OutputStream::try_from_name(device.as_deref()) {
+                    Ok((new_stream, new_handle)) => {
+                        info!("Switched output device to {device:?}");
+                        _stream = new_stream;
+                        handle = new_handle;
+                        sink = Sink::try_new(&handle, picmd_tx.clone(), pcmd_tx.clone()).unwrap();
+                        sink.set_speed(speed_inside as f32 / 10.0);
+                        sink.set_volume(f32::from(volume_inside.load(Ordering::SeqCst)) / 100.0);
+                        sink.set_equalizer(equalizer.clone());
+                    }
+                    Err(err) => error!("Failed to switch output device to {device:?}: {err}"),
+                }
+            }
+
+            PlayerInternalCmd::ReplayGain(settings) => {
+                replaygain = settings;
+            }
+            PlayerInternalCmd::Equalizer(bands) => {
+                sink.set_equalizer(bands.clone());
+                equalizer = bands;
+            }
+            PlayerInternalCmd::Crossfade(duration) => {
+                crossfade = duration;
+            }
+
             PlayerInternalCmd::Eos => {
+                // the outgoing track of a crossfade has ended
+                sink.finish_crossfade();
+                // the current track ended before the crossfade could start, play the next one gapless instead
+                if let Some((track, gapless)) = crossfade_next.take() {
+                    if let Err(err) = queue_next(
+                        &track,
+                        gapless,
+                        &sink,
+                        &mut is_radio,
+                        &total_duration,
+                        &mut next_duration_opt,
+                        &media_title,
+                        &replaygain,
+                        true,
+                    )
+                    .await
+                    {
+                        error!("Failed to queue next track: {:#?}", err);
+                    }
+                }
                 // replace the current total_duration with the next one
                 // this is only present when QueueNext was used; which is only used if gapless is enabled
                 if next_duration_opt.is_some() {
@@ -581,9 +772,11 @@ async fn queue_next(
     total_duration: &ArcTotalDuration,
     next_duration_opt: &mut Option<Duration>,
     media_title: &Arc<Mutex<String>>,
+    replaygain: &ReplayGainSettings,
     enqueue: bool,
 ) -> Result<()> {
     let media_type = &track.media_type;
+    let gain = track.replaygain().factor(replaygain);
     let file_path = track
         .file()
         .ok_or_else(|| anyhow!("No file path found"))?
@@ -599,6 +792,7 @@ async fn queue_next(
                     &file_path,
                     sink,
                     gapless,
+                    gain,
                     next_duration_opt,
                     common_media_title_cb(media_title.clone()),
                 );
@@ -608,6 +802,7 @@ async fn queue_next(
                     &file_path,
                     sink,
                     gapless,
+                    gain,
                     total_duration,
                     common_media_title_cb(media_title.clone()),
                 );
@@ -627,6 +822,7 @@ async fn queue_next(
                         &file_path,
                         sink,
                         gapless,
+                        gain,
                         next_duration_opt,
                         common_media_title_cb(media_title.clone()),
                     );
@@ -636,6 +832,7 @@ async fn queue_next(
                         &file_path,
                         sink,
                         gapless,
+                        gain,
                         total_duration,
                         common_media_title_cb(media_title.clone()),
                     );
@@ -663,6 +860,7 @@ async fn queue_next(
                     &url,
                     sink,
                     gapless,
+                    gain,
                     next_duration_opt,
                     common_media_title_cb(media_title.clone()),
                 );
@@ -672,6 +870,7 @@ async fn queue_next(
                     &url,
                     sink,
                     gapless,
+                    gain,
                     total_duration,
                     common_media_title_cb(media_title.clone()),
                 );
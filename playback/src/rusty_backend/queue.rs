@@ -100,6 +100,28 @@ where
         sounds.clear();
         len
     }
+
+    /// Adds a new source to the end of the queue, signalling the given `Sender` when it has finished playing.
+    #[inline]
+    pub fn append_with_sender<T>(&self, source: T, signal: Sender<()>)
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        self.next_sounds
+            .lock()
+            .unwrap()
+            .push((Box::new(source) as Box<_>, Some(signal)));
+    }
+
+    /// Removes all the sounds from the queue, but returns their signals instead of dropping them.
+    pub fn clear_keep_signals(&self) -> Vec<Option<Sender<()>>> {
+        self.next_sounds
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|(_, signal)| signal)
+            .collect()
+    }
 }
 
 /// The output of the queue. Implements `Source`.
@@ -9,6 +9,7 @@ use super::stream::{OutputStreamHandle, PlayError};
 use super::{queue, source::Done, PlayerInternalCmd, Sample, Source};
 use crate::PlayerCmd;
 use cpal::FromSample;
+use termusiclib::config::v2::server::EqualizerBand;
 
 /// Handle to an device that outputs sounds.
 ///
@@ -16,7 +17,13 @@ use cpal::FromSample;
 /// playing.
 pub struct Sink {
     queue_tx: Arc<queue::SourcesQueueInput<f32>>,
+    /// Controls of the sounds in `queue_tx`
+    queue_controls: Arc<QueueControls>,
+    /// Controls of the outgoing queue while a crossfade is running
+    fading_controls: Option<Arc<QueueControls>>,
     sleep_until_end: Mutex<Option<Receiver<()>>>,
+    /// End signal of a sound removed by [`Sink::clear_queued`], handed over to the next appended sound
+    requeue_signal: Mutex<Option<Sender<()>>>,
     controls: Arc<Controls>,
     sound_count: Arc<AtomicUsize>,
     detached: bool,
@@ -32,6 +39,32 @@ struct Controls {
     stopped: AtomicBool,
     speed: Mutex<f32>,
     to_clear: Mutex<u32>,
+    equalizer: Mutex<Vec<EqualizerBand>>,
+    /// Increased on every change of `equalizer`, so that sources only need to re-calculate their filters on change
+    equalizer_version: AtomicUsize,
+}
+
+/// Controls that only apply to the sounds of one queue, of which there are two while crossfading
+struct QueueControls {
+    /// Start fading out the current sound over the duration
+    fade_out: Mutex<Option<Duration>>,
+    /// Set once a crossfade started, from then on the sounds only take the shared controls like volume
+    outgoing: AtomicBool,
+    /// Stop the sounds of this queue, without affecting the other queue
+    stopped: AtomicBool,
+    /// Whether the sounds send their progress, so that only one queue reports while two are playing
+    report_progress: AtomicBool,
+}
+
+impl QueueControls {
+    fn new(report_progress: bool) -> Self {
+        Self {
+            fade_out: Mutex::new(None),
+            outgoing: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+            report_progress: AtomicBool::new(report_progress),
+        }
+    }
 }
 
 impl Sink {
@@ -58,7 +91,10 @@ impl Sink {
 
         let sink = Sink {
             queue_tx,
+            queue_controls: Arc::new(QueueControls::new(true)),
+            fading_controls: None,
             sleep_until_end: Mutex::new(None),
+            requeue_signal: Mutex::new(None),
             controls: Arc::new(Controls {
                 pause: AtomicBool::new(false),
                 volume: Mutex::new(1.0),
@@ -66,6 +102,8 @@ impl Sink {
                 seek: Mutex::new(None),
                 speed: Mutex::new(1.0),
                 to_clear: Mutex::new(0),
+                equalizer: Mutex::new(Vec::new()),
+                equalizer_version: AtomicUsize::new(0),
             }),
             sound_count: Arc::new(AtomicUsize::new(0)),
             detached: false,
@@ -94,6 +132,8 @@ impl Sink {
         }
 
         let controls = self.controls.clone();
+        let queue_controls = self.queue_controls.clone();
+        let queue_controls_progress = self.queue_controls.clone();
         let controls_tempo = self.controls.clone();
 
         let start_played = AtomicBool::new(false);
@@ -101,24 +141,35 @@ impl Sink {
         let tx = self.message_tx.clone();
         let elapsed = self.elapsed.clone();
         let source = source
+            .fade_out()
             .speed(1.0)
             .pausable(false)
             .amplify(1.0)
             .skippable()
             .stoppable()
             .periodic_access(Duration::from_millis(500), move |src| {
-                tx.send(PlayerInternalCmd::Progress(src.elapsed())).ok();
+                if queue_controls_progress
+                    .report_progress
+                    .load(Ordering::SeqCst)
+                {
+                    tx.send(PlayerInternalCmd::Progress(src.elapsed())).ok();
+                }
             })
             .periodic_access(Duration::from_millis(5), move |src| {
                 let src = src.inner_mut();
-                if controls.stopped.load(Ordering::SeqCst) {
+                if controls.stopped.load(Ordering::SeqCst)
+                    || queue_controls.stopped.load(Ordering::SeqCst)
+                {
                     src.stop();
                 } else {
-                    if let Some(seek_time) = controls.seek.lock().take() {
-                        src.seek(seek_time);
-                    }
-                    *elapsed.write() = src.elapsed();
-                    {
+                    // the outgoing sound of a crossfade must not take commands meant for the incoming one
+                    let is_current = !queue_controls.outgoing.load(Ordering::SeqCst);
+                    if is_current {
+                        if let Some(seek_time) = controls.seek.lock().take() {
+                            src.seek(seek_time);
+                        }
+                        *elapsed.write() = src.elapsed();
+
                         let mut to_clear = controls.to_clear.lock();
                         if *to_clear > 0 {
                             src.inner_mut().skip();
@@ -136,11 +187,28 @@ impl Sink {
                             .inner_mut()
                             .set_factor(*controls.speed.lock());
                     }
+                    if let Some(duration) = queue_controls.fade_out.lock().take() {
+                        amp.inner_mut().inner_mut().inner_mut().start(duration);
+                    }
 
                     start_played.store(true, Ordering::SeqCst);
                 }
             })
-            .convert_samples();
+            .convert_samples::<f32>();
+
+        let controls_eq = self.controls.clone();
+        let mut equalizer_version = controls_eq.equalizer_version.load(Ordering::SeqCst);
+        let bands = controls_eq.equalizer.lock().clone();
+        let source =
+            source
+                .equalizer(&bands)
+                .periodic_access(Duration::from_millis(50), move |src| {
+                    let version = controls_eq.equalizer_version.load(Ordering::SeqCst);
+                    if version != equalizer_version {
+                        equalizer_version = version;
+                        src.set_bands(&controls_eq.equalizer.lock());
+                    }
+                });
 
         #[cfg(feature = "rusty-soundtouch")]
         let source =
@@ -153,7 +221,12 @@ impl Sink {
         self.sound_count.fetch_add(1, Ordering::Relaxed);
         let source = Done::new(source, self.sound_count.clone());
         // let source = super::source::scaletempo::tempo_stretch(source, 1.3);
-        *self.sleep_until_end.lock() = Some(self.queue_tx.append_with_signal(source));
+        // the end of a removed sound may already be waited on, which now waits for this sound instead
+        if let Some(signal) = self.requeue_signal.lock().take() {
+            self.queue_tx.append_with_sender(source, signal);
+        } else {
+            *self.sleep_until_end.lock() = Some(self.queue_tx.append_with_signal(source));
+        }
     }
 
     /// Gets the volume of the sound.
@@ -192,6 +265,56 @@ impl Sink {
         *self.controls.speed.lock() = value;
     }
 
+    /// Changes the equalizer bands of all sounds, an empty list disables the equalizer.
+    #[inline]
+    pub fn set_equalizer(&self, bands: Vec<EqualizerBand>) {
+        *self.controls.equalizer.lock() = bands;
+        self.controls
+            .equalizer_version
+            .fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Starts a crossfade over `duration`: the current sound fades out and ends, while sounds
+    /// appended afterwards fade in from silence at the same time.
+    ///
+    /// Both are mixed by the stream's mixer and share all controls of this sink, the outgoing
+    /// sound keeps reporting progress until [`Sink::finish_crossfade`] is called.
+    pub fn start_crossfade(
+        &mut self,
+        stream: &OutputStreamHandle,
+        duration: Duration,
+    ) -> Result<(), PlayError> {
+        let (queue_tx, queue_rx) = queue::queue(true);
+        stream.play_raw(queue_rx.fade_in(duration))?;
+
+        // a still running crossfade is cut short
+        self.finish_crossfade();
+
+        let old_queue_tx = std::mem::replace(&mut self.queue_tx, queue_tx);
+        old_queue_tx.set_keep_alive_if_empty(false);
+        let old_controls = std::mem::replace(
+            &mut self.queue_controls,
+            Arc::new(QueueControls::new(false)),
+        );
+        old_controls.outgoing.store(true, Ordering::SeqCst);
+        *old_controls.fade_out.lock() = Some(duration);
+        self.fading_controls = Some(old_controls);
+
+        Ok(())
+    }
+
+    /// Ends a running crossfade by stopping the outgoing sound, the incoming one takes over progress reporting.
+    ///
+    /// No effect if no crossfade is running.
+    pub fn finish_crossfade(&mut self) {
+        if let Some(fading_controls) = self.fading_controls.take() {
+            fading_controls.stopped.store(true, Ordering::SeqCst);
+            self.queue_controls
+                .report_progress
+                .store(true, Ordering::SeqCst);
+        }
+    }
+
     /// Resumes playback of a paused sink.
     ///
     /// No effect if not paused.
@@ -242,6 +365,18 @@ impl Sink {
         self.pause();
     }
 
+    /// Removes the sounds queued after the current one, without affecting the current one
+    ///
+    /// Their end signal is kept for the next appended sound, so that [`Sink::message_on_end`]
+    /// does not report an end while the current sound is still playing.
+    pub fn clear_queued(&self) {
+        let signals = self.queue_tx.clear_keep_signals();
+        self.sound_count.fetch_sub(signals.len(), Ordering::SeqCst);
+        if let Some(signal) = signals.into_iter().flatten().last() {
+            *self.requeue_signal.lock() = Some(signal);
+        }
+    }
+
     /// Skips to the next `Source` in the `Sink`
     ///
     /// If there are more `Source`s appended to the `Sink` at the time,
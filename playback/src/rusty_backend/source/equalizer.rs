@@ -0,0 +1,299 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use termusiclib::config::v2::server::{EqualizerBand, EqualizerFilter};
+
+use super::Source;
+
+/// Internal function that builds a `Equalizer` object.
+pub fn equalizer<I>(input: I, bands: &[EqualizerBand]) -> Equalizer<I>
+where
+    I: Source<Item = f32>,
+{
+    let mut equalizer = Equalizer {
+        channels: input.channels(),
+        sample_rate: input.sample_rate(),
+        input,
+        bands: Vec::new(),
+        filters: Vec::new(),
+        states: Vec::new(),
+        current_channel: 0,
+    };
+    equalizer.set_bands(bands);
+
+    equalizer
+}
+
+/// Coefficients of a single biquad filter, already normalized by `a0`
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefficients {
+    /// Calculate the coefficients as per the "Audio EQ Cookbook" by Robert Bristow-Johnson
+    ///
+    /// Returns `None` if the band cannot be represented at the given sample rate
+    #[allow(clippy::cast_precision_loss)]
+    fn new(band: &EqualizerBand, sample_rate: u32) -> Option<Self> {
+        let nyquist = sample_rate as f32 / 2.0;
+        if band.frequency <= 0.0 || band.frequency >= nyquist || band.q <= 0.0 {
+            return None;
+        }
+
+        let a = 10f32.powf(band.gain / 40.0);
+        let w0 = 2.0 * PI * band.frequency / sample_rate as f32;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        let alpha = sin_w0 / (2.0 * band.q);
+
+        let (b0, b1, b2, a0, a1, a2) = match band.kind {
+            EqualizerFilter::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos_w0,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos_w0,
+                1.0 - alpha / a,
+            ),
+            EqualizerFilter::LowShelf => {
+                let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 + sqrt_a_alpha),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 - sqrt_a_alpha),
+                    (a + 1.0) + (a - 1.0) * cos_w0 + sqrt_a_alpha,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    (a + 1.0) + (a - 1.0) * cos_w0 - sqrt_a_alpha,
+                )
+            }
+            EqualizerFilter::HighShelf => {
+                let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 + sqrt_a_alpha),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 - sqrt_a_alpha),
+                    (a + 1.0) - (a - 1.0) * cos_w0 + sqrt_a_alpha,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    (a + 1.0) - (a - 1.0) * cos_w0 - sqrt_a_alpha,
+                )
+            }
+        };
+
+        Some(Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        })
+    }
+}
+
+/// The delay line of a single biquad filter for a single channel
+#[derive(Clone, Copy, Debug, Default)]
+struct State {
+    z1: f32,
+    z2: f32,
+}
+
+impl State {
+    /// Run `input` through the filter, in "Transposed Direct Form II"
+    #[inline]
+    fn process(&mut self, coeffs: &Coefficients, input: f32) -> f32 {
+        let output = coeffs.b0 * input + self.z1;
+        self.z1 = coeffs.b1 * input - coeffs.a1 * output + self.z2;
+        self.z2 = coeffs.b2 * input - coeffs.a2 * output;
+
+        output
+    }
+}
+
+/// Filter that applies a chain of biquad filters to each channel.
+#[derive(Clone, Debug)]
+pub struct Equalizer<I> {
+    input: I,
+    bands: Vec<EqualizerBand>,
+    filters: Vec<Coefficients>,
+    /// One state per filter per channel, grouped by channel
+    states: Vec<State>,
+    channels: u16,
+    sample_rate: u32,
+    /// The channel the next sample belongs to
+    current_channel: usize,
+}
+
+#[allow(unused)]
+impl<I> Equalizer<I>
+where
+    I: Source<Item = f32>,
+{
+    /// Replace the bands, an empty slice disables the equalizer.
+    pub fn set_bands(&mut self, bands: &[EqualizerBand]) {
+        bands.clone_into(&mut self.bands);
+        self.update_filters();
+    }
+
+    /// Re-calculate the filters for the current bands and format and reset the filter states.
+    fn update_filters(&mut self) {
+        self.filters = self
+            .bands
+            .iter()
+            // a band without gain does not change anything
+            .filter(|band| band.gain != 0.0)
+            .filter_map(|band| Coefficients::new(band, self.sample_rate))
+            .collect();
+        self.states = vec![State::default(); self.filters.len() * usize::from(self.channels)];
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for Equalizer<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        // the format can only change at the start of a frame
+        if self.current_channel == 0 {
+            let channels = self.input.channels();
+            let sample_rate = self.input.sample_rate();
+            if channels != self.channels || sample_rate != self.sample_rate {
+                self.channels = channels;
+                self.sample_rate = sample_rate;
+                self.update_filters();
+            }
+        }
+
+        let mut sample = self.input.next()?;
+
+        let channel = self.current_channel;
+        self.current_channel = (channel + 1) % usize::from(self.channels.max(1));
+
+        if self.filters.is_empty() {
+            return Some(sample);
+        }
+
+        let states = &mut self.states[channel * self.filters.len()..][..self.filters.len()];
+        for (state, coeffs) in states.iter_mut().zip(&self.filters) {
+            sample = state.process(coeffs, sample);
+        }
+
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Equalizer<I> where I: Source<Item = f32> + ExactSizeIterator {}
+
+impl<I> Source for Equalizer<I>
+where
+    I: Source<Item = f32>,
+{
+    #[inline]
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn elapsed(&mut self) -> Duration {
+        self.input.elapsed()
+    }
+
+    fn seek(&mut self, time: Duration) -> Option<Duration> {
+        // the old samples are not related to the new position anymore
+        self.states.fill(State::default());
+        self.input.seek(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rusty_backend::buffer::SamplesBuffer;
+
+    fn band(kind: EqualizerFilter, frequency: f32, gain: f32) -> EqualizerBand {
+        EqualizerBand {
+            kind,
+            frequency,
+            gain,
+            q: 1.41,
+        }
+    }
+
+    #[test]
+    fn should_pass_through_without_gain() {
+        let data = vec![0.5f32, -0.5, 0.25, -0.25, 0.0, 1.0];
+        let source = SamplesBuffer::new(2, 44100, data.clone());
+        let output: Vec<f32> =
+            equalizer(source, &[band(EqualizerFilter::Peaking, 1000.0, 0.0)]).collect();
+
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn should_apply_shelf_gain_to_dc() {
+        // a constant signal has a frequency of 0 Hz, so only the low shelf affects it
+        let data = vec![0.1f32; 44100];
+        let source = SamplesBuffer::new(1, 44100, data);
+        let output: Vec<f32> = equalizer(
+            source,
+            &[
+                band(EqualizerFilter::LowShelf, 200.0, 6.0),
+                band(EqualizerFilter::HighShelf, 5000.0, -6.0),
+            ],
+        )
+        .collect();
+
+        let last = *output.last().unwrap();
+        // +6 dB is about double the amplitude
+        assert!((last - 0.1995).abs() < 0.001, "got {last}");
+    }
+
+    #[test]
+    fn should_ignore_bands_above_nyquist() {
+        assert!(Coefficients::new(&band(EqualizerFilter::Peaking, 30000.0, 3.0), 44100).is_none());
+        assert!(Coefficients::new(&band(EqualizerFilter::Peaking, 1000.0, 3.0), 44100).is_some());
+    }
+}
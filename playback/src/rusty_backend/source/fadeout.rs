@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use super::{Sample, Source};
+
+/// Internal function that builds a `FadeOut` object.
+pub fn fadeout<I>(input: I) -> FadeOut<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    FadeOut {
+        input,
+        remaining_ns: None,
+        total_ns: 0.0,
+        current_channel: 0,
+    }
+}
+
+/// Filter that lowers the volume to silence over a time period once started, and then ends.
+///
+/// Counterpart to [`FadeIn`](super::FadeIn).
+#[derive(Clone, Debug)]
+pub struct FadeOut<I> {
+    input: I,
+    /// `None` while not fading
+    remaining_ns: Option<f32>,
+    total_ns: f32,
+    /// The channel the next sample belongs to, so that the source only ends on a frame boundary
+    current_channel: u16,
+}
+
+#[allow(unused)]
+impl<I> FadeOut<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    /// Start fading out over `duration`, no effect if already fading.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn start(&mut self, duration: Duration) {
+        if self.remaining_ns.is_some() {
+            return;
+        }
+
+        let duration = duration.as_nanos() as f32;
+        self.remaining_ns = Some(duration);
+        self.total_ns = duration;
+    }
+
+    /// Returns `true` if the fade-out has been started.
+    #[inline]
+    pub fn is_fading(&self) -> bool {
+        self.remaining_ns.is_some()
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for FadeOut<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    #[inline]
+    #[allow(clippy::cast_precision_loss)]
+    fn next(&mut self) -> Option<I::Item> {
+        let Some(remaining_ns) = self.remaining_ns else {
+            return self.input.next();
+        };
+
+        let frame_start = self.current_channel == 0;
+        if frame_start && remaining_ns <= 0.0 {
+            return None;
+        }
+
+        self.current_channel = (self.current_channel + 1) % self.input.channels().max(1);
+        if frame_start {
+            self.remaining_ns =
+                Some(remaining_ns - 1_000_000_000.0 / self.input.sample_rate() as f32);
+        }
+
+        let factor = (remaining_ns / self.total_ns).clamp(0.0, 1.0);
+        self.input.next().map(|value| value.amplify(factor))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        if self.is_fading() {
+            (0, upper)
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+impl<I> Source for FadeOut<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    #[inline]
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn elapsed(&mut self) -> Duration {
+        self.input.elapsed()
+    }
+
+    fn seek(&mut self, time: Duration) -> Option<Duration> {
+        self.input.seek(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rusty_backend::buffer::SamplesBuffer;
+
+    #[test]
+    fn should_pass_through_until_started() {
+        let data = vec![0.5f32; 8];
+        let source = SamplesBuffer::new(2, 4, data.clone());
+        let output: Vec<f32> = fadeout(source).collect();
+
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn should_fade_to_silence_and_end() {
+        // 2 channels at 4 Hz, so 1 second are 4 frames
+        let source = SamplesBuffer::new(2, 4, vec![1.0f32; 40]);
+        let mut source = fadeout(source);
+        source.start(Duration::from_secs(1));
+        let output: Vec<f32> = source.collect();
+
+        assert_eq!(output, vec![1.0, 1.0, 0.75, 0.75, 0.5, 0.5, 0.25, 0.25]);
+    }
+}
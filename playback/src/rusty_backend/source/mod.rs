@@ -2,13 +2,17 @@
 
 use std::time::Duration;
 
+use termusiclib::config::v2::server::EqualizerBand;
+
 use super::Sample;
 
 pub use self::amplify::Amplify;
 pub use self::delay::Delay;
 pub use self::done::Done;
 pub use self::empty::Empty;
-// pub use self::fadein::FadeIn;
+pub use self::equalizer::Equalizer;
+pub use self::fadein::FadeIn;
+pub use self::fadeout::FadeOut;
 #[allow(clippy::module_name_repetitions)]
 #[allow(unused_imports)]
 pub use self::mix_source::MixSource;
@@ -28,7 +32,9 @@ mod amplify;
 mod delay;
 mod done;
 mod empty;
-// mod fadein;
+mod equalizer;
+mod fadein;
+mod fadeout;
 mod mix_source;
 // mod http;
 mod pausable;
@@ -159,14 +165,33 @@ where
         amplify::amplify(self, value)
     }
 
-    // /// Fades in the sound.
-    // #[inline]
-    // fn fade_in(self, duration: Duration) -> FadeIn<Self>
-    // where
-    //     Self: Sized,
-    // {
-    //     fadein::fadein(self, duration)
-    // }
+    /// Applies the given equalizer bands to every channel.
+    #[inline]
+    fn equalizer(self, bands: &[EqualizerBand]) -> Equalizer<Self>
+    where
+        Self: Sized,
+        Self: Source<Item = f32>,
+    {
+        equalizer::equalizer(self, bands)
+    }
+
+    /// Fades in the sound.
+    #[inline]
+    fn fade_in(self, duration: Duration) -> FadeIn<Self>
+    where
+        Self: Sized,
+    {
+        fadein::fadein(self, duration)
+    }
+
+    /// Allows fading out the sound once [`FadeOut::start`] is called, the source ends afterwards.
+    #[inline]
+    fn fade_out(self) -> FadeOut<Self>
+    where
+        Self: Sized,
+    {
+        fadeout::fadeout(self)
+    }
 
     /// Calls the `access` closure on `Self` the first time the source is iterated and every
     /// time `period` elapses.
@@ -52,6 +52,23 @@ impl OutputStream {
         Ok((out, handle))
     }
 
+    /// Return a new stream & handle using the output device with the given name.
+    ///
+    /// Uses the default output device if `name` is `None`.
+    pub fn try_from_name(name: Option<&str>) -> Result<(Self, OutputStreamHandle), StreamError> {
+        let Some(name) = name else {
+            return Self::try_default();
+        };
+
+        let device = cpal::default_host()
+            .output_devices()
+            .map_err(|_| StreamError::NoDevice)?
+            .find(|d| d.name().is_ok_and(|v| v == name))
+            .ok_or(StreamError::NoDevice)?;
+
+        Self::try_from_device(&device)
+    }
+
     /// Return a new stream & handle using the default output device.
     ///
     /// On failure will fallback to trying any non-default output devices.
@@ -75,6 +92,15 @@ impl OutputStream {
     }
 }
 
+/// Get the names of all available output devices
+pub fn output_device_names() -> Vec<String> {
+    let Ok(devices) = cpal::default_host().output_devices() else {
+        return Vec::new();
+    };
+
+    devices.filter_map(|d| d.name().ok()).collect()
+}
+
 impl OutputStreamHandle {
     /// Plays a source with a device until it ends.
     pub fn play_raw<S>(&self, source: S) -> Result<(), PlayError>
@@ -0,0 +1,263 @@
+//! Named playlists saved by the server
+//!
+//! Every playlist is a `.m3u` file in `$config$/playlists`, containing absolute paths for local files
+//! and urls for podcast episodes and radio streams.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use termusiclib::utils::get_app_config_path;
+
+const PLAYLISTS_DIR: &str = "playlists";
+const EXTENSION: &str = "m3u";
+
+/// Summary of a saved playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedPlaylistInfo {
+    pub name: String,
+    pub track_count: usize,
+}
+
+/// Operations on the saved playlists
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedPlaylistCmd {
+    /// Get all saved playlists
+    List,
+    /// Save the given paths or urls under a new name, `None` saves the current queue
+    Create {
+        name: String,
+        tracks: Option<Vec<String>>,
+    },
+    Rename {
+        from: String,
+        to: String,
+    },
+    Delete(String),
+    /// Add the given paths or urls to the end of a saved playlist, `None` adds the current queue
+    Append {
+        name: String,
+        tracks: Option<Vec<String>>,
+    },
+    /// Add a saved playlist to the queue, replacing the current queue if `replace` is set
+    Load {
+        name: String,
+        replace: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SavedPlaylists {
+    dir: PathBuf,
+}
+
+impl SavedPlaylists {
+    /// Use the playlists in `$config$/playlists`
+    ///
+    /// # Errors
+    /// if the config directory cannot be found or the playlists directory cannot be created
+    pub fn new() -> Result<Self> {
+        Self::with_dir(get_app_config_path()?.join(PLAYLISTS_DIR))
+    }
+
+    /// Use the playlists in the given directory, creating it if necessary
+    ///
+    /// # Errors
+    /// if the directory cannot be created
+    pub fn with_dir(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("creating playlists directory {}", dir.display()))?;
+
+        Ok(Self { dir })
+    }
+
+    /// Get all saved playlists, sorted by name
+    ///
+    /// # Errors
+    /// if the playlists directory cannot be read
+    pub fn list(&self) -> Result<Vec<SavedPlaylistInfo>> {
+        let mut playlists = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |v| v != EXTENSION) {
+                continue;
+            }
+            let Some(name) = path.file_stem().map(|v| v.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let track_count = match std::fs::read_to_string(&path) {
+                Ok(content) => parse(&content).len(),
+                Err(err) => {
+                    warn!("Error reading playlist {}: {err}", path.display());
+                    continue;
+                }
+            };
+
+            playlists.push(SavedPlaylistInfo { name, track_count });
+        }
+        playlists.sort_by_cached_key(|v| v.name.to_lowercase());
+
+        Ok(playlists)
+    }
+
+    /// Get the paths and urls of a saved playlist
+    ///
+    /// # Errors
+    /// if the name is invalid or the playlist does not exist
+    pub fn get(&self, name: &str) -> Result<Vec<String>> {
+        let path = self.path(name)?;
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Playlist \"{name}\" does not exist"))?;
+
+        Ok(parse(&content))
+    }
+
+    /// Save a new playlist
+    ///
+    /// # Errors
+    /// if the name is invalid, the playlist already exists or writing fails
+    pub fn create(&self, name: &str, tracks: &[String]) -> Result<()> {
+        let path = self.path(name)?;
+        if path.exists() {
+            bail!("Playlist \"{name}\" already exists");
+        }
+
+        std::fs::write(path, serialize(tracks))?;
+        Ok(())
+    }
+
+    /// Add tracks to the end of a saved playlist
+    ///
+    /// # Errors
+    /// if the name is invalid, the playlist does not exist or writing fails
+    pub fn append(&self, name: &str, tracks: &[String]) -> Result<()> {
+        let mut all = self.get(name)?;
+        all.extend_from_slice(tracks);
+
+        std::fs::write(self.path(name)?, serialize(&all))?;
+        Ok(())
+    }
+
+    /// # Errors
+    /// if any name is invalid, `from` does not exist or `to` already exists
+    pub fn rename(&self, from: &str, to: &str) -> Result<()> {
+        let from_path = self.path(from)?;
+        let to_path = self.path(to)?;
+        if !from_path.exists() {
+            bail!("Playlist \"{from}\" does not exist");
+        }
+        if to_path.exists() {
+            bail!("Playlist \"{to}\" already exists");
+        }
+
+        std::fs::rename(from_path, to_path)?;
+        Ok(())
+    }
+
+    /// # Errors
+    /// if the name is invalid or the playlist does not exist
+    pub fn delete(&self, name: &str) -> Result<()> {
+        std::fs::remove_file(self.path(name)?)
+            .with_context(|| format!("Playlist \"{name}\" does not exist"))
+    }
+
+    /// Get the file path for the playlist name, checking that the name stays within the playlists directory
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Playlist name cannot be empty");
+        }
+        if name.starts_with('.') || name.contains(['/', '\\']) {
+            bail!("Playlist name \"{name}\" cannot start with \".\" or contain \"/\" or \"\\\"");
+        }
+
+        Ok(self.dir.join(format!("{name}.{EXTENSION}")))
+    }
+}
+
+/// Get the paths and urls of a `.m3u` file, ignoring comments and empty lines
+fn parse(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn serialize(tracks: &[String]) -> String {
+    let mut m3u = String::from("#EXTM3U\n");
+    for track in tracks {
+        m3u.push_str(track);
+        m3u.push('\n');
+    }
+
+    m3u
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SavedPlaylistInfo, SavedPlaylists};
+    use pretty_assertions::assert_eq;
+
+    fn tracks(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn should_create_append_rename_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let playlists = SavedPlaylists::with_dir(dir.path().to_path_buf()).unwrap();
+
+        playlists
+            .create(
+                "Mix",
+                &tracks(&["/music/a.mp3", "https://radio.example/stream"]),
+            )
+            .unwrap();
+        playlists
+            .append("Mix", &tracks(&["https://pod.example/ep1.mp3"]))
+            .unwrap();
+        assert_eq!(
+            playlists.get("Mix").unwrap(),
+            tracks(&[
+                "/music/a.mp3",
+                "https://radio.example/stream",
+                "https://pod.example/ep1.mp3"
+            ])
+        );
+        assert!(playlists.create("Mix", &[]).is_err());
+
+        playlists.create("empty", &[]).unwrap();
+        playlists.rename("Mix", "Another").unwrap();
+        assert!(playlists.rename("Another", "empty").is_err());
+        assert_eq!(
+            playlists.list().unwrap(),
+            vec![
+                SavedPlaylistInfo {
+                    name: "Another".to_string(),
+                    track_count: 3
+                },
+                SavedPlaylistInfo {
+                    name: "empty".to_string(),
+                    track_count: 0
+                },
+            ]
+        );
+
+        playlists.delete("empty").unwrap();
+        assert!(playlists.get("empty").is_err());
+        assert_eq!(playlists.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn should_reject_names_outside_of_the_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let playlists = SavedPlaylists::with_dir(dir.path().to_path_buf()).unwrap();
+
+        assert!(playlists.create("", &[]).is_err());
+        assert!(playlists.create("../escape", &[]).is_err());
+        assert!(playlists.create("sub/dir", &[]).is_err());
+        assert!(playlists.create(".hidden", &[]).is_err());
+    }
+}
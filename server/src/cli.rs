@@ -114,6 +114,8 @@ pub enum Action {
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
+    /// List the available audio output devices, the configured one is marked with "*".
+    ListOutputDevices,
 }
 
 const DEFAULT_LOGFILE_FILENAME: &str = "termusic-server.log";
@@ -0,0 +1,246 @@
+//! Keep the library database in sync with the music directories
+//!
+//! Changes are picked up by watching the music directories (inotify on linux) and applied incrementally,
+//! with a periodic full scan as fallback for changes the watcher cannot see.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use notify::event::{AccessKind, AccessMode, ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use termusiclib::config::ServerOverlay;
+use termusiclib::library_db::DataBase;
+use termusicplayback::{StreamTX, UpdateEvents};
+
+/// How long to wait for more events before applying them, as copying a file for example emits many events
+const DEBOUNCE: Duration = Duration::from_secs(1);
+/// Apply pending events after at most this long, even if more keep coming in
+const MAX_BATCH_AGE: Duration = Duration::from_secs(10);
+
+/// Start the watcher thread for all music directories, does nothing if both watching and full scans are disabled
+pub fn library_watcher_thread(config: &ServerOverlay, stream_tx: StreamTX) -> Result<()> {
+    let settings = &config.settings.player;
+    // the tui scans the library on startup itself in this case
+    if !settings.library_watch.is_enabled() {
+        return Ok(());
+    }
+    let full_scan_interval = Some(settings.library_watch.full_scan_interval_secs)
+        .filter(|v| *v > 0)
+        .map(Duration::from_secs);
+
+    let mut music_dirs = settings.music_dirs.clone();
+    if let Some(ref overwrite) = config.music_dir_overwrite {
+        if !music_dirs.contains(overwrite) {
+            music_dirs.push(overwrite.clone());
+        }
+    }
+
+    let db = DataBase::new(config)?;
+    let watch = settings.library_watch.watch;
+
+    std::thread::Builder::new()
+        .name("library watcher".into())
+        .spawn(move || {
+            let mut watcher = LibraryWatcher {
+                db,
+                music_dirs,
+                stream_tx,
+            };
+            watcher.run(watch, full_scan_interval);
+        })?;
+
+    Ok(())
+}
+
+struct LibraryWatcher {
+    db: DataBase,
+    music_dirs: Vec<PathBuf>,
+    stream_tx: StreamTX,
+}
+
+impl LibraryWatcher {
+    fn run(&mut self, watch: bool, full_scan_interval: Option<Duration>) {
+        let (tx, rx) = mpsc::channel();
+        // the watcher stops once dropped, so it has to live as long as the loop
+        let _watcher = if watch {
+            self.watch(tx)
+        } else {
+            drop(tx);
+            None
+        };
+
+        // catch up with changes while the server was not running
+        self.full_scan();
+        let mut last_full_scan = Instant::now();
+
+        let mut events = Vec::new();
+        // when the first of the pending events was received
+        let mut batch_start = Instant::now();
+        loop {
+            let timeout = if events.is_empty() {
+                full_scan_interval.map_or(Duration::MAX, |v| {
+                    v.saturating_sub(last_full_scan.elapsed())
+                })
+            } else {
+                DEBOUNCE.min(MAX_BATCH_AGE.saturating_sub(batch_start.elapsed()))
+            };
+
+            match rx.recv_timeout(timeout) {
+                Ok(Ok(event)) => {
+                    if events.is_empty() {
+                        batch_start = Instant::now();
+                    }
+                    events.push(event);
+                    // a steady stream of events would otherwise never be applied
+                    if batch_start.elapsed() >= MAX_BATCH_AGE {
+                        self.apply_events(&std::mem::take(&mut events));
+                    }
+                }
+                Ok(Err(err)) => warn!("Error from library watcher: {err}"),
+                Err(RecvTimeoutError::Timeout) => {
+                    if !events.is_empty() {
+                        self.apply_events(&std::mem::take(&mut events));
+                    }
+                    if full_scan_interval.is_some_and(|v| last_full_scan.elapsed() >= v) {
+                        self.full_scan();
+                        last_full_scan = Instant::now();
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    if full_scan_interval.is_none() {
+                        break;
+                    }
+                    // only full scans are left, which are handled on timeout
+                    std::thread::sleep(
+                        full_scan_interval
+                            .unwrap_or_default()
+                            .saturating_sub(last_full_scan.elapsed()),
+                    );
+                    self.full_scan();
+                    last_full_scan = Instant::now();
+                }
+            }
+        }
+    }
+
+    /// Start watching all music directories, returns `None` if no directory could be watched
+    fn watch(&self, tx: mpsc::Sender<notify::Result<Event>>) -> Option<RecommendedWatcher> {
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(v) => v,
+            Err(err) => {
+                error!("Could not create library watcher, only using full scans: {err}");
+                return None;
+            }
+        };
+
+        let mut watching = false;
+        for dir in &self.music_dirs {
+            match watcher.watch(dir, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    info!("Watching \"{}\" for library changes", dir.display());
+                    watching = true;
+                }
+                Err(err) => error!("Could not watch \"{}\": {err}", dir.display()),
+            }
+        }
+
+        watching.then_some(watcher)
+    }
+
+    /// Synchronize all music directories with the database
+    fn full_scan(&mut self) {
+        let mut changed = 0;
+        for dir in &self.music_dirs {
+            match self.db.sync_database_blocking(dir) {
+                Ok(v) => changed += v,
+                Err(err) => error!("Error scanning \"{}\": {err}", dir.display()),
+            }
+        }
+
+        self.notify_changed(changed);
+    }
+
+    /// Apply a batch of watcher events to the database
+    fn apply_events(&mut self, events: &[Event]) {
+        // renames are reported as separate "from" and "to" events, followed by one with both paths
+        // only the combined one is used, so that play positions and history are kept
+        let renamed: Vec<&Path> = events
+            .iter()
+            .filter(|ev| {
+                matches!(
+                    ev.kind,
+                    EventKind::Modify(ModifyKind::Name(RenameMode::Both))
+                )
+            })
+            .flat_map(|ev| ev.paths.iter().map(PathBuf::as_path))
+            .collect();
+
+        let mut changed = 0;
+        for event in events {
+            let res = match event.kind {
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match &event.paths[..] {
+                    [from, to] => self.apply_rename(from, to),
+                    _ => Ok(0),
+                },
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+                    self.apply_paths(event, &renamed, DataBase::remove_path)
+                }
+                EventKind::Create(_)
+                | EventKind::Modify(_)
+                | EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
+                    self.apply_paths(event, &renamed, DataBase::sync_path)
+                }
+                _ => Ok(0),
+            };
+
+            match res {
+                Ok(v) => changed += v,
+                Err(err) => error!("Error applying library change {event:?}: {err}"),
+            }
+        }
+
+        self.notify_changed(changed);
+    }
+
+    /// Call `apply` for all paths of `event` that are not part of a rename
+    fn apply_paths(
+        &mut self,
+        event: &Event,
+        renamed: &[&Path],
+        apply: fn(&mut DataBase, &Path) -> rusqlite::Result<usize>,
+    ) -> rusqlite::Result<usize> {
+        let mut changed = 0;
+        for path in event
+            .paths
+            .iter()
+            .filter(|v| !renamed.contains(&v.as_path()))
+        {
+            changed += apply(&mut self.db, path)?;
+        }
+
+        Ok(changed)
+    }
+
+    fn apply_rename(&mut self, from: &Path, to: &Path) -> rusqlite::Result<usize> {
+        let changed = self.db.rename_path(from, to)?;
+        // the new name may not be a supported file anymore, or it is now one
+        if to.is_file() && !termusiclib::utils::filetype_supported(&to.to_string_lossy()) {
+            return Ok(changed + self.db.remove_path(to)?);
+        }
+
+        Ok(changed + self.db.sync_path(to)?)
+    }
+
+    /// Tell all clients that the library changed, if anything changed
+    fn notify_changed(&self, changed: usize) {
+        if changed == 0 {
+            return;
+        }
+
+        info!("Library changed, {changed} tracks added, updated or removed");
+        // sending only fails if there are no receivers, in which case there is nobody to notify
+        let _ = self.stream_tx.send(UpdateEvents::LibraryChanged);
+    }
+}
@@ -1,16 +1,32 @@
 use anyhow::Result;
+use futures::Stream;
 use parking_lot::Mutex;
+use std::pin::Pin;
 use std::sync::Arc;
+use termusiclib::config::v2::server::EqualizerBand;
+use termusiclib::library_db::{DataBase, SearchQuery};
 use termusicplayback::player::music_player_server::MusicPlayer;
 use termusicplayback::player::{
-    CycleLoopReply, CycleLoopRequest, EmptyReply, GetProgressRequest, GetProgressResponse,
-    PlaySelectedRequest, PlayerTime, ReloadConfigRequest, ReloadPlaylistRequest,
-    SeekBackwardRequest, SeekForwardRequest, SkipNextRequest, SkipNextResponse,
-    SkipPreviousRequest, SpeedDownRequest, SpeedReply, SpeedUpRequest, ToggleGaplessReply,
-    ToggleGaplessRequest, TogglePauseRequest, TogglePauseResponse, VolumeDownRequest, VolumeReply,
-    VolumeUpRequest,
+    ChapterNextRequest, ChapterPreviousRequest, CycleLoopReply, CycleLoopRequest, EmptyReply,
+    Equalizer, GetEqualizerRequest, GetOutputDevicesRequest, GetPlaylistRequest,
+    GetProgressRequest, GetProgressResponse, GetSavedPlaylistTracksRequest,
+    GetSavedPlaylistsRequest, OutputDevices, PlayIndexRequest, PlaySelectedRequest, PlayerTime,
+    PlaylistAddTracksRequest, PlaylistAddUpNextRequest, PlaylistClearRequest,
+    PlaylistMoveTrackRequest, PlaylistPlayNextRequest, PlaylistRedoRequest,
+    PlaylistRemoveTrackRequest, PlaylistShuffleRequest, PlaylistSwapTracksRequest, PlaylistTracks,
+    PlaylistUndoRequest, ReloadConfigRequest, ReloadPlaylistRequest, SavedPlaylistAppendRequest,
+    SavedPlaylistCreateRequest, SavedPlaylistDeleteRequest, SavedPlaylistLoadRequest,
+    SavedPlaylistRenameRequest, SavedPlaylistsInfo, SearchLibraryRequest, SearchLibraryResponse,
+    SeekBackwardRequest, SeekForwardRequest, SeekToRequest, SetEqualizerRequest,
+    SetOutputDeviceRequest, SetSpeedRequest, SetVolumeRequest, SkipNextRequest, SkipNextResponse,
+    SkipPreviousRequest, SpeedDownRequest, SpeedReply, SpeedUpRequest, StreamUpdates,
+    SubscribeServerUpdatesRequest, ToggleGaplessReply, ToggleGaplessRequest, TogglePauseRequest,
+    TogglePauseResponse, VolumeDownRequest, VolumeReply, VolumeUpRequest,
 };
-use termusicplayback::{PlayerCmd, PlayerCmdSender};
+use termusicplayback::{
+    PlayerCmd, PlayerCmdSender, Playlist, SavedPlaylists, StreamTX, UpdateEvents,
+};
+use tokio::sync::broadcast::error::RecvError;
 use tonic::{Request, Response, Status};
 
 use crate::PlayerStats;
@@ -18,16 +34,31 @@ use crate::PlayerStats;
 #[derive(Debug)]
 pub struct MusicPlayerService {
     cmd_tx: PlayerCmdSender,
+    stream_tx: StreamTX,
     pub(crate) player_stats: Arc<Mutex<PlayerStats>>,
+    /// Separate connection to the library database, only used for read requests
+    db: Mutex<DataBase>,
+    saved_playlists: SavedPlaylists,
 }
 
+/// Maximum number of results for a library search if the request does not set a limit
+const DEFAULT_SEARCH_LIMIT: u32 = 100;
+
 impl MusicPlayerService {
-    pub fn new(cmd_tx: PlayerCmdSender) -> Self {
+    pub fn new(
+        cmd_tx: PlayerCmdSender,
+        stream_tx: StreamTX,
+        db: DataBase,
+        saved_playlists: SavedPlaylists,
+    ) -> Self {
         let player_stats = Arc::new(Mutex::new(PlayerStats::new()));
 
         Self {
             cmd_tx,
+            stream_tx,
             player_stats,
+            db: Mutex::new(db),
+            saved_playlists,
         }
     }
 }
@@ -38,10 +69,45 @@ impl MusicPlayerService {
             error!("error {cmd:?}: {e}");
         }
     }
+
+    /// Convert a index from a request and check that it is within the current playlist
+    fn checked_playlist_index(&self, index: u64) -> Result<usize, Status> {
+        let len = self.player_stats.lock().playlist.len();
+        match usize::try_from(index) {
+            Ok(index) if index < len => Ok(index),
+            _ => Err(Status::invalid_argument(format!(
+                "Index {index} is out of bounds for a playlist with {len} tracks"
+            ))),
+        }
+    }
+
+    /// Get the tracks to save for a request, either the given ones or the current playlist
+    fn saved_playlist_tracks(&self, from_queue: bool, tracks: Vec<String>) -> Vec<String> {
+        if from_queue {
+            self.player_stats
+                .lock()
+                .playlist
+                .iter()
+                .map(|track| track.file.clone())
+                .collect()
+        } else {
+            tracks
+        }
+    }
+
+    /// Notify all clients about a modification of the saved playlists
+    fn saved_playlists_changed(&self) {
+        // sending only fails if there are no receivers, in which case there is nobody to notify
+        let _ = self.stream_tx.send(UpdateEvents::SavedPlaylistsChanged);
+    }
 }
 
+type StreamUpdatesResult = Result<StreamUpdates, Status>;
+
 #[tonic::async_trait]
 impl MusicPlayer for MusicPlayerService {
+    type SubscribeServerUpdatesStream = Pin<Box<dyn Stream<Item = StreamUpdatesResult> + Send>>;
+
     async fn cycle_loop(
         &self,
         _request: Request<CycleLoopRequest>,
@@ -220,4 +286,415 @@ impl MusicPlayer for MusicPlayerService {
 
         Ok(Response::new(reply))
     }
+
+    async fn subscribe_server_updates(
+        &self,
+        _request: Request<SubscribeServerUpdatesRequest>,
+    ) -> Result<Response<Self::SubscribeServerUpdatesStream>, Status> {
+        let rx = self.stream_tx.subscribe();
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            let ev = match rx.recv().await {
+                Ok(ev) => ev,
+                // let the client know that it should re-sync its state
+                Err(RecvError::Lagged(amount)) => UpdateEvents::MissedEvents { amount },
+                Err(RecvError::Closed) => return None,
+            };
+
+            Some((Ok(ev.into()), rx))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn play_index(
+        &self,
+        request: Request<PlayIndexRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let index = self.checked_playlist_index(request.into_inner().index)?;
+        self.command(&PlayerCmd::PlayIndex(index));
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn get_playlist(
+        &self,
+        _request: Request<GetPlaylistRequest>,
+    ) -> Result<Response<PlaylistTracks>, Status> {
+        let r = self.player_stats.lock();
+        let reply = r.as_playlist_tracks();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn playlist_add_tracks(
+        &self,
+        request: Request<PlaylistAddTracksRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        self.command(&PlayerCmd::PlaylistAddTracks {
+            // anything that does not fit will be appended anyway
+            at_index: usize::try_from(request.at_index).unwrap_or(usize::MAX),
+            tracks: request.tracks,
+        });
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_play_next(
+        &self,
+        request: Request<PlaylistPlayNextRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::PlaylistPlayNext(request.into_inner().tracks));
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_add_up_next(
+        &self,
+        request: Request<PlaylistAddUpNextRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::PlaylistAddUpNext(request.into_inner().tracks));
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_remove_track(
+        &self,
+        request: Request<PlaylistRemoveTrackRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let index = self.checked_playlist_index(request.into_inner().index)?;
+        self.command(&PlayerCmd::PlaylistRemoveTrack(index));
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_swap_tracks(
+        &self,
+        request: Request<PlaylistSwapTracksRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        let index_a = self.checked_playlist_index(request.index_a)?;
+        let index_b = self.checked_playlist_index(request.index_b)?;
+        self.command(&PlayerCmd::PlaylistSwapTracks(index_a, index_b));
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_move_track(
+        &self,
+        request: Request<PlaylistMoveTrackRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        let from = self.checked_playlist_index(request.from)?;
+        let to = self.checked_playlist_index(request.to)?;
+        self.command(&PlayerCmd::PlaylistMoveTrack { from, to });
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_clear(
+        &self,
+        _request: Request<PlaylistClearRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::PlaylistClear);
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_shuffle(
+        &self,
+        _request: Request<PlaylistShuffleRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::PlaylistShuffle);
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_undo(
+        &self,
+        _request: Request<PlaylistUndoRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::PlaylistUndo);
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn playlist_redo(
+        &self,
+        _request: Request<PlaylistRedoRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::PlaylistRedo);
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn chapter_next(
+        &self,
+        _request: Request<ChapterNextRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::ChapterNext);
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn chapter_previous(
+        &self,
+        _request: Request<ChapterPreviousRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.command(&PlayerCmd::ChapterPrevious);
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn seek_to(
+        &self,
+        request: Request<SeekToRequest>,
+    ) -> Result<Response<PlayerTime>, Status> {
+        let Some(position) = request.into_inner().position else {
+            return Err(Status::invalid_argument("Missing position to seek to"));
+        };
+        self.command(&PlayerCmd::SeekTo(position.into()));
+        // This is to let the player update the progress within loop
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let s = self.player_stats.lock();
+
+        let reply = s.as_playertime();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_volume(
+        &self,
+        request: Request<SetVolumeRequest>,
+    ) -> Result<Response<VolumeReply>, Status> {
+        // the players clamp the volume themself, so anything above u16 can just be clamped
+        let volume = u16::try_from(request.into_inner().volume).unwrap_or(u16::MAX);
+        self.command(&PlayerCmd::SetVolume(volume));
+        // This is to let the player update volume within loop
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let r = self.player_stats.lock();
+        let reply = VolumeReply {
+            volume: u32::from(r.volume),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_speed(
+        &self,
+        request: Request<SetSpeedRequest>,
+    ) -> Result<Response<SpeedReply>, Status> {
+        self.command(&PlayerCmd::SetSpeed(request.into_inner().speed));
+        // This is to let the player update speed within loop
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let s = self.player_stats.lock();
+        let reply = SpeedReply { speed: s.speed };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_output_devices(
+        &self,
+        _request: Request<GetOutputDevicesRequest>,
+    ) -> Result<Response<OutputDevices>, Status> {
+        let devices = termusicplayback::list_output_devices();
+        let current = self
+            .player_stats
+            .lock()
+            .output_device
+            .clone()
+            .unwrap_or_default();
+
+        Ok(Response::new(OutputDevices { devices, current }))
+    }
+
+    async fn set_output_device(
+        &self,
+        request: Request<SetOutputDeviceRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let device = request.into_inner().device;
+        let device = if device.is_empty() {
+            None
+        } else {
+            if !termusicplayback::list_output_devices().contains(&device) {
+                return Err(Status::not_found(format!(
+                    "No output device named \"{device}\""
+                )));
+            }
+            Some(device)
+        };
+        self.command(&PlayerCmd::SetOutputDevice(device));
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn get_equalizer(
+        &self,
+        _request: Request<GetEqualizerRequest>,
+    ) -> Result<Response<Equalizer>, Status> {
+        let reply = (&self.player_stats.lock().equalizer).into();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_equalizer(
+        &self,
+        request: Request<SetEqualizerRequest>,
+    ) -> Result<Response<Equalizer>, Status> {
+        let request = request.into_inner();
+        if request.preset.is_empty() {
+            return Err(Status::invalid_argument("Preset name must not be empty"));
+        }
+
+        let mut settings = self.player_stats.lock().equalizer.clone();
+        if request.bands.is_empty() {
+            if !settings.presets.contains_key(&request.preset) {
+                return Err(Status::not_found(format!(
+                    "No equalizer preset named \"{}\"",
+                    request.preset
+                )));
+            }
+        } else {
+            let bands: Vec<EqualizerBand> = request.bands.into_iter().map(Into::into).collect();
+            for band in &bands {
+                band.validate()
+                    .map_err(|err| Status::invalid_argument(err.to_string()))?;
+            }
+            settings.presets.insert(request.preset.clone(), bands);
+        }
+        settings.enabled = request.enabled;
+        settings.preset = request.preset;
+
+        let reply = (&settings).into();
+        self.command(&PlayerCmd::SetEqualizer(settings));
+
+        Ok(Response::new(reply))
+    }
+
+    async fn search_library(
+        &self,
+        request: Request<SearchLibraryRequest>,
+    ) -> Result<Response<SearchLibraryResponse>, Status> {
+        let request = request.into_inner();
+        let query = SearchQuery::parse(&request.query)
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        let limit = if request.limit == 0 {
+            DEFAULT_SEARCH_LIMIT
+        } else {
+            request.limit
+        };
+
+        let tracks = self
+            .db
+            .lock()
+            .search_tracks(&query, limit)
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(SearchLibraryResponse {
+            tracks: tracks.iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_saved_playlists(
+        &self,
+        _request: Request<GetSavedPlaylistsRequest>,
+    ) -> Result<Response<SavedPlaylistsInfo>, Status> {
+        let playlists = self
+            .saved_playlists
+            .list()
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(SavedPlaylistsInfo {
+            playlists: playlists.iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_saved_playlist_tracks(
+        &self,
+        request: Request<GetSavedPlaylistTracksRequest>,
+    ) -> Result<Response<PlaylistTracks>, Status> {
+        let items = self
+            .saved_playlists
+            .get(&request.into_inner().name)
+            .map_err(|err| Status::not_found(format!("{err:#}")))?;
+        let tracks =
+            Playlist::tracks_from_saved(items).map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(PlaylistTracks {
+            current_track_index: 0,
+            tracks: tracks.iter().map(Into::into).collect(),
+            up_next: 0,
+        }))
+    }
+
+    async fn saved_playlist_create(
+        &self,
+        request: Request<SavedPlaylistCreateRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        let tracks = self.saved_playlist_tracks(request.from_queue, request.tracks);
+        self.saved_playlists
+            .create(&request.name, &tracks)
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        self.saved_playlists_changed();
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn saved_playlist_rename(
+        &self,
+        request: Request<SavedPlaylistRenameRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        self.saved_playlists
+            .rename(&request.from, &request.to)
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        self.saved_playlists_changed();
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn saved_playlist_delete(
+        &self,
+        request: Request<SavedPlaylistDeleteRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        self.saved_playlists
+            .delete(&request.into_inner().name)
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        self.saved_playlists_changed();
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn saved_playlist_append(
+        &self,
+        request: Request<SavedPlaylistAppendRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        let tracks = self.saved_playlist_tracks(request.from_queue, request.tracks);
+        self.saved_playlists
+            .append(&request.name, &tracks)
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        self.saved_playlists_changed();
+
+        Ok(Response::new(EmptyReply {}))
+    }
+
+    async fn saved_playlist_load(
+        &self,
+        request: Request<SavedPlaylistLoadRequest>,
+    ) -> Result<Response<EmptyReply>, Status> {
+        let request = request.into_inner();
+        let tracks = self
+            .saved_playlists
+            .get(&request.name)
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        self.command(&PlayerCmd::PlaylistLoad {
+            tracks,
+            replace: request.replace,
+        });
+
+        Ok(Response::new(EmptyReply {}))
+    }
 }
@@ -0,0 +1,289 @@
+//! Refresh podcast feeds, download new episodes and remove old downloads in the background
+//!
+//! What is done for which feed is decided by its [`PodcastPolicy`](termusiclib::podcast::PodcastPolicy) stored in the podcast database,
+//! which is re-read every check so that changes apply without restarting the server.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use termusiclib::config::ServerOverlay;
+use termusiclib::podcast::db::{Database, PodcastDBId};
+use termusiclib::podcast::{
+    check_feed, download_list, podcast_dir_name, transcript, DownloadCancel, EpData, Podcast,
+    PodcastFeed,
+};
+use termusiclib::taskpool::TaskPool;
+use termusiclib::types::{Msg, PCMsg};
+use termusiclib::utils::{create_podcast_dir, get_app_config_path};
+use termusicplayback::{StreamTX, UpdateEvents};
+use tokio::runtime::Handle;
+
+/// How often to check whether any feed needs to be refreshed or has downloads to remove
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start the podcast updater thread
+pub fn podcast_updater_thread(config: &ServerOverlay, stream_tx: StreamTX) -> Result<()> {
+    let db_path = get_app_config_path().context("getting app-config-path")?;
+    let db = Database::new(&db_path)?;
+    let config = config.clone();
+    // the feed refreshes and downloads are run as tokio tasks
+    let tokio_handle = Handle::current();
+
+    std::thread::Builder::new()
+        .name("podcast updater".into())
+        .spawn(move || {
+            let _guard = tokio_handle.enter();
+            let (tx, rx) = mpsc::channel();
+            let taskpool = TaskPool::new(usize::from(
+                config.settings.podcast.concurrent_downloads_max.get(),
+            ));
+            let mut updater = PodcastUpdater {
+                db,
+                config,
+                stream_tx,
+                taskpool,
+                tx,
+                refreshing: HashSet::new(),
+                downloading: HashSet::new(),
+                failed: HashMap::new(),
+                failed_refreshes: HashMap::new(),
+            };
+            updater.run(&rx);
+        })?;
+
+    Ok(())
+}
+
+struct PodcastUpdater {
+    db: Database,
+    config: ServerOverlay,
+    stream_tx: StreamTX,
+    taskpool: TaskPool,
+    /// Sender for the results of the feed refreshes and downloads
+    tx: Sender<Msg>,
+    /// Urls of the feeds currently being refreshed
+    refreshing: HashSet<String>,
+    /// Urls of the episodes currently being downloaded
+    downloading: HashSet<String>,
+    /// Urls of the episodes whose download failed, with their podcast id
+    ///
+    /// They are not tried again until their feed is refreshed.
+    failed: HashMap<String, PodcastDBId>,
+    /// Urls of the feeds whose last refresh failed, with when it was tried
+    ///
+    /// A failed refresh counts as a check, so that a broken feed is only tried again after its refresh interval.
+    failed_refreshes: HashMap<String, Instant>,
+}
+
+impl PodcastUpdater {
+    fn run(&mut self, rx: &Receiver<Msg>) {
+        // catch up with everything that was due while the server was not running
+        self.check();
+        let mut last_check = Instant::now();
+
+        loop {
+            match rx.recv_timeout(CHECK_INTERVAL.saturating_sub(last_check.elapsed())) {
+                Ok(Msg::Podcast(msg)) => self.handle_msg(msg),
+                Ok(_) => (),
+                Err(RecvTimeoutError::Timeout) => {
+                    self.check();
+                    last_check = Instant::now();
+                }
+                // cannot happen as "self.tx" is kept
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    /// Refresh all feeds that are due and apply the policy of all others
+    fn check(&mut self) {
+        let podcasts = match self.db.get_podcasts() {
+            Ok(v) => v,
+            Err(err) => {
+                error!("Could not get podcasts for updating: {err:#}");
+                return;
+            }
+        };
+
+        let mut changed = false;
+        for podcast in podcasts.iter().filter(|v| v.policy.is_enabled()) {
+            if podcast.policy.is_refresh_due(podcast.last_checked)
+                && !self.is_refresh_failed_recently(podcast)
+            {
+                // the policy is applied once the feed is refreshed
+                self.refresh(podcast);
+            } else {
+                changed |= self.apply_policy(podcast);
+            }
+        }
+
+        if changed {
+            let _ = self.stream_tx.send(UpdateEvents::PodcastsChanged);
+        }
+    }
+
+    /// Get whether the last refresh of the feed failed less than its refresh interval ago
+    fn is_refresh_failed_recently(&self, podcast: &Podcast) -> bool {
+        let (Some(failed_at), Some(interval)) = (
+            self.failed_refreshes.get(&podcast.url),
+            podcast.policy.refresh_interval,
+        ) else {
+            return false;
+        };
+
+        failed_at.elapsed() < interval
+    }
+
+    fn refresh(&mut self, podcast: &Podcast) {
+        if !self.refreshing.insert(podcast.url.clone()) {
+            return;
+        }
+
+        info!("Refreshing podcast \"{}\"", podcast.title);
+        check_feed(
+            PodcastFeed::new(Some(podcast.id), &podcast.url, Some(podcast.title.clone())),
+            usize::from(self.config.settings.podcast.max_download_retries),
+            &self.taskpool,
+            self.tx.clone(),
+        );
+    }
+
+    fn handle_msg(&mut self, msg: PCMsg) {
+        match msg {
+            PCMsg::SyncData((id, pod)) => {
+                self.refreshing.remove(&pod.url);
+                self.failed_refreshes.remove(&pod.url);
+                self.failed.retain(|_, pod_id| *pod_id != id);
+                if let Err(err) = self.db.update_podcast(id, &pod) {
+                    error!("Could not update podcast \"{}\": {err:#}", pod.title);
+                    return;
+                }
+
+                match self
+                    .db
+                    .get_podcasts()
+                    .map(|v| v.into_iter().find(|v| v.id == id))
+                {
+                    Ok(Some(podcast)) => {
+                        self.apply_policy(&podcast);
+                    }
+                    Ok(None) => (),
+                    Err(err) => error!("Could not get podcasts for updating: {err:#}"),
+                }
+                let _ = self.stream_tx.send(UpdateEvents::PodcastsChanged);
+            }
+            PCMsg::Error(url, _) => {
+                warn!("Refreshing podcast \"{url}\" failed");
+                self.refreshing.remove(&url);
+                self.failed_refreshes.insert(url, Instant::now());
+            }
+            PCMsg::DLComplete(ep_data) => {
+                self.downloading.remove(&ep_data.url);
+                let Some(path) = ep_data.file_path else {
+                    return;
+                };
+                if let Err(err) = self.db.insert_file(ep_data.id, &path) {
+                    error!(
+                        "Could not add episode file to database \"{}\": {err:#}",
+                        path.display()
+                    );
+                    return;
+                }
+                info!("Downloaded episode \"{}\"", ep_data.title);
+                let _ = self.stream_tx.send(UpdateEvents::PodcastsChanged);
+            }
+            // tried again on the next check, once the other download is done the file exists
+            PCMsg::DLAlreadyRunning(ep_data) => {
+                info!("Episode \"{}\" is already being downloaded", ep_data.title);
+                self.downloading.remove(&ep_data.url);
+            }
+            PCMsg::DLResponseError(ep_data)
+            | PCMsg::DLFileCreateError(ep_data)
+            | PCMsg::DLFileWriteError(ep_data)
+            | PCMsg::DLCancelled(ep_data) => {
+                warn!("Downloading episode \"{}\" failed", ep_data.title);
+                self.downloading.remove(&ep_data.url);
+                self.failed.insert(ep_data.url, ep_data.pod_id);
+            }
+            _ => (),
+        }
+    }
+
+    /// Start the downloads and remove the files the policy asks for, returns whether any file was removed
+    fn apply_policy(&mut self, podcast: &Podcast) -> bool {
+        let policy = &podcast.policy;
+
+        let to_download: Vec<EpData> = policy
+            .episodes_to_download(&podcast.episodes)
+            .into_iter()
+            .filter(|ep| !self.downloading.contains(&ep.url) && !self.failed.contains_key(&ep.url))
+            .map(|ep| EpData {
+                id: ep.id,
+                pod_id: ep.pod_id,
+                title: ep.title.clone(),
+                url: ep.url.clone(),
+                pubdate: ep.pubdate,
+                file_path: None,
+            })
+            .collect();
+        if !to_download.is_empty() {
+            self.download(podcast, to_download);
+        }
+
+        let mut removed = false;
+        for ep in policy.episodes_to_remove(&podcast.episodes) {
+            let Some(ref path) = ep.path else {
+                continue;
+            };
+            transcript::remove_cached(path);
+            if let Err(err) = std::fs::remove_file(path) {
+                // still remove it from the database if it does not exist anymore
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    error!(
+                        "Could not remove episode file \"{}\": {err}",
+                        path.display()
+                    );
+                    continue;
+                }
+            }
+            if let Err(err) = self.db.remove_file(ep.id) {
+                error!("Could not remove episode file from database: {err:#}");
+                continue;
+            }
+            info!("Removed episode download \"{}\"", ep.title);
+            removed = true;
+        }
+
+        removed
+    }
+
+    fn download(&mut self, podcast: &Podcast, episodes: Vec<EpData>) {
+        let path: PathBuf = match create_podcast_dir(&self.config, podcast_dir_name(&podcast.title))
+        {
+            Ok(v) => v,
+            Err(err) => {
+                error!("Could not create podcast directory: {err:#}");
+                return;
+            }
+        };
+
+        let episodes = episodes
+            .into_iter()
+            .inspect(|ep| {
+                info!("Downloading episode \"{}\"", ep.title);
+                self.downloading.insert(ep.url.clone());
+            })
+            .map(|ep| (ep, DownloadCancel::default()))
+            .collect();
+        download_list(
+            episodes,
+            &path,
+            usize::from(self.config.settings.podcast.max_download_retries),
+            &self.taskpool,
+            &self.tx,
+        );
+    }
+}
@@ -1,6 +1,8 @@
 mod cli;
+mod library_watcher;
 mod logger;
 mod music_player_service;
+mod podcast_updater;
 
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -9,23 +11,28 @@ use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
+use library_watcher::library_watcher_thread;
 use music_player_service::MusicPlayerService;
 use parking_lot::Mutex;
+use podcast_updater::podcast_updater_thread;
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
-use termusiclib::config::v2::server::ScanDepth;
+use termusiclib::config::v2::server::{ComProtocol, EqualizerSettings, ScanDepth};
 use termusiclib::config::ServerOverlay;
+use termusiclib::library_db::DataBase;
 use termusiclib::track::MediaType;
 use termusiclib::{podcast, utils};
 use termusicplayback::player::music_player_server::MusicPlayerServer;
-use termusicplayback::player::{GetProgressResponse, PlayerTime};
+use termusicplayback::player::{GetProgressResponse, PlayerTime, PlaylistTrack, PlaylistTracks};
 use termusicplayback::{
-    Backend, BackendSelect, GeneralPlayer, PlayerCmd, PlayerCmdReciever, PlayerCmdSender,
-    PlayerProgress, PlayerTrait, SpeedSigned, Status, VolumeSigned,
+    auth, Backend, BackendSelect, GeneralPlayer, PlayerCmd, PlayerCmdReciever, PlayerCmdSender,
+    PlayerProgress, PlayerTrait, Playlist, SavedPlaylists, SpeedSigned, Status, StreamTX,
+    UpdateEvents, VolumeSigned, MAX_SPEED, MIN_SPEED,
 };
 use tokio::runtime::Handle;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 use tonic::transport::server::TcpIncoming;
 use tonic::transport::Server;
+use tonic::Request;
 
 #[macro_use]
 extern crate log;
@@ -33,6 +40,8 @@ extern crate log;
 pub const MAX_DEPTH: usize = 4;
 pub const VOLUME_STEP: VolumeSigned = 5;
 pub const SPEED_STEP: SpeedSigned = 1;
+/// How many events a subscribed client may lag behind before it starts missing events
+pub const STREAM_UPDATES_CAPACITY: usize = 64;
 
 /// Stats for the music player responses
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +54,15 @@ struct PlayerStats {
     pub gapless: bool,
     pub current_track_updated: bool,
     pub radio_title: String,
+    /// Title of the chapter currently playing, empty if there is none
+    pub chapter_title: String,
+    /// Snapshot of the tracks in the playlist, updated on every playlist change
+    pub playlist: Vec<PlaylistTrack>,
+    /// Amount of "up next" tracks in the playlist snapshot
+    pub up_next: usize,
+    /// The selected output device, `None` for the default device
+    pub output_device: Option<String>,
+    pub equalizer: EqualizerSettings,
 }
 
 impl PlayerStats {
@@ -61,6 +79,11 @@ impl PlayerStats {
             gapless: true,
             current_track_updated: false,
             radio_title: String::new(),
+            chapter_title: String::new(),
+            playlist: Vec::new(),
+            up_next: 0,
+            output_device: None,
+            equalizer: EqualizerSettings::default(),
         }
     }
 
@@ -74,12 +97,27 @@ impl PlayerStats {
             gapless: self.gapless,
             current_track_updated: self.current_track_updated,
             radio_title: self.radio_title.clone(),
+            chapter_title: self.chapter_title.clone(),
         }
     }
 
     pub fn as_playertime(&self) -> PlayerTime {
         self.progress.into()
     }
+
+    pub fn as_playlist_tracks(&self) -> PlaylistTracks {
+        PlaylistTracks {
+            current_track_index: u64::from(self.current_track_index),
+            tracks: self.playlist.clone(),
+            up_next: self.up_next as u64,
+        }
+    }
+
+    /// Update the playlist snapshot from the given [`Playlist`]
+    pub fn set_playlist(&mut self, playlist: &Playlist) {
+        self.playlist = playlist.tracks().iter().map(Into::into).collect();
+        self.up_next = playlist.up_next_len();
+    }
 }
 
 fn main() -> Result<()> {
@@ -104,8 +142,13 @@ async fn actual_main() -> Result<()> {
 
     info!("Server starting...");
     let (cmd_tx, cmd_rx) = tokio::sync::mpsc::unbounded_channel();
+    // the initial receiver is not needed, each subscribed client gets its own
+    let (stream_tx, _) = broadcast::channel(STREAM_UPDATES_CAPACITY);
 
-    let music_player_service: MusicPlayerService = MusicPlayerService::new(cmd_tx.clone());
+    let db = DataBase::new(&config)?;
+    let saved_playlists = SavedPlaylists::new()?;
+    let music_player_service: MusicPlayerService =
+        MusicPlayerService::new(cmd_tx.clone(), stream_tx.clone(), db, saved_playlists);
     let playerstats = music_player_service.player_stats.clone();
 
     let cmd_tx_ctrlc = cmd_tx.clone();
@@ -118,16 +161,55 @@ async fn actual_main() -> Result<()> {
     })
     .expect("Error setting Ctrl-C handler");
 
-    let addr = std::net::SocketAddr::from(config.settings.com);
+    let com = config.settings.com.clone();
+    let token = com.token.clone();
+    let service =
+        MusicPlayerServer::with_interceptor(music_player_service, move |request: Request<()>| {
+            match token {
+                Some(ref token) => auth::check_token(token, &request).map(|()| request),
+                None => Ok(request),
+            }
+        });
+    let router = Server::builder().add_service(service);
+    // the socket file to clean-up on exit, if any
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut socket_path: Option<PathBuf> = None;
+
+    match com.protocol {
+        ComProtocol::Tcp => {
+            let addr = std::net::SocketAddr::from(&com);
+            if com.token.is_none() && !addr.ip().is_loopback() {
+                warn!("Listening on non-loopback address {addr} without a token, anyone who can reach it can control the player!");
+            }
+
+            // workaround to print address once sever "actually" is started and address is known
+            // see https://github.com/hyperium/tonic/issues/351
+            let tcp_listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("Error binding address: {}", addr))?;
+            info!("Server listening on {}", tcp_listener.local_addr().unwrap());
+            let tcp_stream = TcpIncoming::from_listener(tcp_listener, true, None)
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+            tokio::spawn(router.serve_with_incoming(tcp_stream));
+        }
+        #[cfg(unix)]
+        ComProtocol::Uds => {
+            let path = com.socket_path()?;
+            let uds_stream = bind_unix_socket(&path)?;
+            info!("Server listening on {}", path.display());
+
+            tokio::spawn(router.serve_with_incoming(uds_stream));
+            socket_path = Some(path);
+        }
+        #[cfg(not(unix))]
+        ComProtocol::Uds => {
+            bail!("Unix domain sockets are not supported on this platform, use protocol \"tcp\"");
+        }
+    }
 
-    // workaround to print address once sever "actually" is started and address is known
-    // see https://github.com/hyperium/tonic/issues/351
-    let tcp_listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .with_context(|| format!("Error binding address: {}", addr))?;
-    info!("Server listening on {}", tcp_listener.local_addr().unwrap());
-    let tcp_stream =
-        TcpIncoming::from_listener(tcp_listener, true, None).map_err(|e| anyhow::anyhow!(e))?;
+    library_watcher_thread(&config, stream_tx.clone())?;
+    podcast_updater_thread(&config, stream_tx.clone())?;
 
     let tokio_handle = Handle::current();
     let (player_handle_os_tx, player_handle_os_rx) = oneshot::channel();
@@ -135,19 +217,20 @@ async fn actual_main() -> Result<()> {
         .name("main player loop".into())
         .spawn(move || {
             let _guard = tokio_handle.enter();
-            let res = player_loop(args.backend.into(), cmd_tx, cmd_rx, config, playerstats);
+            let res = player_loop(
+                args.backend.into(),
+                cmd_tx,
+                cmd_rx,
+                stream_tx,
+                config,
+                playerstats,
+            );
             let _ = player_handle_os_tx.send(res);
         })?;
 
     ticker_thread(cmd_tx_ticker)?;
 
-    tokio::spawn(
-        Server::builder()
-            .add_service(MusicPlayerServer::new(music_player_service))
-            .serve_with_incoming(tcp_stream),
-    );
-
-    info!("Server started and listening on {}", addr);
+    info!("Server started");
 
     // await the oneshot completing in a async fashion
     player_handle_os_rx.await??;
@@ -155,26 +238,104 @@ async fn actual_main() -> Result<()> {
     // and by doing this after the oneshot we can be sure the thread is actually exited, or exiting
     let _ = player_handle.join();
 
+    if let Some(path) = socket_path {
+        let _ = std::fs::remove_file(path);
+    }
+
     Ok(())
 }
 
+/// The name of the socket while it is bound in the private directory, see [`bind_unix_socket`]
+#[cfg(unix)]
+const SOCKET_BIND_NAME: &str = "server.sock";
+
+/// Bind a unix socket at `path` that is only accessible by the current user
+///
+/// A stale socket from a previous server is removed, but this will error if another server is still listening on it.
+#[cfg(unix)]
+fn bind_unix_socket(
+    path: &Path,
+) -> Result<impl futures::Stream<Item = std::io::Result<tokio::net::UnixStream>>> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    if let Some(parent) = path.parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)
+            .with_context(|| format!("creating socket directory {}", parent.display()))?;
+    }
+
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            bail!(
+                "Another server is already listening on socket {}",
+                path.display()
+            );
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("removing stale socket {}", path.display()))?;
+    }
+
+    // bind in a private directory first and only move the socket into place once its permissions are set,
+    // otherwise other users could connect in between binding and "set_permissions" if the parent is shared
+    let private_dir = path.with_extension(format!("bind-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&private_dir);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .with_context(|| format!("creating socket directory {}", private_dir.display()))?;
+    let private_path = private_dir.join(SOCKET_BIND_NAME);
+
+    let res = (|| {
+        let listener = tokio::net::UnixListener::bind(&private_path)
+            .with_context(|| format!("Error binding socket: {}", path.display()))?;
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("setting permissions on socket {}", path.display()))?;
+        std::fs::rename(&private_path, path)
+            .with_context(|| format!("moving socket to {}", path.display()))?;
+
+        Ok::<_, anyhow::Error>(listener)
+    })();
+    let _ = std::fs::remove_dir_all(&private_dir);
+    let listener = res?;
+
+    Ok(futures::stream::unfold(listener, |listener| async move {
+        let res = listener.accept().await.map(|(stream, _addr)| stream);
+        Some((res, listener))
+    }))
+}
+
 /// The main player loop where we handle all events
 fn player_loop(
     backend: BackendSelect,
     cmd_tx: PlayerCmdSender,
     mut cmd_rx: PlayerCmdReciever,
+    stream_tx: StreamTX,
     config: ServerOverlay,
     playerstats: Arc<Mutex<PlayerStats>>,
 ) -> Result<()> {
-    let mut player = GeneralPlayer::new_backend(backend, config, cmd_tx)?;
+    let mut player = GeneralPlayer::new_backend(backend, config, cmd_tx, stream_tx)?;
+    {
+        let mut p_tick = playerstats.lock();
+        p_tick.set_playlist(&player.playlist);
+        let config_read = player.config.read();
+        p_tick.output_device = config_read.settings.player.output_device.clone();
+        p_tick.equalizer = config_read.settings.player.equalizer.clone();
+    }
     while let Some(cmd) = cmd_rx.blocking_recv() {
         #[allow(unreachable_patterns)]
         match cmd {
             PlayerCmd::AboutToFinish => {
                 info!("about to finish signal received");
-                if !player.playlist.is_empty()
-                    && !player.playlist.has_next_track()
-                    && player.config.read().settings.player.gapless
+                let enqueue_next = {
+                    let config = player.config.read();
+                    // crossfading also needs the next track enqueued early
+                    config.settings.player.gapless
+                        || (config.settings.player.crossfade_ms > 0
+                            && player.backend.supports_crossfade())
+                };
+                if !player.playlist.is_empty() && !player.playlist.has_next_track() && enqueue_next
                 {
                     player.enqueue_next_from_playlist();
                 }
@@ -182,6 +343,7 @@ fn player_loop(
             PlayerCmd::Quit => {
                 info!("PlayerCmd::Quit received");
                 player.player_save_last_position();
+                player.record_play(true);
                 if let Err(e) = player.playlist.save() {
                     error!("error when saving playlist: {e}");
                 };
@@ -192,6 +354,30 @@ fn player_loop(
                 };
                 std::process::exit(0);
             }
+            PlayerCmd::ChapterNext => {
+                if !player.chapter_next() {
+                    info!("no next chapter to seek to");
+                    continue;
+                }
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress
+                }
+            }
+            PlayerCmd::ChapterPrevious => {
+                if !player.chapter_previous() {
+                    info!("no previous chapter to seek to");
+                    continue;
+                }
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress
+                }
+            }
+            PlayerCmd::ChaptersFetched { file, chapters } => {
+                info!("fetched {} chapters for \"{file}\"", chapters.len());
+                player.set_chapters(&file, chapters);
+            }
             PlayerCmd::CycleLoop => {
                 player.config.write().settings.player.loop_mode = player.playlist.cycle_loop_mode();
             }
@@ -205,32 +391,127 @@ fn player_loop(
                     "current track index: {:?}",
                     player.playlist.get_current_track_index()
                 );
+                let had_up_next = player.playlist.up_next_len() > 0;
                 player.playlist.clear_current_track();
                 player.start_play();
                 debug!(
                     "playing index is: {}",
                     player.playlist.get_current_track_index()
                 );
+                if had_up_next {
+                    // playing "up next" tracks consumes or moves them, which the clients need to know
+                    playlist_changed(&mut player, &playerstats);
+                }
             }
-            PlayerCmd::GetProgress | PlayerCmd::ProcessID => {}
+            PlayerCmd::GetProgress | PlayerCmd::ProcessID | PlayerCmd::SavedPlaylist(_) => {}
             PlayerCmd::PlaySelected => {
                 info!("play selected");
                 player.player_save_last_position();
                 player.playlist.proceed_false();
                 player.next();
             }
+            PlayerCmd::PlayIndex(index) => {
+                info!("play index {index}");
+                if index >= player.playlist.len() {
+                    error!("Index {index} is out of bounds for the playlist");
+                    continue;
+                }
+                player.player_save_last_position();
+                player.playlist.jump_to(index);
+                player.playlist.proceed_false();
+                player.next();
+            }
+            PlayerCmd::PlaylistAddTracks { at_index, tracks } => {
+                if let Err(err) = player.playlist.add_playlist_at(&tracks, at_index) {
+                    error!("Error adding tracks to the playlist: {err:#}");
+                }
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistPlayNext(tracks) => {
+                if let Err(err) = player.playlist.add_playlist_next(&tracks) {
+                    error!("Error adding tracks to play next: {err:#}");
+                }
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistAddUpNext(tracks) => {
+                if let Err(err) = player.playlist.add_playlist_up_next(&tracks) {
+                    error!("Error adding tracks to up next: {err:#}");
+                }
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistRemoveTrack(index) => {
+                let had_next_track = player.playlist.has_next_track();
+                player.playlist.remove(index);
+                // the removed track was already enqueued in the backend
+                if had_next_track && !player.playlist.has_next_track() {
+                    player.requeue_next_from_playlist();
+                }
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistSwapTracks(index_a, index_b) => {
+                player.playlist.swap(index_a, index_b);
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistMoveTrack { from, to } => {
+                player.playlist.move_track(from, to);
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistClear => {
+                player.playlist.clear();
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistLoad { tracks, replace } => {
+                match Playlist::tracks_from_saved(tracks) {
+                    Ok(tracks) => {
+                        if replace {
+                            player.playlist.replace_tracks(tracks);
+                        } else {
+                            let len = player.playlist.len();
+                            player.playlist.insert_tracks(tracks, len);
+                        }
+                        playlist_changed(&mut player, &playerstats);
+                    }
+                    Err(err) => error!("Error loading saved playlist: {err:#}"),
+                }
+            }
+            PlayerCmd::PlaylistShuffle => {
+                player.playlist.shuffle();
+                playlist_changed(&mut player, &playerstats);
+            }
+            PlayerCmd::PlaylistUndo => match player.playlist.undo() {
+                Ok(true) => playlist_changed(&mut player, &playerstats),
+                Ok(false) => info!("nothing to undo in the playlist"),
+                Err(err) => error!("Error undoing playlist change: {err:#}"),
+            },
+            PlayerCmd::PlaylistRedo => match player.playlist.redo() {
+                Ok(true) => playlist_changed(&mut player, &playerstats),
+                Ok(false) => info!("nothing to redo in the playlist"),
+                Err(err) => error!("Error redoing playlist change: {err:#}"),
+            },
             PlayerCmd::SkipPrevious => {
                 info!("skip to previous track");
                 player.player_save_last_position();
                 player.previous();
             }
             PlayerCmd::ReloadConfig => {
+                let old_device = player.config.read().settings.player.output_device.clone();
                 if let Err(err) = player.reload_config() {
                     error!("Reloading config failed, using old: {:#?}", err);
                 }
+                let new_device = player.config.read().settings.player.output_device.clone();
+                playerstats.lock().equalizer =
+                    player.config.read().settings.player.equalizer.clone();
+                if old_device != new_device {
+                    match player.set_output_device(new_device.clone()) {
+                        Ok(()) => playerstats.lock().output_device = new_device,
+                        Err(err) => error!("Failed to set output device: {err:#}"),
+                    }
+                }
             }
             PlayerCmd::ReloadPlaylist => {
                 player.playlist.reload_tracks().ok();
+                playerstats.lock().set_playlist(&player.playlist);
+                player.send_stream_ev(UpdateEvents::PlaylistChanged);
             }
             PlayerCmd::SeekBackward => {
                 player.seek_relative(false);
@@ -246,6 +527,51 @@ fn player_loop(
                     p_tick.progress = progress
                 }
             }
+            PlayerCmd::SeekTo(position) => {
+                player.seek_to(position);
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress
+                }
+            }
+            PlayerCmd::SetOutputDevice(device) => {
+                if let Err(err) = player.set_output_device(device.clone()) {
+                    error!("Failed to set output device: {err:#}");
+                    continue;
+                }
+                player.config.write().settings.player.output_device = device.clone();
+                playerstats.lock().output_device = device;
+            }
+            PlayerCmd::SetEqualizer(settings) => {
+                player.set_equalizer(settings.clone());
+                playerstats.lock().equalizer = settings;
+                // save directly, so that the changes are not lost on a config reload
+                if let Err(err) =
+                    ServerConfigVersionedDefaulted::save_config_path(&player.config.read().settings)
+                {
+                    error!("error when saving config: {err:#}");
+                }
+            }
+            PlayerCmd::SetSpeed(speed) => {
+                let new_speed = player.set_speed(speed.clamp(MIN_SPEED, MAX_SPEED));
+                info!("after set speed: {}", new_speed);
+                // a speed set for the current podcast should not change the global speed
+                if !player.is_podcast_speed_active() {
+                    player.config.write().settings.player.speed = new_speed;
+                }
+                let mut p_tick = playerstats.lock();
+                p_tick.speed = new_speed;
+                player.send_stream_ev(UpdateEvents::SpeedChanged { speed: new_speed });
+            }
+            PlayerCmd::SetVolume(volume) => {
+                let new_volume = player.set_volume(volume);
+                player.config.write().settings.player.volume = new_volume;
+                info!("after set volume: {}", new_volume);
+                let mut p_tick = playerstats.lock();
+                p_tick.volume = new_volume;
+                player.mpris_volume_update();
+                player.send_stream_ev(UpdateEvents::VolumeChanged { volume: new_volume });
+            }
             PlayerCmd::SkipNext => {
                 info!("skip to next track.");
                 player.player_save_last_position();
@@ -254,23 +580,33 @@ fn player_loop(
             PlayerCmd::SpeedDown => {
                 let new_speed = player.add_speed(-SPEED_STEP);
                 info!("after speed down: {}", new_speed);
-                player.config.write().settings.player.speed = new_speed;
+                if !player.is_podcast_speed_active() {
+                    player.config.write().settings.player.speed = new_speed;
+                }
                 let mut p_tick = playerstats.lock();
                 p_tick.speed = new_speed;
+                player.send_stream_ev(UpdateEvents::SpeedChanged { speed: new_speed });
             }
 
             PlayerCmd::SpeedUp => {
                 let new_speed = player.add_speed(SPEED_STEP);
                 info!("after speed up: {}", new_speed);
-                player.config.write().settings.player.speed = new_speed;
+                if !player.is_podcast_speed_active() {
+                    player.config.write().settings.player.speed = new_speed;
+                }
                 let mut p_tick = playerstats.lock();
                 p_tick.speed = new_speed;
+                player.send_stream_ev(UpdateEvents::SpeedChanged { speed: new_speed });
             }
             PlayerCmd::Tick => {
                 // info!("tick received");
                 player.mpris_handle_events();
                 let mut p_tick = playerstats.lock();
-                p_tick.status = player.playlist.status().as_u32();
+                let status = player.playlist.status();
+                if p_tick.status != status.as_u32() {
+                    p_tick.status = status.as_u32();
+                    player.send_stream_ev(UpdateEvents::StatusChanged { status });
+                }
                 // branch to auto-start playing if status is "stopped"(not paused) and playlist is not empty anymore
                 if player.playlist.status() == Status::Stopped {
                     if player.playlist.is_empty() {
@@ -289,6 +625,16 @@ fn player_loop(
                     p_tick.progress = progress;
                     player.mpris_update_progress(&p_tick.progress);
                 }
+                if player.is_podcast_outro_reached() {
+                    info!("skipping podcast outro");
+                    player.next();
+                }
+                // the speed also changes on track change, for podcasts with their own speed
+                let speed = player.speed();
+                if p_tick.speed != speed {
+                    p_tick.speed = speed;
+                    player.send_stream_ev(UpdateEvents::SpeedChanged { speed });
+                }
                 if player.current_track_updated {
                     p_tick.current_track_index = player.playlist.get_current_track_index() as u32;
                     p_tick.current_track_updated = player.current_track_updated;
@@ -299,7 +645,13 @@ fn player_loop(
                     #[allow(irrefutable_let_patterns)]
                     if MediaType::LiveRadio == track.media_type {
                         // TODO: consider changing "radio_title" and "media_title" to be consistent
-                        p_tick.radio_title = player.media_info().media_title.unwrap_or_default();
+                        let radio_title = player.media_info().media_title.unwrap_or_default();
+                        if p_tick.radio_title != radio_title {
+                            p_tick.radio_title.clone_from(&radio_title);
+                            player.send_stream_ev(UpdateEvents::RadioTitleChanged {
+                                title: radio_title,
+                            });
+                        }
 
                         #[cfg(feature = "rusty")]
                         if let Backend::Rusty(ref mut backend) = player.backend {
@@ -323,17 +675,30 @@ fn player_loop(
                         }
                     }
                 }
+                let chapter_title = player.current_chapter_title().unwrap_or_default();
+                if p_tick.chapter_title != chapter_title {
+                    p_tick.chapter_title.clone_from(&chapter_title);
+                    player.send_stream_ev(UpdateEvents::ChapterChanged {
+                        title: chapter_title,
+                    });
+                }
+                player.send_stream_ev(UpdateEvents::Progress(p_tick.progress));
             }
             PlayerCmd::ToggleGapless => {
                 let new_gapless = player.toggle_gapless();
                 let mut p_tick = playerstats.lock();
                 p_tick.gapless = new_gapless;
+                player.send_stream_ev(UpdateEvents::GaplessChanged {
+                    gapless: new_gapless,
+                });
             }
             PlayerCmd::TogglePause => {
                 info!("player toggled pause");
                 player.toggle_pause();
                 let mut p_tick = playerstats.lock();
-                p_tick.status = player.playlist.status().as_u32();
+                let status = player.playlist.status();
+                p_tick.status = status.as_u32();
+                player.send_stream_ev(UpdateEvents::StatusChanged { status });
             }
             PlayerCmd::VolumeDown => {
                 info!("before volumedown: {}", player.volume());
@@ -343,6 +708,7 @@ fn player_loop(
                 let mut p_tick = playerstats.lock();
                 p_tick.volume = new_volume;
                 player.mpris_volume_update();
+                player.send_stream_ev(UpdateEvents::VolumeChanged { volume: new_volume });
             }
             PlayerCmd::VolumeUp => {
                 info!("before volumeup: {}", player.volume());
@@ -352,6 +718,7 @@ fn player_loop(
                 let mut p_tick = playerstats.lock();
                 p_tick.volume = new_volume;
                 player.mpris_volume_update();
+                player.send_stream_ev(UpdateEvents::VolumeChanged { volume: new_volume });
             }
             PlayerCmd::Pause => {
                 player.pause();
@@ -365,6 +732,16 @@ fn player_loop(
     Ok(())
 }
 
+/// Save the modified playlist, update the snapshot and notify all clients
+fn playlist_changed(player: &mut GeneralPlayer, playerstats: &Mutex<PlayerStats>) {
+    // save so that clients reading the playlist file (like the TUI) get the changes
+    if let Err(err) = player.playlist.save() {
+        error!("error when saving playlist: {err:#}");
+    }
+    playerstats.lock().set_playlist(&player.playlist);
+    player.send_stream_ev(UpdateEvents::PlaylistChanged);
+}
+
 /// Spawn the thread that periodically sends [`PlayerCmd::Tick`]
 fn ticker_thread(cmd_tx: PlayerCmdSender) -> Result<()> {
     std::thread::Builder::new()
@@ -438,6 +815,17 @@ fn execute_action(action: cli::Action, config: &ServerOverlay) -> Result<()> {
                 utils::get_app_config_path().context("getting app-config-path")?;
             podcast::export_to_opml(&config_dir_path, &path).context("export opml")?;
         }
+        cli::Action::ListOutputDevices => {
+            let current = config.settings.player.output_device.as_deref();
+            for device in termusicplayback::list_output_devices() {
+                let marker = if current == Some(device.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{marker} {device}");
+            }
+        }
     };
 
     Ok(())
@@ -93,6 +93,124 @@ pub enum Action {
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
+    /// Show or change what the server does automatically for a podcast feed.
+    PodcastPolicy {
+        /// Url or title of the feed.
+        #[arg(value_name = "FEED")]
+        feed: String,
+        /// Refresh the feed every this many hours, 0 to disable.
+        #[arg(long, value_name = "HOURS")]
+        refresh_hours: Option<u32>,
+        /// Keep the newest this many unplayed episodes downloaded, 0 to disable.
+        #[arg(long, value_name = "AMOUNT")]
+        auto_download: Option<u32>,
+        /// Delete downloads of played episodes this many days after they were downloaded, 0 to disable.
+        #[arg(long, value_name = "DAYS")]
+        keep_days: Option<u32>,
+        /// Keep at most this many downloads, removing played and then the oldest episodes first, 0 to disable.
+        #[arg(long, value_name = "AMOUNT")]
+        keep_max: Option<u32>,
+    },
+    /// Control a already running termusic-server without starting the TUI.
+    Ctl {
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+}
+
+/// Commands to send to a running server
+#[derive(Subcommand, Debug)]
+pub enum CtlCommand {
+    /// Toggle between playing and paused.
+    TogglePause,
+    /// Skip to the next track.
+    Next,
+    /// Skip to the previous track.
+    Previous,
+    /// Seek in the current track by the configured step.
+    Seek {
+        #[arg(value_enum)]
+        direction: SeekDirection,
+    },
+    /// Seek to a absolute position in the current track.
+    SeekTo {
+        /// Position in seconds.
+        #[arg(value_name = "SECONDS")]
+        position: u64,
+    },
+    /// Seek to the start of the next chapter of the current track.
+    NextChapter,
+    /// Seek to the start of the current chapter, or the previous one if the current one just started.
+    PreviousChapter,
+    /// Change the volume by the configured step.
+    Volume {
+        #[arg(value_enum)]
+        direction: VolumeDirection,
+    },
+    /// Set the volume to a absolute value (0 to 100).
+    SetVolume {
+        #[arg(value_parser = clap::value_parser!(u16).range(0..=100))]
+        volume: u16,
+    },
+    /// Set the playback speed (0.1 to 3.0).
+    SetSpeed { speed: f32 },
+    /// Cycle the loop mode (single, playlist, random).
+    Loop,
+    /// Add files or urls to the playlist.
+    Enqueue {
+        /// Index to insert the tracks at, default is to append them.
+        #[arg(long, conflicts_with_all = ["next", "up_next"])]
+        at: Option<u64>,
+        /// Insert the tracks directly after the current track.
+        #[arg(long, conflicts_with = "up_next")]
+        next: bool,
+        /// Add the tracks to the end of the "up next" tracks.
+        #[arg(long)]
+        up_next: bool,
+        #[arg(value_name = "FILE", required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Undo the last change to the playlist.
+    Undo,
+    /// Redo the last undone change to the playlist.
+    Redo,
+    /// List the audio output devices of the server, the selected one is marked with "*".
+    OutputDevices,
+    /// Switch the audio output device of the server.
+    SetOutputDevice {
+        /// Name of the device, the default device is used if omitted.
+        device: Option<String>,
+    },
+    /// Search the music library, see the search popup help for the query syntax.
+    Search {
+        /// Query like `artist:"miles davis" year:<1960 -live`.
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Maximum number of results, the server default is used if omitted.
+        #[arg(long)]
+        limit: Option<u32>,
+        /// Print the results as JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the current status.
+    Status {
+        /// Print the status as JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SeekDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum VolumeDirection {
+    Up,
+    Down,
 }
 
 const DEFAULT_LOGFILE_FILENAME: &str = "termusic-tui.log";
@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use termusiclib::track::Track;
+use termusiclib::utils;
+use termusicplayback::player::PlaylistTrack;
+use termusicplayback::{PlayerProgress, Status};
+
+use crate::cli::{CtlCommand, SeekDirection, VolumeDirection};
+use crate::ui::playback::Playback;
+use crate::CombinedSettings;
+
+/// Connect to the already running server and execute the given command
+///
+/// Unlike the TUI, this will not start a server if none is running.
+pub async fn execute(command: CtlCommand, config: &CombinedSettings) -> Result<()> {
+    let com = {
+        let config_read = config.tui.read();
+        config_read
+            .settings
+            .get_com()
+            .ok_or(anyhow::anyhow!(
+                "Expected tui-com settings to be resolved at this point"
+            ))?
+            .clone()
+    };
+
+    let client = crate::connect(&com)
+        .await
+        .context("connecting to termusic-server")?;
+    let mut playback = Playback::new(client);
+
+    match command {
+        CtlCommand::TogglePause => {
+            let status = playback.toggle_pause().await?;
+            println!("{status}");
+        }
+        CtlCommand::Next => playback.skip_next().await?,
+        CtlCommand::Previous => playback.skip_previous().await?,
+        CtlCommand::Seek { direction } => {
+            let progress = match direction {
+                SeekDirection::Forward => playback.seek_forward().await?,
+                SeekDirection::Backward => playback.seek_backward().await?,
+            };
+            println!("{}", format_progress(&progress));
+        }
+        CtlCommand::SeekTo { position } => {
+            let progress = playback.seek_to(Duration::from_secs(position)).await?;
+            println!("{}", format_progress(&progress));
+        }
+        CtlCommand::NextChapter => playback.chapter_next().await?,
+        CtlCommand::PreviousChapter => playback.chapter_previous().await?,
+        CtlCommand::Volume { direction } => {
+            let volume = match direction {
+                VolumeDirection::Up => playback.volume_up().await?,
+                VolumeDirection::Down => playback.volume_down().await?,
+            };
+            println!("{volume}");
+        }
+        CtlCommand::SetVolume { volume } => {
+            let volume = playback.set_volume(volume).await?;
+            println!("{volume}");
+        }
+        CtlCommand::SetSpeed { speed } => {
+            // the server stores the speed as tenths
+            #[allow(clippy::cast_possible_truncation)]
+            let speed = (speed * 10.0).round() as i32;
+            let speed = playback.set_speed(speed).await?;
+            #[allow(clippy::cast_precision_loss)]
+            let speed = speed as f32 / 10.0;
+            println!("{speed:.1}");
+        }
+        CtlCommand::Loop => playback.cycle_loop().await?,
+        CtlCommand::Enqueue {
+            at,
+            next,
+            up_next,
+            files,
+        } => {
+            let mut tracks = Vec::with_capacity(files.len());
+            for file in files {
+                let as_str = file.to_string_lossy();
+                // urls are passed as-is, everything else is a path relative to the current directory
+                if as_str.starts_with("http") {
+                    tracks.push(as_str.to_string());
+                } else {
+                    let path = utils::absolute_path(&file)?;
+                    tracks.push(path.to_string_lossy().to_string());
+                }
+            }
+
+            if next {
+                playback.playlist_play_next(tracks).await?;
+            } else if up_next {
+                playback.playlist_add_up_next(tracks).await?;
+            } else {
+                playback
+                    .playlist_add_tracks(at.unwrap_or(u64::MAX), tracks)
+                    .await?;
+            }
+        }
+        CtlCommand::Undo => playback.playlist_undo().await?,
+        CtlCommand::Redo => playback.playlist_redo().await?,
+        CtlCommand::OutputDevices => {
+            let devices = playback.get_output_devices().await?;
+            for device in devices.devices {
+                let marker = if device == devices.current { "*" } else { " " };
+                println!("{marker} {device}");
+            }
+        }
+        CtlCommand::SetOutputDevice { device } => playback.set_output_device(device).await?,
+        CtlCommand::Search { query, limit, json } => {
+            let tracks = playback
+                .search_library(query.join(" "), limit.unwrap_or_default())
+                .await?;
+            print_tracks(&tracks, json)?;
+        }
+        CtlCommand::Status { json } => print_status(&mut playback, json).await?,
+    }
+
+    Ok(())
+}
+
+/// Query the server for the current status and print it to stdout
+async fn print_status(playback: &mut Playback, as_json: bool) -> Result<()> {
+    let response = playback.get_progress().await?;
+    let playlist = playback.get_playlist().await?;
+
+    let status = Status::from_u32(response.status);
+    let progress: PlayerProgress = response.progress.unwrap_or_default().into();
+    let current_track = usize::try_from(playlist.current_track_index)
+        .ok()
+        .and_then(|idx| playlist.tracks.get(idx))
+        .filter(|_| status != Status::Stopped);
+    #[allow(clippy::cast_precision_loss)]
+    let speed = response.speed as f32 / 10.0;
+
+    if as_json {
+        let value = json!({
+            "status": status.to_string(),
+            "volume": response.volume,
+            "speed": speed,
+            "gapless": response.gapless,
+            "position": progress.position.map(|v| v.as_secs()),
+            "duration": progress.total_duration.map(|v| v.as_secs()),
+            "radio_title": Some(response.radio_title).filter(|v| !v.is_empty()),
+            "chapter_title": Some(response.chapter_title).filter(|v| !v.is_empty()),
+            "playlist_length": playlist.tracks.len(),
+            "current_track_index": current_track.map(|_| playlist.current_track_index),
+            "current_track": current_track.map(|track| json!({
+                "file": track.file,
+                "title": track.title,
+                "artist": track.artist,
+                "album": track.album,
+            })),
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+
+        return Ok(());
+    }
+
+    println!("Status: {status}");
+    if let Some(track) = current_track {
+        println!(
+            "Track: {}/{} {}",
+            playlist.current_track_index + 1,
+            playlist.tracks.len(),
+            format_track(track)
+        );
+    }
+    if !response.radio_title.is_empty() {
+        println!("Radio: {}", response.radio_title);
+    }
+    if !response.chapter_title.is_empty() {
+        println!("Chapter: {}", response.chapter_title);
+    }
+    println!("Progress: {}", format_progress(&progress));
+    println!("Volume: {}", response.volume);
+    println!("Speed: {speed:.1}");
+    println!("Gapless: {}", response.gapless);
+
+    Ok(())
+}
+
+/// Print the given tracks to stdout, one per line
+fn print_tracks(tracks: &[PlaylistTrack], as_json: bool) -> Result<()> {
+    if as_json {
+        let value: Vec<_> = tracks
+            .iter()
+            .map(|track| {
+                json!({
+                    "file": track.file,
+                    "title": track.title,
+                    "artist": track.artist,
+                    "album": track.album,
+                    "duration": track.duration.map(|v| v.secs),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+
+        return Ok(());
+    }
+
+    for track in tracks {
+        let duration: Duration = track.duration.unwrap_or_default().into();
+        println!(
+            "[{}] {} ({})",
+            Track::duration_formatted_short(&duration),
+            format_track(track),
+            track.file
+        );
+    }
+
+    Ok(())
+}
+
+/// Format a track as "artist - title", falling back to the file if there is no title
+fn format_track(track: &PlaylistTrack) -> String {
+    match (track.artist.is_empty(), track.title.is_empty()) {
+        (_, true) => track.file.clone(),
+        (true, false) => track.title.clone(),
+        (false, false) => format!("{} - {}", track.artist, track.title),
+    }
+}
+
+/// Format a progress as "position / duration"
+fn format_progress(progress: &PlayerProgress) -> String {
+    let position = progress.position.unwrap_or_default();
+    let duration = progress.total_duration.unwrap_or_default();
+
+    format!(
+        "{} / {}",
+        Track::duration_formatted_short(&position),
+        Track::duration_formatted_short(&duration)
+    )
+}
@@ -27,6 +27,7 @@
  * SOFTWARE.
  */
 mod cli;
+mod ctl;
 mod logger;
 mod ui;
 
@@ -38,12 +39,13 @@ use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use std::{error::Error, path::Path};
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
-use termusiclib::config::v2::server::ScanDepth;
+use termusiclib::config::v2::server::{ComProtocol, ComSettings, ScanDepth};
 use termusiclib::config::v2::tui::config_extra::TuiConfigVersionedDefaulted;
 use termusiclib::config::{
     new_shared_server_settings, new_shared_tui_settings, ServerOverlay, SharedServerSettings,
     SharedTuiSettings, TuiOverlay,
 };
+use termusicplayback::auth::TokenInterceptor;
 use termusicplayback::player::music_player_client::MusicPlayerClient;
 
 use sysinfo::{Pid, ProcessStatus, System};
@@ -82,7 +84,7 @@ async fn actual_main() -> Result<()> {
     let config = get_config(&args)?;
 
     if let Some(action) = args.action {
-        return execute_action(action, &config);
+        return execute_action(action, &config).await;
     }
 
     // launch the daemon if it isn't already
@@ -153,14 +155,18 @@ async fn actual_main() -> Result<()> {
     info!("Waiting until connected");
 
     let client = {
-        let addr = {
+        let com = {
             let config_read = config.tui.read();
-            SocketAddr::from(*config_read.settings.get_com().ok_or(anyhow::anyhow!(
-                "Expected tui-com settings to be resolved at this point"
-            ))?)
+            config_read
+                .settings
+                .get_com()
+                .ok_or(anyhow::anyhow!(
+                    "Expected tui-com settings to be resolved at this point"
+                ))?
+                .clone()
         };
 
-        wait_till_connected(addr, pid).await?
+        wait_till_connected(&com, pid).await?
     };
     info!("Connected!");
 
@@ -176,13 +182,10 @@ const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
 const WAIT_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Wait until tonic is connected, or:
-/// - tonic errors anything other than `ConnectionRefused`
+/// - tonic errors anything other than `ConnectionRefused` (or `NotFound` for unix sockets)
 /// - given PID does not exist anymore
 /// - timeout of [`WAIT_TIMEOUT`] reached
-async fn wait_till_connected(
-    socket: SocketAddr,
-    pid: u32,
-) -> Result<MusicPlayerClient<tonic::transport::Channel>> {
+async fn wait_till_connected(com: &ComSettings, pid: u32) -> Result<ui::playback::Client> {
     let mut sys = sysinfo::System::new();
     let sys_pid = Pid::from_u32(pid);
     let start_time = Instant::now();
@@ -203,11 +206,15 @@ async fn wait_till_connected(
             anyhow::bail!("Process {pid} exited before being able to connect!");
         }
 
-        match MusicPlayerClient::connect(format!("http://{socket}")).await {
+        match connect(com).await {
             Err(err) => {
                 // downcast "tonic::transport::Error" to a "std::io::Error"(kind: Os)
-                if let Some(os_err) = find_source::<std::io::Error>(&err) {
-                    if os_err.kind() == std::io::ErrorKind::ConnectionRefused {
+                if let Some(os_err) = find_source::<std::io::Error>(&*err) {
+                    // "NotFound" happens when the server has not created the unix socket yet
+                    if matches!(
+                        os_err.kind(),
+                        std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::NotFound
+                    ) {
                         debug!("Connection refused found!");
                         tokio::time::sleep(WAIT_INTERVAL).await;
                         continue;
@@ -222,6 +229,40 @@ async fn wait_till_connected(
     }
 }
 
+/// Connect to the server with the transport configured in `com`
+pub async fn connect(com: &ComSettings) -> Result<ui::playback::Client> {
+    let channel = match com.protocol {
+        ComProtocol::Tcp => {
+            let addr = SocketAddr::from(com);
+            tonic::transport::Endpoint::new(format!("http://{addr}"))?
+                .connect()
+                .await?
+        }
+        #[cfg(unix)]
+        ComProtocol::Uds => {
+            let path = com.socket_path()?;
+            // the uri is not used by the connector, but tonic requires a valid one
+            tonic::transport::Endpoint::from_static("http://[::]:50101")
+                .connect_with_connector(tower::service_fn(move |_: tonic::transport::Uri| {
+                    let path = path.clone();
+                    async move {
+                        let stream = tokio::net::UnixStream::connect(path).await?;
+                        Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+                    }
+                }))
+                .await?
+        }
+        #[cfg(not(unix))]
+        ComProtocol::Uds => {
+            bail!("Unix domain sockets are not supported on this platform, use protocol \"tcp\"")
+        }
+    };
+
+    let interceptor = TokenInterceptor::new(com.token.as_deref())?;
+
+    Ok(MusicPlayerClient::with_interceptor(channel, interceptor))
+}
+
 /// Find a specific error in the [`Error::source`] chain
 fn find_source<E: Error + 'static>(err: &dyn Error) -> Option<&E> {
     let mut err = err.source();
@@ -288,7 +329,7 @@ fn get_path(dir: &Path) -> Result<PathBuf> {
     bail!("Error: non-existing directory '{}'", dir.display());
 }
 
-fn execute_action(action: cli::Action, config: &CombinedSettings) -> Result<()> {
+async fn execute_action(action: cli::Action, config: &CombinedSettings) -> Result<()> {
     match action {
         cli::Action::Import { file } => {
             println!("need to import from file {}", file.display());
@@ -311,7 +352,77 @@ fn execute_action(action: cli::Action, config: &CombinedSettings) -> Result<()>
                 utils::get_app_config_path().context("getting app-config-path")?;
             podcast::export_to_opml(&config_dir_path, &path).context("export opml")?;
         }
+        cli::Action::PodcastPolicy {
+            feed,
+            refresh_hours,
+            auto_download,
+            keep_days,
+            keep_max,
+        } => {
+            let config_dir_path =
+                utils::get_app_config_path().context("getting app-config-path")?;
+            let db = podcast::db::Database::new(&config_dir_path)?;
+            let podcasts = db.get_podcasts()?;
+            let Some(podcast) = podcasts
+                .iter()
+                .find(|v| v.url == feed || v.title.eq_ignore_ascii_case(&feed))
+            else {
+                bail!("No podcast feed with url or title \"{feed}\"");
+            };
+
+            let mut policy = podcast.policy;
+            if let Some(hours) = refresh_hours {
+                policy.refresh_interval = Some(hours)
+                    .filter(|v| *v > 0)
+                    .map(|v| Duration::from_secs(u64::from(v) * 60 * 60));
+            }
+            if let Some(amount) = auto_download {
+                policy.auto_download = amount;
+            }
+            if let Some(days) = keep_days {
+                policy.keep_days = Some(days).filter(|v| *v > 0);
+            }
+            if let Some(amount) = keep_max {
+                policy.keep_max = Some(amount).filter(|v| *v > 0);
+            }
+            if policy != podcast.policy {
+                db.set_policy(podcast.id, &policy)?;
+            }
+
+            print_podcast_policy(&podcast.title, &policy);
+        }
+        cli::Action::Ctl { command } => ctl::execute(command, config).await?,
     };
 
     Ok(())
 }
+
+fn print_podcast_policy(title: &str, policy: &podcast::PodcastPolicy) {
+    let disabled = || "off".to_string();
+    println!("{title}");
+    println!(
+        "  refresh:       {}",
+        policy.refresh_interval.map_or_else(disabled, |v| format!(
+            "every {} hours",
+            v.as_secs() / 60 / 60
+        ))
+    );
+    println!(
+        "  auto-download: {}",
+        Some(policy.auto_download)
+            .filter(|v| *v > 0)
+            .map_or_else(disabled, |v| format!("newest {v} unplayed"))
+    );
+    println!(
+        "  keep-days:     {}",
+        policy
+            .keep_days
+            .map_or_else(disabled, |v| format!("{v} days after download once played"))
+    );
+    println!(
+        "  keep-max:      {}",
+        policy
+            .keep_max
+            .map_or_else(disabled, |v| format!("{v} downloads"))
+    );
+}
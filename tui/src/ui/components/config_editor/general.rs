@@ -24,7 +24,7 @@
 use crate::ui::{ConfigEditorMsg, Msg};
 use crate::CombinedSettings;
 
-use termusiclib::config::v2::tui::{keys::Keys, Alignment as XywhAlign};
+use termusiclib::config::v2::tui::{columns::PlaylistColumns, keys::Keys, Alignment as XywhAlign};
 use termusiclib::config::SharedTuiSettings;
 use tui_realm_stdlib::{Input, Radio};
 use tuirealm::props::{Alignment, BorderType, Borders, Color, InputType, Style};
@@ -554,6 +554,52 @@ impl Component<Msg, NoUserEvent> for PodcastMaxRetries {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ConfigPlaylistColumns {
+    component: Input,
+    config: SharedTuiSettings,
+}
+
+impl ConfigPlaylistColumns {
+    pub fn new(config: CombinedSettings) -> Self {
+        let component = {
+            let config_tui = config.tui.read();
+            Input::default()
+                .borders(
+                    Borders::default()
+                        .color(config_tui.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(config_tui.settings.theme.library_highlight())
+                .input_type(InputType::Text)
+                .invalid_style(Style::default().fg(Color::Red))
+                .placeholder(
+                    PlaylistColumns::DEFAULT,
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title(" Playlist Columns: ", Alignment::Left)
+                .value(config_tui.settings.playlist.columns.to_string())
+        };
+
+        Self {
+            component,
+            config: config.tui,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ConfigPlaylistColumns {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_input_ev(
+            &mut self.component,
+            ev,
+            &self.config.read().settings.keys,
+            Msg::ConfigEditor(ConfigEditorMsg::PlaylistColumnsBlurDown),
+            Msg::ConfigEditor(ConfigEditorMsg::PlaylistColumnsBlurUp),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct AlbumPhotoAlign {
     component: Radio,
@@ -96,14 +96,19 @@ impl Model {
                     .ok();
             }
             ConfigEditorMsg::PodcastSimulDownloadBlurDown
-            | ConfigEditorMsg::AlbumPhotoAlignBlurUp => {
+            | ConfigEditorMsg::PlaylistColumnsBlurUp => {
                 self.app
                     .active(&Id::ConfigEditor(IdConfigEditor::PodcastMaxRetries))
                     .ok();
             }
 
-            ConfigEditorMsg::PodcastMaxRetriesBlurDown
-            | ConfigEditorMsg::SaveLastPosotionBlurUp => {
+            ConfigEditorMsg::PodcastMaxRetriesBlurDown | ConfigEditorMsg::AlbumPhotoAlignBlurUp => {
+                self.app
+                    .active(&Id::ConfigEditor(IdConfigEditor::PlaylistColumns))
+                    .ok();
+            }
+
+            ConfigEditorMsg::PlaylistColumnsBlurDown | ConfigEditorMsg::SaveLastPosotionBlurUp => {
                 self.app
                     .active(&Id::ConfigEditor(IdConfigEditor::AlbumPhotoAlign))
                     .ok();
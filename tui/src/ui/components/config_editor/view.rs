@@ -20,16 +20,16 @@ use crate::ui::components::{
     ConfigLibrarySwitchRoot, ConfigLibraryTagEditor, ConfigLibraryTitle, ConfigLibraryYank,
     ConfigLyricBackground, ConfigLyricBorder, ConfigLyricForeground, ConfigLyricTitle,
     ConfigPlaylistAddRandomAlbum, ConfigPlaylistAddRandomTracks, ConfigPlaylistBackground,
-    ConfigPlaylistBorder, ConfigPlaylistDelete, ConfigPlaylistDeleteAll, ConfigPlaylistForeground,
-    ConfigPlaylistHighlight, ConfigPlaylistHighlightSymbol, ConfigPlaylistModeCycle,
-    ConfigPlaylistPlaySelected, ConfigPlaylistSearch, ConfigPlaylistShuffle,
-    ConfigPlaylistSwapDown, ConfigPlaylistSwapUp, ConfigPlaylistTitle, ConfigPodcastDeleteAllFeeds,
-    ConfigPodcastDeleteFeed, ConfigPodcastEpDeleteFile, ConfigPodcastEpDownload,
-    ConfigPodcastMarkAllPlayed, ConfigPodcastMarkPlayed, ConfigPodcastRefreshAllFeeds,
-    ConfigPodcastRefreshFeed, ConfigPodcastSearchAddFeed, ConfigProgressBackground,
-    ConfigProgressBorder, ConfigProgressForeground, ConfigProgressTitle, ConfigSavePopup,
-    ConfigSeekStep, ExitConfirmation, GlobalListener, KillDaemon, MusicDir, PlayerPort,
-    PlayerUseDiscord, PlayerUseMpris, PlaylistDisplaySymbol, PlaylistRandomAlbum,
+    ConfigPlaylistBorder, ConfigPlaylistColumns, ConfigPlaylistDelete, ConfigPlaylistDeleteAll,
+    ConfigPlaylistForeground, ConfigPlaylistHighlight, ConfigPlaylistHighlightSymbol,
+    ConfigPlaylistModeCycle, ConfigPlaylistPlaySelected, ConfigPlaylistSearch,
+    ConfigPlaylistShuffle, ConfigPlaylistSwapDown, ConfigPlaylistSwapUp, ConfigPlaylistTitle,
+    ConfigPodcastDeleteAllFeeds, ConfigPodcastDeleteFeed, ConfigPodcastEpDeleteFile,
+    ConfigPodcastEpDownload, ConfigPodcastMarkAllPlayed, ConfigPodcastMarkPlayed,
+    ConfigPodcastRefreshAllFeeds, ConfigPodcastRefreshFeed, ConfigPodcastSearchAddFeed,
+    ConfigProgressBackground, ConfigProgressBorder, ConfigProgressForeground, ConfigProgressTitle,
+    ConfigSavePopup, ConfigSeekStep, ExitConfirmation, GlobalListener, KillDaemon, MusicDir,
+    PlayerPort, PlayerUseDiscord, PlayerUseMpris, PlaylistDisplaySymbol, PlaylistRandomAlbum,
     PlaylistRandomTrack, PodcastDir, PodcastMaxRetries, PodcastSimulDownload, SaveLastPosition,
 };
 use include_dir::DirEntry;
@@ -67,6 +67,7 @@ use crate::ui::{Application, Id, IdConfigEditor, IdKey, Msg};
 use anyhow::{bail, Result};
 use std::num::{NonZeroU32, NonZeroU8};
 use std::path::PathBuf;
+use termusiclib::config::v2::tui::columns::PlaylistColumns;
 use termusiclib::config::v2::tui::Alignment as XywhAlign;
 use tuirealm::event::NoUserEvent;
 use tuirealm::props::{PropPayload, PropValue, TableBuilder, TextSpan};
@@ -182,6 +183,11 @@ impl Model {
                     f,
                     chunks_middle_left[7],
                 );
+                self.app.view(
+                    &Id::ConfigEditor(IdConfigEditor::PlaylistColumns),
+                    f,
+                    chunks_middle_left[8],
+                );
                 self.app.view(
                     &Id::ConfigEditor(IdConfigEditor::AlbumPhotoAlign),
                     f,
@@ -1827,6 +1833,15 @@ impl Model {
             )
             .is_ok());
 
+        assert!(self
+            .app
+            .remount(
+                Id::ConfigEditor(IdConfigEditor::PlaylistColumns),
+                Box::new(ConfigPlaylistColumns::new(self.get_combined_settings())),
+                vec![]
+            )
+            .is_ok());
+
         assert!(self
             .app
             .remount(
@@ -2944,6 +2959,9 @@ impl Model {
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::Key(IdKey::GlobalQuit)))
             .ok();
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::PlaylistColumns))
+            .ok();
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::Key(IdKey::GlobalLeft)))
             .ok();
@@ -3407,6 +3425,19 @@ impl Model {
                 }
             }
         }
+        if let Ok(State::One(StateValue::String(playlist_columns))) = self
+            .app
+            .state(&Id::ConfigEditor(IdConfigEditor::PlaylistColumns))
+        {
+            config_tui.settings.playlist.columns = if playlist_columns.trim().is_empty() {
+                PlaylistColumns::default()
+            } else {
+                match PlaylistColumns::parse(&playlist_columns) {
+                    Ok(columns) => columns,
+                    Err(err) => bail!(" {err} "),
+                }
+            };
+        }
         if let Ok(State::One(StateValue::Usize(align))) = self
             .app
             .state(&Id::ConfigEditor(IdConfigEditor::AlbumPhotoAlign))
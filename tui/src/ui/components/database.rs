@@ -1,18 +1,21 @@
 use crate::ui::Model;
 use std::path::Path;
 use termusiclib::config::SharedTuiSettings;
-use termusiclib::library_db::SearchCriteria;
+use termusiclib::library_db::{SearchCriteria, SearchQuery};
 use termusiclib::types::{DBMsg, Id, Msg};
 use termusiclib::utils::{is_playlist, playlist_get_vec};
 use tui_realm_stdlib::List;
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::props::Borders;
-use tuirealm::props::{Alignment, BorderType, TableBuilder, TextSpan};
+use tuirealm::props::{Alignment, BorderType, PropPayload, PropValue, TableBuilder, TextSpan};
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers, NoUserEvent},
     AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
 };
 
+/// Maximum number of tracks shown in the search popup, the most relevant are shown first
+const DATABASE_SEARCH_LIMIT: u32 = 500;
+
 #[derive(MockComponent)]
 pub struct DBListCriteria {
     component: List,
@@ -51,6 +54,8 @@ impl DBListCriteria {
                         .add_col(TextSpan::from("Directory"))
                         .add_row()
                         .add_col(TextSpan::from("Playlists"))
+                        .add_row()
+                        .add_col(TextSpan::from("Smart Playlists"))
                         .build(),
                 )
         };
@@ -271,6 +276,13 @@ impl Component<Msg, NoUserEvent> for DBListSearchResult {
                 }
                 CmdResult::None
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left | Key::Backspace,
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::DataBase(DBMsg::SearchResultBack)),
+            Event::Keyboard(key) if key == keys.navigation_keys.left.get() => {
+                return Some(Msg::DataBase(DBMsg::SearchResultBack))
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Tab,
                 modifiers: KeyModifiers::NONE,
@@ -405,6 +417,9 @@ impl Component<Msg, NoUserEvent> for DBListSearchTracks {
             Event::Keyboard(keyevent) if keyevent == keys.database_keys.add_all.get() => {
                 return Some(Msg::DataBase(DBMsg::AddAllToPlaylist))
             }
+            Event::Keyboard(keyevent) if keyevent == keys.database_keys.cycle_sort.get() => {
+                return Some(Msg::DataBase(DBMsg::CycleSort))
+            }
 
             Event::Keyboard(keyevent) if keyevent == keys.library_keys.search.get() => {
                 return Some(Msg::GeneralSearch(crate::ui::GSMsg::PopupShowDatabase))
@@ -517,16 +532,107 @@ impl Model {
             SearchCriteria::Playlist => {
                 self.dw.search_results = self.database_get_playlist();
             }
+            SearchCriteria::SmartPlaylist => {
+                self.dw.search_results = self
+                    .config_tui
+                    .read()
+                    .settings
+                    .smart_playlists
+                    .iter()
+                    .map(|v| v.name.clone())
+                    .collect();
+            }
             _ => {
-                if let Ok(results) = self.db.get_criterias(&self.dw.criteria) {
+                if let Ok(results) = self
+                    .db
+                    .get_criterias_by_filters(&self.dw.criteria, &filters_as_ref(&self.dw.filters))
+                {
                     self.dw.search_results = results;
                 }
             }
         }
         self.database_sync_results();
+        self.database_update_result_title();
         self.app.active(&Id::DBListSearchResult).ok();
     }
 
+    /// Show the values selected while drilling down in the title of the result list
+    fn database_update_result_title(&mut self) {
+        let title = if self.dw.filters.is_empty() {
+            " Result ".to_string()
+        } else {
+            let path: Vec<&str> = self.dw.filters.iter().map(|(_, v)| v.as_str()).collect();
+            format!(" Result: {} ", path.join(" > "))
+        };
+
+        self.app
+            .attr(
+                &Id::DBListSearchResult,
+                Attribute::Title,
+                AttrValue::Title((title, Alignment::Left)),
+            )
+            .ok();
+    }
+
+    /// Go back up one level after drilling down, selecting the value that was drilled into
+    pub fn database_drill_up(&mut self) {
+        let Some((criteria, value)) = self.dw.filters.pop() else {
+            return;
+        };
+        self.dw.criteria = criteria;
+        self.database_update_search_results();
+
+        if let Some(index) = self.dw.search_results.iter().position(|v| *v == value) {
+            self.app
+                .attr(
+                    &Id::DBListSearchResult,
+                    Attribute::Value,
+                    AttrValue::Payload(PropPayload::One(PropValue::Usize(index))),
+                )
+                .ok();
+        }
+
+        if self.dw.filters.is_empty() {
+            self.dw.search_tracks = Vec::new();
+        } else {
+            self.database_load_filtered_tracks(&[]);
+        }
+        self.database_sync_tracks();
+    }
+
+    /// Cycle to the next [`DatabaseSort`] mode and re-sort the current tracks
+    pub fn database_cycle_sort(&mut self) {
+        self.dw.sort = self.dw.sort.next();
+        self.database_sort_tracks();
+        self.database_sync_tracks();
+    }
+
+    /// Sort the tracks by the current [`DatabaseSort`], except for playlist files which keep their order
+    fn database_sort_tracks(&mut self) {
+        if self.dw.criteria != SearchCriteria::Playlist {
+            self.dw.sort.sort(&mut self.dw.search_tracks);
+        }
+
+        self.app
+            .attr(
+                &Id::DBListSearchTracks,
+                Attribute::Title,
+                AttrValue::Title((format!(" Tracks (by {}) ", self.dw.sort), Alignment::Left)),
+            )
+            .ok();
+    }
+
+    /// Load the tracks matching all current drill-down filters plus the `extra` filters
+    fn database_load_filtered_tracks(&mut self, extra: &[(SearchCriteria, &str)]) {
+        let mut filters = filters_as_ref(&self.dw.filters);
+        filters.extend_from_slice(extra);
+
+        if let Ok(vec) = self.db.get_records_by_filters(&filters) {
+            self.dw.search_tracks = vec;
+        }
+        self.database_sort_tracks();
+    }
+
     fn database_get_playlist(&self) -> Vec<String> {
         let mut vec = Vec::new();
 
@@ -559,13 +665,43 @@ impl Model {
                     }
                 }
             }
+            SearchCriteria::SmartPlaylist => {
+                let playlist = self
+                    .config_tui
+                    .read()
+                    .settings
+                    .smart_playlists
+                    .get(index)
+                    .cloned();
+                if let Some(playlist) = playlist {
+                    match self.db.get_smart_playlist_tracks(&playlist) {
+                        Ok(vec) => {
+                            self.dw.search_tracks = vec;
+                            self.database_sort_tracks();
+                        }
+                        Err(err) => self.mount_error_popup(
+                            anyhow::Error::from(err)
+                                .context(format!("smart playlist \"{}\"", playlist.name)),
+                        ),
+                    }
+                }
+            }
             _ => {
-                if let Ok(vec) = self
-                    .db
-                    .get_record_by_criteria(&self.dw.search_results[index], &self.dw.criteria)
-                {
-                    self.dw.search_tracks = vec;
+                let Some(value) = self.dw.search_results.get(index).cloned() else {
+                    return;
                 };
+
+                if let Some(next) = drill_down_criteria(self.dw.criteria) {
+                    // show all tracks of the selected value while choosing the next level
+                    self.dw.filters.push((self.dw.criteria, value));
+                    self.dw.criteria = next;
+                    self.database_load_filtered_tracks(&[]);
+                    self.database_sync_tracks();
+                    self.database_update_search_results();
+                    return;
+                }
+
+                self.database_load_filtered_tracks(&[(self.dw.criteria, &value)]);
             }
         }
 
@@ -620,15 +756,13 @@ impl Model {
 
     pub fn database_update_search(&mut self, input: &str) {
         let mut table: TableBuilder = TableBuilder::default();
-        let mut idx = 0;
-        let search = format!("*{}*", input.to_lowercase());
-        let mut db_tracks = vec![];
-        if let Ok(tracks) = self.db.get_all_records() {
-            db_tracks.clone_from(&tracks);
-            for record in tracks {
-                if wildmatch::WildMatch::new(&search).matches(&record.artist.to_lowercase())
-                    | wildmatch::WildMatch::new(&search).matches(&record.title.to_lowercase())
-                {
+
+        let tracks = SearchQuery::parse(input)
+            .and_then(|query| Ok(self.db.search_tracks(&query, DATABASE_SEARCH_LIMIT)?));
+
+        match tracks {
+            Ok(tracks) if !tracks.is_empty() => {
+                for (idx, record) in tracks.into_iter().enumerate() {
                     if idx > 0 {
                         table.add_row();
                     }
@@ -645,19 +779,38 @@ impl Model {
                         )
                         .add_col(TextSpan::new(record.title).bold())
                         .add_col(TextSpan::new(record.file));
-                    // .add_col(TextSpan::new(record.album().unwrap_or("Unknown Album")));
-                    idx += 1;
                 }
             }
-        }
-
-        if db_tracks.is_empty() {
-            table.add_col(TextSpan::from("0"));
-            table.add_col(TextSpan::from("empty tracks from db"));
-            table.add_col(TextSpan::from(""));
+            Ok(_) => {
+                table.add_col(TextSpan::from("0"));
+                table.add_col(TextSpan::from("no matching tracks in db"));
+                table.add_col(TextSpan::from(""));
+            }
+            Err(err) => {
+                // likely a incomplete query while typing, so only show it instead of a error popup
+                table.add_col(TextSpan::from("!"));
+                table.add_col(TextSpan::from(format!("{err:#}")));
+                table.add_col(TextSpan::from(""));
+            }
         }
         let table = table.build();
 
         self.general_search_update_show(table);
     }
 }
+
+/// Get the criteria to browse next when drilling down from `criteria`, `None` if the next level are the tracks
+///
+/// This allows browsing `Genre -> Artist -> Album -> Tracks`.
+fn drill_down_criteria(criteria: SearchCriteria) -> Option<SearchCriteria> {
+    match criteria {
+        SearchCriteria::Genre => Some(SearchCriteria::Artist),
+        SearchCriteria::Artist => Some(SearchCriteria::Album),
+        _ => None,
+    }
+}
+
+/// Borrow the owned filters for use in [`DataBase`](termusiclib::library_db::DataBase) queries
+fn filters_as_ref(filters: &[(SearchCriteria, String)]) -> Vec<(SearchCriteria, &str)> {
+    filters.iter().map(|(c, v)| (*c, v.as_str())).collect()
+}
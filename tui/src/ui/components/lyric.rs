@@ -163,6 +163,10 @@ impl Model {
         if self.podcast.podcasts.is_empty() {
             return Ok(());
         }
+        if self.lyric_update_for_transcript() {
+            self.lyric_update_title();
+            return Ok(());
+        }
         if let Ok(State::One(StateValue::Usize(episode_index))) = self.app.state(&Id::Episode) {
             let podcast_selected = self
                 .podcast
@@ -182,6 +186,37 @@ impl Model {
         Ok(())
     }
 
+    /// Show the current line of the transcript in the podcast view, if the selected episode is playing and has a transcript
+    ///
+    /// Returns whether a transcript line is shown.
+    pub fn lyric_update_for_transcript(&mut self) -> bool {
+        let Some(song) = &self.current_song else {
+            return false;
+        };
+        let Some(transcript) = song
+            .parsed_lyric()
+            .filter(|_| song.media_type == MediaType::Podcast)
+        else {
+            return false;
+        };
+        let Ok(State::One(StateValue::Usize(episode_index))) = self.app.state(&Id::Episode) else {
+            return false;
+        };
+        let is_selected = self
+            .podcast
+            .podcasts
+            .get(self.podcast.podcasts_index)
+            .and_then(|v| v.episodes.get(episode_index))
+            .is_some_and(|ep| song.file() == Some(ep.url.as_str()));
+        if !is_selected {
+            return false;
+        }
+
+        let line = transcript.get_text(self.time_pos).unwrap_or_default();
+        self.lyric_set_lyric(line);
+        true
+    }
+
     pub fn lyric_update_for_episode_after(&mut self, po_title: &str, ep: &Episode) {
         // convert <br/> tags to a single line break
         let br_to_lb = RE_BR_TAGS.replace_all(&ep.description, "\n");
@@ -244,6 +279,8 @@ impl Model {
                 AttrValue::Payload(PropPayload::Vec(final_vec)),
             )
             .ok();
+        // the details replaced whatever line was shown before
+        self.lyric_line.clear();
     }
 
     pub fn lyric_update(&mut self) {
@@ -263,14 +300,15 @@ impl Model {
             }
 
             let mut line = String::new();
-            if song.lyric_frames_is_empty() {
-                self.lyric_set_lyric("No lyrics available.");
+            // podcast transcripts are only a parsed lyric, without any lyric frames
+            if song.lyric_frames_is_empty() && song.parsed_lyric().is_none() {
+                self.lyric_set_no_lyric();
                 return;
             }
 
             if let Some(l) = song.parsed_lyric() {
                 if l.unsynced_captions.is_empty() {
-                    self.lyric_set_lyric("No lyrics available.");
+                    self.lyric_set_no_lyric();
                     return;
                 }
                 if let Some(l) = l.get_text(self.time_pos) {
@@ -293,6 +331,20 @@ impl Model {
         }
     }
 
+    /// Store the title of the chapter currently playing, shown in place of lyrics if there are none
+    pub fn lyric_update_for_chapter<T: Into<String>>(&mut self, chapter_title: T) {
+        self.chapter_title = chapter_title.into();
+        self.lyric_update();
+    }
+
+    fn lyric_set_no_lyric(&mut self) {
+        if self.chapter_title.is_empty() {
+            self.lyric_set_lyric("No lyrics available.");
+        } else {
+            self.lyric_set_lyric(format!("Chapter: {}", self.chapter_title));
+        }
+    }
+
     fn lyric_set_lyric<T: Into<String>>(&mut self, text: T) {
         let text = text.into();
         if self.lyric_line == *text {
@@ -57,7 +57,9 @@ pub use tag_editor::*;
 use termusiclib::config::v2::tui::keys::Keys;
 use termusiclib::config::SharedTuiSettings;
 
-use crate::ui::{ConfigEditorMsg, Id, IdConfigEditor, IdTagEditor, Model, Msg, PLMsg, XYWHMsg};
+use crate::ui::{
+    ConfigEditorMsg, EQMsg, Id, IdConfigEditor, IdTagEditor, Model, Msg, PLMsg, SPMsg, XYWHMsg,
+};
 use tui_realm_stdlib::Phantom;
 use tuirealm::event::NoUserEvent;
 use tuirealm::{Component, Event, MockComponent, Sub, SubClause, SubEventClause};
@@ -113,12 +115,26 @@ impl Component<Msg, NoUserEvent> for GlobalListener {
             Event::Keyboard(keyevent) if keyevent == keys.select_view_keys.open_help.get() => {
                 Some(Msg::HelpPopupShow)
             }
+            Event::Keyboard(keyevent) if keyevent == keys.select_view_keys.open_equalizer.get() => {
+                Some(Msg::Equalizer(EQMsg::PopupShow))
+            }
+            Event::Keyboard(keyevent)
+                if keyevent == keys.select_view_keys.open_saved_playlists.get() =>
+            {
+                Some(Msg::SavedPlaylists(SPMsg::PopupShow))
+            }
             Event::Keyboard(keyevent) if keyevent == keys.player_keys.seek_forward.get() => {
                 Some(Msg::PlayerSeekForward)
             }
             Event::Keyboard(keyevent) if keyevent == keys.player_keys.seek_backward.get() => {
                 Some(Msg::PlayerSeekBackward)
             }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.next_chapter.get() => {
+                Some(Msg::PlayerChapterNext)
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.previous_chapter.get() => {
+                Some(Msg::PlayerChapterPrevious)
+            }
             Event::Keyboard(keyevent) if keyevent == keys.player_keys.speed_up.get() => {
                 Some(Msg::PlayerSpeedUp)
             }
@@ -246,6 +262,14 @@ impl Model {
                 SubEventClause::Keyboard(keys.select_view_keys.open_help.get()),
                 Self::no_popup_mounted_clause(),
             ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.select_view_keys.open_equalizer.get()),
+                Self::no_popup_mounted_clause(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.select_view_keys.open_saved_playlists.get()),
+                Self::no_popup_mounted_clause(),
+            ),
             Sub::new(
                 SubEventClause::Keyboard(keys.player_keys.seek_forward.get()),
                 Self::no_popup_mounted_clause(),
@@ -254,6 +278,14 @@ impl Model {
                 SubEventClause::Keyboard(keys.player_keys.seek_backward.get()),
                 Self::no_popup_mounted_clause(),
             ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.next_chapter.get()),
+                Self::no_popup_mounted_clause(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.previous_chapter.get()),
+                Self::no_popup_mounted_clause(),
+            ),
             Sub::new(
                 SubEventClause::Keyboard(keys.lyric_keys.adjust_offset_forwards.get()),
                 Self::no_popup_mounted_clause(),
@@ -332,7 +364,19 @@ impl Model {
             Box::new(SubClause::IsMounted(Id::FeedDeleteConfirmRadioPopup)),
             Box::new(SubClause::Or(
                 Box::new(SubClause::IsMounted(Id::FeedDeleteConfirmInputPopup)),
-                Box::new(SubClause::IsMounted(Id::PodcastSearchTablePopup)),
+                Box::new(SubClause::Or(
+                    Box::new(SubClause::IsMounted(Id::PodcastSearchTablePopup)),
+                    Box::new(SubClause::Or(
+                        Box::new(SubClause::IsMounted(Id::EqualizerPopup)),
+                        Box::new(SubClause::Or(
+                            Box::new(SubClause::IsMounted(Id::SavedPlaylistsPopup)),
+                            Box::new(SubClause::Or(
+                                Box::new(SubClause::IsMounted(Id::SavedPlaylistNamePopup)),
+                                Box::new(SubClause::IsMounted(Id::PodcastPlaybackSettingsPopup)),
+                            )),
+                        )),
+                    )),
+                )),
             )),
         )))
     }
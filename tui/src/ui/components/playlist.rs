@@ -2,8 +2,9 @@ use crate::ui::model::TermusicLayout;
 use crate::ui::Model;
 use anyhow::{anyhow, bail, Result};
 use rand::seq::SliceRandom;
-use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::Path;
+use termusiclib::config::v2::tui::columns::{ColumnAlign, ColumnKind, PlaylistColumns};
 use termusiclib::config::SharedTuiSettings;
 use termusiclib::library_db::SearchCriteria;
 use termusiclib::library_db::TrackDB;
@@ -14,27 +15,48 @@ use termusicplayback::PlayerCmd;
 
 use tui_realm_stdlib::Table;
 use tuirealm::props::Borders;
-use tuirealm::props::{Alignment, BorderType, PropPayload, PropValue, TableBuilder, TextSpan};
+use tuirealm::props::{
+    Alignment, BorderType, PropPayload, PropValue, Table as TableContent, TableBuilder, TextSpan,
+};
+use tuirealm::tui::layout::Rect;
 use tuirealm::{
     command::{Cmd, CmdResult, Direction, Position},
     event::KeyModifiers,
 };
 use tuirealm::{
     event::{Key, KeyEvent, NoUserEvent},
-    AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, StateValue,
 };
+use unicode_width::UnicodeWidthStr;
+
+/// Spacing between the columns of the playlist table
+const COLUMN_SPACING: u16 = 2;
+
+/// Attribute to set the alignment of each column, as [`PropValue::Alignment`]
+pub const ATTR_COLUMN_ALIGNMENT: &str = "column-alignment";
 
-#[derive(MockComponent)]
 pub struct Playlist {
     component: Table,
     config: SharedTuiSettings,
+    /// Widths of the columns in percent
+    widths: Vec<u16>,
+    /// The table only supports left alignment, so the content is padded on draw for the other alignments
+    alignments: Vec<Alignment>,
+    /// The content as set, without padding
+    content: TableContent,
+    /// The area width the content was last padded for
+    padded_width: Option<u16>,
 }
 
 impl Playlist {
     pub fn new(config: SharedTuiSettings) -> Self {
-        let component = {
+        let (component, widths, alignments) = {
             let config = config.read();
-            Table::default()
+            let columns = &config.settings.playlist.columns;
+            let headers: Vec<&str> = columns.columns().iter().map(|v| v.kind.header()).collect();
+            let widths: Vec<u16> = columns.columns().iter().map(|v| v.width).collect();
+            let alignments = column_alignments(columns);
+            let component = Table::default()
                 .borders(
                     Borders::default()
                         .modifiers(BorderType::Rounded)
@@ -49,19 +71,129 @@ impl Playlist {
                 .rewind(false)
                 .step(4)
                 .row_height(1)
-                .headers(&["Duration", "Artist", "Title", "Album"])
-                .column_spacing(2)
-                .widths(&[12, 20, 25, 43])
+                .headers(&headers)
+                .column_spacing(COLUMN_SPACING)
+                .widths(&widths)
                 .table(
                     TableBuilder::default()
                         .add_col(TextSpan::from("Empty"))
                         .add_col(TextSpan::from("Empty Queue"))
                         .add_col(TextSpan::from("Empty"))
                         .build(),
-                )
+                );
+
+            (component, widths, alignments)
         };
 
-        Self { component, config }
+        Self {
+            component,
+            config,
+            widths,
+            alignments,
+            content: TableContent::new(),
+            padded_width: None,
+        }
+    }
+
+    /// Set the content padded to the column alignments for the given area width
+    fn pad_content(&mut self, area_width: u16) {
+        self.padded_width = Some(area_width);
+        if self.content.is_empty() {
+            return;
+        }
+
+        // the borders, the highlight symbol and the spacing between the columns are not available to the columns
+        let symbol_width = self
+            .config
+            .read()
+            .settings
+            .theme
+            .style
+            .playlist
+            .highlight_symbol
+            .width();
+        let spacing =
+            COLUMN_SPACING * u16::try_from(self.widths.len().saturating_sub(1)).unwrap_or(0);
+        let available = u32::from(
+            area_width
+                .saturating_sub(2)
+                .saturating_sub(u16::try_from(symbol_width).unwrap_or(u16::MAX))
+                .saturating_sub(spacing),
+        );
+
+        let content = self
+            .content
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(idx, span)| {
+                        let percent = u32::from(self.widths.get(idx).copied().unwrap_or(0));
+                        let width = usize::try_from(available * percent / 100).unwrap_or(0);
+                        let align = self.alignments.get(idx).copied().unwrap_or(Alignment::Left);
+                        align_span(span, align, width)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.component
+            .attr(Attribute::Content, AttrValue::Table(content));
+    }
+}
+
+impl MockComponent for Playlist {
+    fn view(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        if self.padded_width != Some(area.width) {
+            self.pad_content(area.width);
+        }
+
+        self.component.view(frame, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        match (&attr, &value) {
+            (Attribute::Custom(ATTR_COLUMN_ALIGNMENT), AttrValue::Payload(PropPayload::Vec(v))) => {
+                self.alignments = v
+                    .iter()
+                    .map(|v| match v {
+                        PropValue::Alignment(align) => *align,
+                        _ => Alignment::Left,
+                    })
+                    .collect();
+                self.padded_width = None;
+                return;
+            }
+            (Attribute::Content, AttrValue::Table(content)) => {
+                self.content.clone_from(content);
+                self.padded_width = None;
+            }
+            (Attribute::Width, AttrValue::Payload(PropPayload::Vec(v))) => {
+                self.widths = v
+                    .iter()
+                    .map(|v| match v {
+                        PropValue::U16(width) => *width,
+                        _ => 0,
+                    })
+                    .collect();
+                self.padded_width = None;
+            }
+            _ => (),
+        }
+
+        self.component.attr(attr, value);
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
     }
 }
 
@@ -151,6 +283,24 @@ impl Component<Msg, NoUserEvent> for Playlist {
             Event::Keyboard(key) if key == keys.playlist_keys.search.get() => {
                 return Some(Msg::GeneralSearch(GSMsg::PopupShowPlaylist))
             }
+            Event::Keyboard(key) if key == keys.playlist_keys.play_next.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Playlist(PLMsg::PlayNext(index)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.undo.get() => {
+                return Some(Msg::Playlist(PLMsg::Undo))
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.redo.get() => {
+                return Some(Msg::Playlist(PLMsg::Redo))
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.add_up_next.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Playlist(PLMsg::AddUpNext(index)));
+                }
+                CmdResult::None
+            }
             Event::Keyboard(key) if key == keys.playlist_keys.swap_down.get() => {
                 match self.component.state() {
                     State::One(StateValue::Usize(index_selected)) => {
@@ -197,36 +347,28 @@ impl Model {
 
     pub fn playlist_switch_layout(&mut self) {
         if self.layout == TermusicLayout::Podcast {
-            let headers = &["Duration", "Episodes"];
-            self.app
-                .attr(
-                    &Id::Playlist,
-                    Attribute::Text,
-                    AttrValue::Payload(PropPayload::Vec(
-                        headers
-                            .iter()
-                            .map(|x| PropValue::Str((*x).to_string()))
-                            // .map(|x| PropValue::Str(x.as_ref().to_string()))
-                            .collect(),
-                    )),
-                )
-                .ok();
-
-            let widths = &[12, 88];
-            self.app
-                .attr(
-                    &Id::Playlist,
-                    Attribute::Width,
-                    AttrValue::Payload(PropPayload::Vec(
-                        widths.iter().map(|x| PropValue::U16(*x)).collect(),
-                    )),
-                )
-                .ok();
+            self.playlist_set_columns(
+                &["Duration", "Episodes"],
+                &[12, 88],
+                &[Alignment::Left, Alignment::Left],
+            );
             self.playlist_sync();
             return;
         }
 
-        let headers = &["Duration", "Artist", "Title", "Album"];
+        let (headers, widths, alignments) = {
+            let config = self.config_tui.read();
+            let columns = &config.settings.playlist.columns;
+            let headers: Vec<&str> = columns.columns().iter().map(|v| v.kind.header()).collect();
+            let widths: Vec<u16> = columns.columns().iter().map(|v| v.width).collect();
+            (headers, widths, column_alignments(columns))
+        };
+        self.playlist_set_columns(&headers, &widths, &alignments);
+        self.playlist_sync();
+    }
+
+    /// Set the headers, widths and alignments of the playlist columns
+    fn playlist_set_columns(&mut self, headers: &[&str], widths: &[u16], alignments: &[Alignment]) {
         self.app
             .attr(
                 &Id::Playlist,
@@ -235,13 +377,11 @@ impl Model {
                     headers
                         .iter()
                         .map(|x| PropValue::Str((*x).to_string()))
-                        // .map(|x| PropValue::Str(x.as_ref().to_string()))
                         .collect(),
                 )),
             )
             .ok();
 
-        let widths = &[12, 20, 25, 43];
         self.app
             .attr(
                 &Id::Playlist,
@@ -251,7 +391,19 @@ impl Model {
                 )),
             )
             .ok();
-        self.playlist_sync();
+
+        self.app
+            .attr(
+                &Id::Playlist,
+                Attribute::Custom(ATTR_COLUMN_ALIGNMENT),
+                AttrValue::Payload(PropPayload::Vec(
+                    alignments
+                        .iter()
+                        .map(|x| PropValue::Alignment(*x))
+                        .collect(),
+                )),
+            )
+            .ok();
     }
 
     fn playlist_add_playlist(&mut self, current_node: &str) -> Result<()> {
@@ -397,6 +549,35 @@ impl Model {
             return;
         }
 
+        let (columns, current_track_symbol) = {
+            let config = self.config_tui.read();
+            (
+                config.settings.playlist.columns.clone(),
+                config
+                    .settings
+                    .theme
+                    .style
+                    .playlist
+                    .current_track_symbol
+                    .clone(),
+            )
+        };
+        // the current track symbol is shown before the title, or the first column if there is no title
+        let symbol_column = if columns.contains(ColumnKind::Title) {
+            ColumnKind::Title
+        } else {
+            columns.columns()[0].kind
+        };
+        // fetched once for all rows, as this runs on every playlist change
+        let play_counts = if columns.contains(ColumnKind::PlayCount) {
+            self.db.get_play_counts().unwrap_or_else(|err| {
+                error!("Error getting play counts: {err}");
+                HashMap::new()
+            })
+        } else {
+            HashMap::new()
+        };
+
         let mut table: TableBuilder = TableBuilder::default();
 
         for (idx, record) in self.playlist.tracks().iter().enumerate() {
@@ -404,41 +585,61 @@ impl Model {
                 table.add_row();
             }
 
-            let duration = record.duration_formatted().to_string();
-            let duration_string = format!("[{duration:^7.7}]");
-
-            let noname_string = "No Name".to_string();
-            let name = record.name().unwrap_or(&noname_string);
-            let artist = record.artist().unwrap_or(name);
-            let mut title: Cow<'_, str> = record.title().unwrap_or("Unknown Title").into();
-            let album = record.album().unwrap_or("Unknown Album");
-
-            // TODO: is there maybe a better option to do this on-demand instead of the whole playlist; like on draw-time?
-            if idx == self.playlist.get_current_track_index() {
-                title = format!(
-                    "{}{title}",
-                    self.config_tui
-                        .read()
-                        .settings
-                        .theme
-                        .style
-                        .playlist
-                        .current_track_symbol
-                )
-                .into();
-            };
+            for column in columns.columns() {
+                let mut span = match column.kind {
+                    ColumnKind::Duration => {
+                        let duration = record.duration_formatted().to_string();
+                        TextSpan::new(format!("[{duration:^7.7}]"))
+                    }
+                    ColumnKind::Artist => {
+                        let artist = record.artist().or(record.name()).unwrap_or("No Name");
+                        TextSpan::new(artist).fg(tuirealm::tui::style::Color::LightYellow)
+                    }
+                    ColumnKind::Title => {
+                        TextSpan::new(record.title().unwrap_or("Unknown Title")).bold()
+                    }
+                    ColumnKind::Album => TextSpan::new(record.album().unwrap_or("Unknown Album")),
+                    ColumnKind::Genre => TextSpan::new(record.genre().unwrap_or_default()),
+                    ColumnKind::TrackNumber => TextSpan::new(format_track_number(record)),
+                    ColumnKind::Year => {
+                        TextSpan::new(record.year().map(|v| v.to_string()).unwrap_or_default())
+                    }
+                    ColumnKind::FileName => TextSpan::new(
+                        record
+                            .file()
+                            .and_then(|v| Path::new(v).file_name())
+                            .map(|v| v.to_string_lossy())
+                            .unwrap_or_default(),
+                    ),
+                    ColumnKind::PlayCount => TextSpan::new(
+                        record
+                            .file()
+                            .map(|v| play_counts.get(v).copied().unwrap_or(0).to_string())
+                            .unwrap_or_default(),
+                    ),
+                };
+
+                // TODO: is there maybe a better option to do this on-demand instead of the whole playlist; like on draw-time?
+                if column.kind == symbol_column && idx == self.playlist.get_current_track_index() {
+                    span.content = format!("{current_track_symbol}{}", span.content);
+                }
+                // "up next" tracks are shown in italic
+                if self.playlist.is_up_next(idx) {
+                    span = span.italic();
+                }
 
-            table
-                .add_col(TextSpan::new(duration_string.as_str()))
-                .add_col(TextSpan::new(artist).fg(tuirealm::tui::style::Color::LightYellow))
-                .add_col(TextSpan::new(title).bold())
-                .add_col(TextSpan::new(album));
+                table.add_col(span);
+            }
         }
         if self.playlist.is_empty() {
-            table.add_col(TextSpan::from("0"));
-            table.add_col(TextSpan::from("empty playlist"));
-            table.add_col(TextSpan::from(""));
-            table.add_col(TextSpan::from(""));
+            for column in columns.columns() {
+                let text = if column.kind == symbol_column {
+                    "empty playlist"
+                } else {
+                    ""
+                };
+                table.add_col(TextSpan::from(text));
+            }
         }
 
         let table = table.build();
@@ -480,6 +681,24 @@ impl Model {
         self.playlist_sync();
     }
 
+    /// Move the track at `index` to be played directly after the current track
+    pub fn playlist_play_next(&mut self, index: usize) {
+        self.playlist.move_to_next(index);
+        if let Err(e) = self.player_sync_playlist() {
+            self.mount_error_popup(e.context("player sync playlist"));
+        }
+        self.playlist_sync();
+    }
+
+    /// Add the track at `index` to the end of the "up next" tracks
+    pub fn playlist_add_up_next(&mut self, index: usize) {
+        self.playlist.move_to_up_next(index);
+        if let Err(e) = self.player_sync_playlist() {
+            self.mount_error_popup(e.context("player sync playlist"));
+        }
+        self.playlist_sync();
+    }
+
     pub fn playlist_update_library_delete(&mut self) {
         self.playlist.remove_deleted_items();
         if let Err(e) = self.player_sync_playlist() {
@@ -499,8 +718,12 @@ impl Model {
             .playlist
             .use_loop_mode_symbol;
         let loop_mode = self.config_server.read().settings.player.loop_mode;
+        let up_next = match self.playlist.up_next_len() {
+            0 => String::new(),
+            amount => format!(" | Up next: {amount}"),
+        };
         let title = format!(
-            "\u{2500} Playlist \u{2500}\u{2500}\u{2524} Total {} tracks | {} | Mode: {} \u{251c}\u{2500}",
+            "\u{2500} Playlist \u{2500}\u{2500}\u{2524} Total {} tracks | {} | Mode: {}{up_next} \u{251c}\u{2500}",
             self.playlist.len(),
             Track::duration_formatted_short(&duration),
             loop_mode.display(display_symbol),
@@ -514,7 +737,7 @@ impl Model {
             .ok();
     }
     pub fn playlist_play_selected(&mut self, index: usize) {
-        self.playlist.set_current_track_index(index);
+        self.playlist.jump_to(index);
         if let Err(e) = self.player_sync_playlist() {
             self.mount_error_popup(e.context("player sync playlist"));
         }
@@ -648,3 +871,42 @@ impl Model {
         Ok(())
     }
 }
+
+/// Format the track number for display, prefixed with the disc number like `2-05` if it is not on the first disc
+fn format_track_number(track: &Track) -> String {
+    match (track.disc_number(), track.track_number()) {
+        (Some(disc), Some(number)) if disc > 1 => format!("{disc}-{number:02}"),
+        (_, Some(number)) => number.to_string(),
+        (_, None) => String::new(),
+    }
+}
+
+/// Get the alignment of each column
+fn column_alignments(columns: &PlaylistColumns) -> Vec<Alignment> {
+    columns
+        .columns()
+        .iter()
+        .map(|v| match v.align {
+            ColumnAlign::Left => Alignment::Left,
+            ColumnAlign::Center => Alignment::Center,
+            ColumnAlign::Right => Alignment::Right,
+        })
+        .collect()
+}
+
+/// Pad the span with leading spaces to align it in a column of the given width
+fn align_span(span: &TextSpan, align: Alignment, width: usize) -> TextSpan {
+    let free = width.saturating_sub(span.content.width());
+    let padding = match align {
+        Alignment::Left => 0,
+        Alignment::Center => free / 2,
+        Alignment::Right => free,
+    };
+
+    let mut span = span.clone();
+    if padding > 0 {
+        span.content = format!("{}{}", " ".repeat(padding), span.content);
+    }
+
+    span
+}
@@ -2,11 +2,13 @@ use crate::ui::Model;
 use anyhow::{anyhow, bail, Result};
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use reqwest::ClientBuilder;
-use sanitize_filename::{sanitize_with_options, Options};
 use serde_json::Value;
 use std::time::Duration;
 use termusiclib::config::SharedTuiSettings;
-use termusiclib::podcast::{download_list, EpData, PodcastFeed, PodcastNoId};
+use termusiclib::podcast::{
+    download_list, fetch_transcript, is_download_running, podcast_dir_name, transcript, EpData,
+    PodcastFeed, PodcastNoId, PodcastPlaybackSettings,
+};
 use termusiclib::track::MediaType;
 use termusiclib::types::{Id, Msg, PCMsg};
 use tokio::runtime::Handle;
@@ -165,6 +167,10 @@ impl Component<Msg, NoUserEvent> for FeedsList {
                 return Some(Msg::Podcast(PCMsg::PodcastRefreshAll));
             }
 
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.playback_settings.get() => {
+                return Some(Msg::Podcast(PCMsg::PlaybackSettingsPopupShow));
+            }
+
             Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.delete_feed.get() => {
                 return Some(Msg::Podcast(PCMsg::FeedDeleteShow));
             }
@@ -329,6 +335,13 @@ impl Component<Msg, NoUserEvent> for EpisodeList {
                 CmdResult::None
             }
 
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.cancel_download.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::EpisodeDownloadCancel(index)));
+                }
+                CmdResult::None
+            }
+
             Event::Keyboard(keyevent)
                 if keyevent == keys.podcast_keys.delete_local_episode.get() =>
             {
@@ -505,6 +518,8 @@ impl Model {
             // if let Some(_) = record.path {
             if record.path.is_some() {
                 title = format!("[D] {title}");
+            } else if let Some(percent) = self.download_tracker.percent(&record.url) {
+                title = format!("[{percent:>2}%] {title}");
             }
             if record.played {
                 table.add_col(TextSpan::new(title).strikethrough());
@@ -725,19 +740,30 @@ impl Model {
 
         if !ep_data.is_empty() {
             // add directory for podcast, create if it does not exist
-            let dir_name = sanitize_with_options(
-                &pod_title,
-                Options {
-                    truncate: true,
-                    windows: true, // for simplicity, we'll just use Windows-friendly paths for everyone
-                    replacement: "",
-                },
-            );
+            let dir_name = podcast_dir_name(&pod_title);
             match crate::utils::create_podcast_dir(&self.config_server.read(), dir_name) {
                 Ok(path) => {
-                    // for ep in ep_data.iter() {
-                    //     self.download_tracker.insert(ep.id);
-                    // }
+                    // the server may already download episodes of feeds with a policy
+                    let (running, ep_data): (Vec<EpData>, Vec<EpData>) = ep_data
+                        .into_iter()
+                        .partition(|ep| is_download_running(ep, &path));
+                    if let Some(ep) = running.first() {
+                        self.show_message_timeout_label_help(
+                            self.download_tracker
+                                .message_download_already_running(&ep.title),
+                            None,
+                            None,
+                            None,
+                        );
+                    }
+
+                    let ep_data = ep_data
+                        .into_iter()
+                        .map(|ep| {
+                            let cancel = self.download_tracker.add_download(&ep.url);
+                            (ep, cancel)
+                        })
+                        .collect();
                     download_list(
                         ep_data,
                         &path,
@@ -778,6 +804,105 @@ impl Model {
         Ok(())
     }
 
+    /// Cancel the running download of the episode at `ep_index` of the selected podcast
+    pub fn episode_download_cancel(&mut self, ep_index: usize) -> Result<()> {
+        let podcast_selected = self
+            .podcast
+            .podcasts
+            .get(self.podcast.podcasts_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+        let ep = podcast_selected
+            .episodes
+            .get(ep_index)
+            .ok_or_else(|| anyhow!("get episode selected failed"))?;
+
+        if !self.download_tracker.cancel_download(&ep.url) {
+            bail!("episode \"{}\" is not being downloaded", ep.title);
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the transcript of the current track in the background, if it has one that is not loaded yet
+    ///
+    /// Once fetched, it is sent to `Model::tx_to_main` as [`PCMsg::TranscriptFetched`].
+    ///
+    /// Requires that the current thread has a entered runtime
+    pub fn podcast_fetch_transcript(&self) {
+        let Some(track) = &self.current_song else {
+            return;
+        };
+        let (Some(url), Some(file)) = (track.transcript_url(), track.file()) else {
+            return;
+        };
+        let url = url.to_string();
+        let file = file.to_string();
+        let tx = self.tx_to_main.clone();
+
+        Handle::current().spawn(async move {
+            match fetch_transcript(&url).await {
+                Ok(text) => {
+                    tx.send(Msg::Podcast(PCMsg::TranscriptFetched(file, text)))
+                        .ok();
+                }
+                Err(err) => warn!("Fetching transcript from \"{url}\" failed: {err:#}"),
+            }
+        });
+    }
+
+    /// Show the fetched transcript of the episode `file` and cache it if the episode is downloaded
+    pub fn podcast_transcript_fetched(&mut self, file: &str, text: &str) -> Result<()> {
+        let parsed = transcript::parse(text);
+        if let Err(err) = &parsed {
+            // it would fail the same way every time the episode is played
+            warn!("Could not parse transcript of \"{file}\": {err:#}");
+        }
+
+        for track in [
+            self.current_song.as_mut(),
+            self.playlist.current_track_as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|track| track.file() == Some(file))
+        {
+            match &parsed {
+                Ok(parsed) => track.set_transcript(parsed.clone()),
+                Err(_) => track.clear_transcript_url(),
+            }
+        }
+        if parsed.is_err() {
+            return Ok(());
+        }
+
+        // streamed episodes fetch it again the next time they are played
+        let episode_path = self
+            .podcast
+            .podcasts
+            .iter()
+            .flat_map(|pod| &pod.episodes)
+            .find(|ep| ep.url == file)
+            .and_then(|ep| ep.path.as_deref())
+            .filter(|path| path.exists());
+        if let Some(path) = episode_path {
+            transcript::write_cache(path, text)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reload all podcasts from the database, like after the server changed them
+    pub fn podcast_reload(&mut self) -> Result<()> {
+        self.podcast.podcasts = self.podcast.db_podcast.get_podcasts()?;
+        self.podcast.podcasts_index = self
+            .podcast
+            .podcasts_index
+            .min(self.podcast.podcasts.len().saturating_sub(1));
+
+        self.podcast_sync_feeds_and_episodes();
+        Ok(())
+    }
+
     /// Deletes a downloaded file for an episode from the user's local
     /// system.
     pub fn episode_delete_file(&mut self, ep_index: usize) -> Result<()> {
@@ -798,6 +923,7 @@ impl Model {
         if ep.path.is_some() {
             let title = &ep.title;
             let path = ep.path.clone().unwrap();
+            transcript::remove_cached(&path);
             match std::fs::remove_file(path) {
                 Ok(()) => {
                     self.podcast.db_podcast.remove_file(ep.id).map_err(|e| {
@@ -836,8 +962,9 @@ impl Model {
                 .ok_or_else(|| anyhow!("failed to find the podcast selected for deletion."))?;
 
             for ep in &mut podcast_selected.episodes {
-                if ep.path.is_some() {
-                    match std::fs::remove_file(ep.path.clone().unwrap()) {
+                if let Some(path) = &ep.path {
+                    transcript::remove_cached(path);
+                    match std::fs::remove_file(path) {
                         Ok(()) => {
                             eps_to_remove.push(ep.id);
                             ep.path = None;
@@ -877,6 +1004,24 @@ impl Model {
         Ok(())
     }
 
+    /// Parse `input` as [`PodcastPlaybackSettings`] and store them for the selected feed
+    ///
+    /// They are applied by the server the next time a episode of the feed starts playing.
+    pub fn podcast_set_playback_settings(&mut self, input: &str) -> Result<()> {
+        let settings: PodcastPlaybackSettings = input.parse()?;
+        let Some(podcast_selected) = self.podcast.podcasts.get_mut(self.podcast.podcasts_index)
+        else {
+            return Ok(());
+        };
+
+        self.podcast
+            .db_podcast
+            .set_playback_settings(podcast_selected.id, &settings)?;
+        podcast_selected.playback = settings;
+
+        Ok(())
+    }
+
     pub fn podcast_remove_feed(&mut self) -> Result<()> {
         if self.podcast.podcasts.is_empty() {
             return Ok(());
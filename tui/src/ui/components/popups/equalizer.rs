@@ -0,0 +1,175 @@
+use termusiclib::config::v2::server::{EqualizerFilter, EqualizerSettings};
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::types::{EQMsg, Id, Msg};
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    command::{Cmd, CmdResult, Direction},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, PropPayload, PropValue, TableBuilder, TextSpan},
+    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State, StateValue,
+};
+
+use crate::ui::model::Model;
+
+#[derive(MockComponent)]
+pub struct EqualizerPopup {
+    component: Table,
+    config: SharedTuiSettings,
+}
+
+impl EqualizerPopup {
+    pub fn new(config: SharedTuiSettings, equalizer: &EqualizerSettings) -> Self {
+        let mut table = TableBuilder::default();
+        match equalizer.preset_bands() {
+            Some(bands) if !bands.is_empty() => {
+                for (idx, band) in bands.iter().enumerate() {
+                    if idx > 0 {
+                        table.add_row();
+                    }
+                    let kind = match band.kind {
+                        EqualizerFilter::Peaking => "Peaking",
+                        EqualizerFilter::LowShelf => "Low Shelf",
+                        EqualizerFilter::HighShelf => "High Shelf",
+                    };
+                    let frequency = if band.frequency >= 1000.0 {
+                        format!("{:.1} kHz", band.frequency / 1000.0)
+                    } else {
+                        format!("{:.0} Hz", band.frequency)
+                    };
+                    table
+                        .add_col(TextSpan::new(kind))
+                        .add_col(TextSpan::new(frequency))
+                        .add_col(TextSpan::new(format!("{:+.1} dB", band.gain)).bold())
+                        .add_col(TextSpan::new(format!("{:.2}", band.q)));
+                }
+            }
+            _ => {
+                table.add_col(TextSpan::new("Preset has no bands"));
+            }
+        }
+
+        let component = {
+            let config = config.read();
+            let state = if equalizer.enabled { "on" } else { "off" };
+            Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(config.settings.theme.fallback_border()),
+                )
+                .foreground(config.settings.theme.fallback_foreground())
+                .background(config.settings.theme.fallback_background())
+                .highlighted_color(config.settings.theme.fallback_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .scroll(true)
+                .title(
+                    format!(
+                        " Equalizer \"{}\" ({state}) | Enter: toggle, Tab: preset, Left/Right: gain ",
+                        equalizer.preset
+                    ),
+                    Alignment::Center,
+                )
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers(&["Type", "Frequency", "Gain", "Q"])
+                .column_spacing(3)
+                .widths(&[30, 25, 25, 20])
+                .table(table.build())
+        };
+
+        Self { component, config }
+    }
+
+    /// Get the index of the currently selected band
+    fn selected(&self) -> usize {
+        match self.state() {
+            State::One(StateValue::Usize(index)) => index,
+            _ => 0,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for EqualizerPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter,
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::Equalizer(EQMsg::ToggleEnabled)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab,
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::Equalizer(EQMsg::NextPreset)),
+
+            Event::Keyboard(key) if key == keys.quit.get() => {
+                return Some(Msg::Equalizer(EQMsg::PopupClose))
+            }
+            Event::Keyboard(key) if key == keys.escape.get() => {
+                return Some(Msg::Equalizer(EQMsg::PopupClose))
+            }
+
+            Event::Keyboard(key) if key == keys.navigation_keys.left.get() => {
+                return Some(Msg::Equalizer(EQMsg::GainDown(self.selected())))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.right.get() => {
+                return Some(Msg::Equalizer(EQMsg::GainUp(self.selected())))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left,
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::Equalizer(EQMsg::GainDown(self.selected()))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right,
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(Msg::Equalizer(EQMsg::GainUp(self.selected()))),
+
+            Event::Keyboard(key) if key == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+impl Model {
+    /// Mount the equalizer popup or refresh it if it is already mounted, keeping the selected band
+    pub fn mount_equalizer_popup(&mut self) {
+        let selected = match self.app.state(&Id::EqualizerPopup) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => 0,
+        };
+        let popup = EqualizerPopup::new(
+            self.config_tui.clone(),
+            &self.config_server.read().settings.player.equalizer,
+        );
+        assert!(self
+            .app
+            .remount(Id::EqualizerPopup, Box::new(popup), vec![])
+            .is_ok());
+        self.app
+            .attr(
+                &Id::EqualizerPopup,
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::One(PropValue::Usize(selected))),
+            )
+            .ok();
+        self.update_photo().ok();
+        assert!(self.app.active(&Id::EqualizerPopup).is_ok());
+    }
+}
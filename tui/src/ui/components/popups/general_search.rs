@@ -40,36 +40,26 @@ pub struct GSInputPopup {
 
 impl GSInputPopup {
     pub fn new(source: Source, config: &TuiOverlay) -> Self {
-        match source {
-            Source::Episode => Self {
-                component: Input::default()
-                    .background(config.settings.theme.fallback_background())
-                    .foreground(config.settings.theme.fallback_foreground())
-                    .borders(
-                        Borders::default()
-                            .color(config.settings.theme.fallback_border())
-                            .modifiers(BorderType::Rounded),
-                    )
-                    .input_type(InputType::Text)
-                    .title(
-                        " Search for all episodes from all feeds: (support * and ?) ",
-                        Alignment::Left,
-                    ),
-                source,
-            },
-            _ => Self {
-                component: Input::default()
-                    .background(config.settings.theme.fallback_background())
-                    .foreground(config.settings.theme.fallback_foreground())
-                    .borders(
-                        Borders::default()
-                            .color(config.settings.theme.fallback_border())
-                            .modifiers(BorderType::Rounded),
-                    )
-                    .input_type(InputType::Text)
-                    .title(" Search for: (support * and ?) ", Alignment::Left),
-                source,
-            },
+        let title = match source {
+            Source::Episode => " Search for all episodes from all feeds: (support * and ?) ",
+            Source::Database => {
+                " Search library: (words, \"phrase\", artist:, album:, title:, genre:, year:>2000, duration:<300, -exclude) "
+            }
+            _ => " Search for: (support * and ?) ",
+        };
+
+        Self {
+            component: Input::default()
+                .background(config.settings.theme.fallback_background())
+                .foreground(config.settings.theme.fallback_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.fallback_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(title, Alignment::Left),
+            source,
         }
     }
 }
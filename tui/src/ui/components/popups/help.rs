@@ -80,6 +80,12 @@ impl HelpPopup {
                         ]))
                         .add_col(Self::comment("Seek forward/backward 5 seconds"))
                         .add_row()
+                        .add_col(Self::key(&[
+                            &keys.player_keys.next_chapter,
+                            &keys.player_keys.previous_chapter,
+                        ]))
+                        .add_col(Self::comment("Seek to next/previous chapter"))
+                        .add_row()
                         .add_col(Self::key(&[
                             &keys.lyric_keys.adjust_offset_forwards,
                             &keys.lyric_keys.adjust_offset_backwards,
@@ -122,6 +128,12 @@ impl HelpPopup {
                         .add_col(Self::key(&[&keys.select_view_keys.open_config]))
                         .add_col(Self::comment("Open Config Editor(all configuration)"))
                         .add_row()
+                        .add_col(Self::key(&[&keys.select_view_keys.open_equalizer]))
+                        .add_col(Self::comment("Open Equalizer"))
+                        .add_row()
+                        .add_col(Self::key(&[&keys.select_view_keys.open_saved_playlists]))
+                        .add_col(Self::comment("Open Saved Playlists"))
+                        .add_row()
                         .add_col(Self::key(&[&keys.player_keys.save_playlist]))
                         .add_col(Self::comment("Save Playlist to m3u"))
                         .add_row()
@@ -228,6 +240,18 @@ impl HelpPopup {
                         ]))
                         .add_col(Self::comment("Select random tracks/albums to playlist"))
                         .add_row()
+                        .add_col(Self::key(&[
+                            &keys.playlist_keys.play_next,
+                            &keys.playlist_keys.add_up_next,
+                        ]))
+                        .add_col(Self::comment("Play selected next/add to up next"))
+                        .add_row()
+                        .add_col(Self::key(&[
+                            &keys.playlist_keys.undo,
+                            &keys.playlist_keys.redo,
+                        ]))
+                        .add_col(Self::comment("Undo/redo playlist change"))
+                        .add_row()
                         .add_col(TextSpan::new("Database").bold().fg(Color::LightYellow))
                         .add_row()
                         .add_col(Self::key(&[
@@ -236,6 +260,12 @@ impl HelpPopup {
                         ]))
                         .add_col(Self::comment("Add one/all track(s) to playlist"))
                         .add_row()
+                        .add_col(Self::key(&[&keys.database_keys.cycle_sort]))
+                        .add_col(Self::comment("Cycle sorting of tracks"))
+                        .add_row()
+                        .add_col(Self::key(&[&keys.navigation_keys.left]))
+                        .add_col(Self::comment("Result: go back up after drilling down"))
+                        .add_row()
                         // TODO: add search key to database
                         .add_col(Self::key(&[&keys.library_keys.search]))
                         .add_col(Self::comment("Search in database"))
@@ -257,6 +287,9 @@ impl HelpPopup {
                         ]))
                         .add_col(Self::comment("Feeds : refresh one/all feeds"))
                         .add_row()
+                        .add_col(Self::key(&[&keys.podcast_keys.playback_settings]))
+                        .add_col(Self::comment("Feeds : edit speed and intro/outro skip"))
+                        .add_row()
                         .add_col(Self::key(&[
                             &keys.podcast_keys.mark_played,
                             &keys.podcast_keys.mark_all_played,
@@ -266,6 +299,9 @@ impl HelpPopup {
                         .add_col(Self::key(&[&keys.podcast_keys.download_episode]))
                         .add_col(Self::comment("Episode: Download episode"))
                         .add_row()
+                        .add_col(Self::key(&[&keys.podcast_keys.cancel_download]))
+                        .add_col(Self::comment("Episode: Cancel running download"))
+                        .add_row()
                         .add_col(Self::key(&[&keys.podcast_keys.delete_local_episode]))
                         .add_col(Self::comment("Episode: delete episode local file"))
                         .add_row()
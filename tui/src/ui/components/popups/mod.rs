@@ -1,6 +1,7 @@
 #![allow(clippy::module_name_repetitions)]
 
 mod deleteconfirm;
+mod equalizer;
 mod error;
 pub mod general_search;
 mod help;
@@ -8,12 +9,15 @@ mod message;
 mod mock_yn_confirm;
 mod podcast;
 mod quit;
+mod saved_playlists;
 mod saveplaylist;
 pub mod youtube_search;
 
 #[allow(unused_imports)]
 pub use deleteconfirm::{DeleteConfirmInputPopup, DeleteConfirmRadioPopup};
 #[allow(unused_imports)]
+pub use equalizer::EqualizerPopup;
+#[allow(unused_imports)]
 pub use error::ErrorPopup;
 #[allow(unused_imports)]
 pub use help::HelpPopup;
@@ -23,9 +27,11 @@ pub use mock_yn_confirm::{YNConfirm, YNConfirmStyle};
 #[allow(unused_imports)]
 pub use podcast::{
     FeedDeleteConfirmInputPopup, FeedDeleteConfirmRadioPopup, PodcastAddPopup,
-    PodcastSearchTablePopup,
+    PodcastPlaybackSettingsPopup, PodcastSearchTablePopup,
 };
 #[allow(unused_imports)]
 pub use quit::QuitPopup;
 #[allow(unused_imports)]
+pub use saved_playlists::{SavedPlaylistNamePopup, SavedPlaylistsPopup};
+#[allow(unused_imports)]
 pub use saveplaylist::{SavePlaylistConfirmPopup, SavePlaylistPopup};
@@ -1,4 +1,5 @@
 use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::podcast::PodcastPlaybackSettings;
 use termusiclib::types::{Id, Msg, PCMsg};
 use tui_realm_stdlib::{Input, Table};
 use tuirealm::{
@@ -197,6 +198,78 @@ impl Component<Msg, NoUserEvent> for FeedDeleteConfirmInputPopup {
     }
 }
 
+#[derive(MockComponent)]
+pub struct PodcastPlaybackSettingsPopup {
+    component: Input,
+}
+
+impl PodcastPlaybackSettingsPopup {
+    pub fn new(config: &TuiOverlay, title: &str, settings: &PodcastPlaybackSettings) -> Self {
+        let config = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(config.theme.library_foreground())
+                .background(config.theme.library_background())
+                .borders(
+                    Borders::default()
+                        .color(config.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(
+                    format!(" Playback of \"{title}\": (Enter to confirm) "),
+                    Alignment::Left,
+                )
+                .value(settings.to_string()),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for PodcastPlaybackSettingsPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Podcast(PCMsg::PlaybackSettingsPopupCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    return Some(Msg::Podcast(PCMsg::PlaybackSettingsPopupCloseOk(
+                        input_string,
+                    )));
+                }
+                _ => return Some(Msg::None),
+            },
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
+
 #[derive(MockComponent)]
 pub struct PodcastSearchTablePopup {
     component: Table,
@@ -412,4 +485,29 @@ impl Model {
             assert!(self.app.umount(&Id::PodcastAddPopup).is_ok());
         }
     }
+
+    /// Mount the popup to edit the playback settings of the selected feed, if there is one
+    pub fn mount_podcast_playback_settings_popup(&mut self) {
+        let Some(podcast) = self.podcast.podcasts.get(self.podcast.podcasts_index) else {
+            return;
+        };
+        let popup = PodcastPlaybackSettingsPopup::new(
+            &self.config_tui.read(),
+            &podcast.title,
+            &podcast.playback,
+        );
+
+        assert!(self
+            .app
+            .remount(Id::PodcastPlaybackSettingsPopup, Box::new(popup), vec![])
+            .is_ok());
+
+        assert!(self.app.active(&Id::PodcastPlaybackSettingsPopup).is_ok());
+    }
+
+    pub fn umount_podcast_playback_settings_popup(&mut self) {
+        if self.app.mounted(&Id::PodcastPlaybackSettingsPopup) {
+            assert!(self.app.umount(&Id::PodcastPlaybackSettingsPopup).is_ok());
+        }
+    }
 }
@@ -0,0 +1,291 @@
+use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::types::{Id, Msg, SPMsg};
+use termusicplayback::SavedPlaylistInfo;
+use tui_realm_stdlib::{Input, Table};
+use tuirealm::{
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{
+        Alignment, BorderType, Borders, InputType, PropPayload, PropValue, TableBuilder, TextSpan,
+    },
+    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State, StateValue,
+};
+
+use crate::ui::model::Model;
+
+#[derive(MockComponent)]
+pub struct SavedPlaylistsPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// Whether there is at least one playlist to select
+    has_playlists: bool,
+}
+
+impl SavedPlaylistsPopup {
+    pub fn new(config: SharedTuiSettings, playlists: &[SavedPlaylistInfo]) -> Self {
+        let mut table = TableBuilder::default();
+        if playlists.is_empty() {
+            let save_key = config
+                .read()
+                .settings
+                .keys
+                .saved_playlist_keys
+                .save_queue
+                .clone();
+            table.add_col(TextSpan::new(format!(
+                "No saved playlists, press \"{save_key}\" to save the queue"
+            )));
+        }
+        for (idx, playlist) in playlists.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+            table
+                .add_col(TextSpan::new(playlist.name.as_str()).bold())
+                .add_col(TextSpan::new(playlist.track_count.to_string()));
+        }
+
+        let component = {
+            let config = config.read();
+            let keys = &config.settings.keys.saved_playlist_keys;
+            let title = format!(
+                " Saved Playlists | Enter: load, {}: append, {}: save queue, {}: add queue, {}: rename, {}: delete ",
+                keys.append, keys.save_queue, keys.add_queue, keys.rename, keys.delete
+            );
+            Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(config.settings.theme.fallback_border()),
+                )
+                .foreground(config.settings.theme.fallback_foreground())
+                .background(config.settings.theme.fallback_background())
+                .highlighted_color(config.settings.theme.fallback_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .scroll(true)
+                .title(title, Alignment::Center)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers(&["Name", "Tracks"])
+                .column_spacing(3)
+                .widths(&[80, 20])
+                .table(table.build())
+        };
+
+        Self {
+            component,
+            config,
+            has_playlists: !playlists.is_empty(),
+        }
+    }
+
+    /// Get the index of the currently selected playlist
+    fn selected(&self) -> Option<usize> {
+        if !self.has_playlists {
+            return None;
+        }
+        match self.state() {
+            State::One(StateValue::Usize(index)) => Some(index),
+            _ => Some(0),
+        }
+    }
+
+    /// Create a message for the selected playlist, if there is one
+    fn selected_msg(&self, msg: fn(usize) -> SPMsg) -> Option<Msg> {
+        Some(
+            self.selected()
+                .map_or(Msg::None, |index| Msg::SavedPlaylists(msg(index))),
+        )
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SavedPlaylistsPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter,
+                modifiers: KeyModifiers::NONE,
+            }) => return self.selected_msg(SPMsg::Load),
+            Event::Keyboard(key) if key == keys.saved_playlist_keys.append.get() => {
+                return self.selected_msg(SPMsg::Append)
+            }
+            Event::Keyboard(key) if key == keys.saved_playlist_keys.add_queue.get() => {
+                return self.selected_msg(SPMsg::AddQueue)
+            }
+            Event::Keyboard(key) if key == keys.saved_playlist_keys.rename.get() => {
+                return self.selected_msg(SPMsg::RenameShow)
+            }
+            Event::Keyboard(key) if key == keys.saved_playlist_keys.delete.get() => {
+                return self.selected_msg(SPMsg::Delete)
+            }
+            Event::Keyboard(key) if key == keys.saved_playlist_keys.save_queue.get() => {
+                return Some(Msg::SavedPlaylists(SPMsg::CreateShow))
+            }
+
+            Event::Keyboard(key) if key == keys.quit.get() => {
+                return Some(Msg::SavedPlaylists(SPMsg::PopupClose))
+            }
+            Event::Keyboard(key) if key == keys.escape.get() => {
+                return Some(Msg::SavedPlaylists(SPMsg::PopupClose))
+            }
+
+            Event::Keyboard(key) if key == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            Event::Keyboard(key) if key == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+/// Input for the name of a saved playlist, either to save the queue as or to rename a playlist to
+#[derive(MockComponent)]
+pub struct SavedPlaylistNamePopup {
+    component: Input,
+}
+
+impl SavedPlaylistNamePopup {
+    pub fn new(config: &TuiOverlay, rename: Option<&str>) -> Self {
+        let settings = &config.settings;
+        let title = match rename {
+            Some(name) => format!(" Rename \"{name}\" to: (Enter to confirm) "),
+            None => " Save queue as: (Enter to confirm) ".to_string(),
+        };
+        let mut component = Input::default()
+            .foreground(settings.theme.fallback_foreground())
+            .background(settings.theme.fallback_background())
+            .borders(
+                Borders::default()
+                    .color(settings.theme.fallback_border())
+                    .modifiers(BorderType::Rounded),
+            )
+            .input_type(InputType::Text)
+            .title(title, Alignment::Left);
+        if let Some(name) = rename {
+            component = component.value(name);
+        }
+
+        Self { component }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SavedPlaylistNamePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let _cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::SavedPlaylists(SPMsg::NameInputCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(name)) => {
+                    return Some(Msg::SavedPlaylists(SPMsg::NameInputOk(name)))
+                }
+                _ => return Some(Msg::None),
+            },
+            _ => CmdResult::None,
+        };
+
+        Some(Msg::None)
+    }
+}
+
+impl Model {
+    /// Mount the saved playlists popup or refresh it if it is already mounted, keeping the selected playlist
+    pub fn mount_saved_playlists_popup(&mut self) {
+        let selected = match self.app.state(&Id::SavedPlaylistsPopup) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => 0,
+        };
+        let selected = selected.min(self.saved_playlists.len().saturating_sub(1));
+        let popup = SavedPlaylistsPopup::new(self.config_tui.clone(), &self.saved_playlists);
+        assert!(self
+            .app
+            .remount(Id::SavedPlaylistsPopup, Box::new(popup), vec![])
+            .is_ok());
+        self.app
+            .attr(
+                &Id::SavedPlaylistsPopup,
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::One(PropValue::Usize(selected))),
+            )
+            .ok();
+        self.update_photo().ok();
+        // the name popup is drawn over this one and should keep the focus
+        if !self.app.mounted(&Id::SavedPlaylistNamePopup) {
+            assert!(self.app.active(&Id::SavedPlaylistsPopup).is_ok());
+        }
+    }
+
+    pub fn umount_saved_playlists_popup(&mut self) {
+        if self.app.mounted(&Id::SavedPlaylistsPopup) {
+            self.app.umount(&Id::SavedPlaylistsPopup).ok();
+        }
+        self.update_photo().ok();
+    }
+
+    /// Mount the name input, to rename the given playlist or to save the queue as a new playlist
+    pub fn mount_saved_playlist_name_popup(&mut self, rename: Option<String>) {
+        let popup = SavedPlaylistNamePopup::new(&self.config_tui.read(), rename.as_deref());
+        assert!(self
+            .app
+            .remount(Id::SavedPlaylistNamePopup, Box::new(popup), vec![])
+            .is_ok());
+        assert!(self.app.active(&Id::SavedPlaylistNamePopup).is_ok());
+        self.saved_playlist_rename = rename;
+    }
+
+    pub fn umount_saved_playlist_name_popup(&mut self) {
+        if self.app.mounted(&Id::SavedPlaylistNamePopup) {
+            self.app.umount(&Id::SavedPlaylistNamePopup).ok();
+        }
+        self.saved_playlist_rename = None;
+        if self.app.mounted(&Id::SavedPlaylistsPopup) {
+            assert!(self.app.active(&Id::SavedPlaylistsPopup).is_ok());
+        }
+    }
+}
@@ -289,6 +289,24 @@ impl Model {
             {
                 song.set_genre(&genre);
             }
+            if let Ok(State::One(StateValue::String(album_artist))) = self
+                .app
+                .state(&Id::TagEditor(IdTagEditor::InputAlbumArtist))
+            {
+                song.set_album_artist(&album_artist);
+            }
+            if let Ok(State::One(StateValue::String(composer))) =
+                self.app.state(&Id::TagEditor(IdTagEditor::InputComposer))
+            {
+                song.set_composer(&composer);
+            }
+            song.set_year(self.te_number_input(IdTagEditor::InputYear, "Year")?);
+            song.set_track_number(
+                self.te_number_input(IdTagEditor::InputTrackNumber, "Track number")?,
+            );
+            song.set_disc_number(
+                self.te_number_input(IdTagEditor::InputDiscNumber, "Disc number")?,
+            );
             song.save_tag()?;
             self.init_by_song(&song);
             self.playlist_update_library_delete();
@@ -296,6 +314,22 @@ impl Model {
         Ok(())
     }
 
+    /// Get the value of a number input, `None` if it is empty
+    fn te_number_input(&self, id: IdTagEditor, name: &str) -> Result<Option<u32>> {
+        let Ok(State::One(StateValue::String(value))) = self.app.state(&Id::TagEditor(id)) else {
+            return Ok(None);
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        value
+            .parse()
+            .map(Some)
+            .with_context(|| format!("{name} must be a number, got \"{value}\""))
+    }
+
     pub fn te_load_lyric_and_photo(&mut self, index: usize) -> Result<()> {
         if self.songtag_options.is_empty() {
             return Ok(());
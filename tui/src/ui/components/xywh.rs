@@ -84,6 +84,12 @@ impl Model {
         if self.app.mounted(&Id::HelpPopup) {
             return true;
         }
+        if self.app.mounted(&Id::EqualizerPopup) {
+            return true;
+        }
+        if self.app.mounted(&Id::SavedPlaylistsPopup) {
+            return true;
+        }
         if self.app.mounted(&Id::PodcastSearchTablePopup) {
             return true;
         }
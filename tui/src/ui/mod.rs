@@ -23,7 +23,7 @@
  */
 pub mod components;
 pub mod model;
-mod playback;
+pub mod playback;
 pub mod utils;
 
 use anyhow::Result;
@@ -32,10 +32,10 @@ use playback::Playback;
 use std::time::Duration;
 use sysinfo::System;
 pub use termusiclib::types::*;
-use termusicplayback::player::music_player_client::MusicPlayerClient;
-use termusicplayback::{PlayerCmd, PlayerProgress, Status};
-use tokio::sync::mpsc::{self, UnboundedReceiver};
-use tonic::transport::Channel;
+use termusicplayback::player::StreamUpdates;
+use termusicplayback::{PlayerCmd, PlayerProgress, SavedPlaylistCmd, Status, UpdateEvents};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tonic::Streaming;
 use tuirealm::application::PollStrategy;
 use tuirealm::{Application, Update};
 
@@ -43,6 +43,10 @@ use crate::CombinedSettings;
 // -- internal
 
 const FORCED_REDRAW_INTERVAL: Duration = Duration::from_millis(1000);
+/// How long to wait before the first try to subscribe again after the server update stream ended
+const RESUBSCRIBE_DELAY_MIN: Duration = Duration::from_secs(1);
+/// The longest wait between tries to subscribe again, the delay doubles with every failed try
+const RESUBSCRIBE_DELAY_MAX: Duration = Duration::from_secs(30);
 
 // Let's define the messages handled by our app. NOTE: it must derive `PartialEq`
 
@@ -51,6 +55,8 @@ pub struct UI {
     model: Model,
     playback: Playback,
     cmd_rx: UnboundedReceiver<PlayerCmd>,
+    /// Events pushed by the server, forwarded from the subscription stream
+    stream_rx: UnboundedReceiver<UpdateEvents>,
 }
 
 impl UI {
@@ -63,18 +69,93 @@ impl UI {
         // }
     }
     /// Instantiates a new Ui
-    pub async fn new(config: CombinedSettings, client: MusicPlayerClient<Channel>) -> Result<Self> {
+    pub async fn new(config: CombinedSettings, client: playback::Client) -> Result<Self> {
         let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
-        let mut model = Model::new(config, cmd_tx).await;
+        let mut model = Model::new(config, cmd_tx.clone()).await;
         model.init_config();
-        let playback = Playback::new(client);
+        let stream_playback = Playback::new(client.clone());
+        let mut playback = Playback::new(client);
+
+        let (stream_tx, stream_rx) = mpsc::unbounded_channel();
+        let stream = playback.subscribe_to_stream_updates().await?;
+        Self::spawn_stream_updates_task(stream, stream_playback, stream_tx, cmd_tx);
+
         Ok(Self {
             model,
             playback,
             cmd_rx,
+            stream_rx,
         })
     }
 
+    /// Spawn a task that forwards all [`UpdateEvents`] received from the server to `tx`
+    ///
+    /// If the stream ends, like when the server restarted, it subscribes again with a increasing delay
+    /// and requests the current state with `cmd_tx`, as updates may have been missed in the meantime.
+    fn spawn_stream_updates_task(
+        mut stream: Streaming<StreamUpdates>,
+        mut playback: Playback,
+        tx: UnboundedSender<UpdateEvents>,
+        cmd_tx: UnboundedSender<PlayerCmd>,
+    ) {
+        tokio::spawn(async move {
+            loop {
+                if !Self::forward_stream_updates(&mut stream, &tx).await {
+                    // the ui has exited
+                    return;
+                }
+
+                let mut delay = RESUBSCRIBE_DELAY_MIN;
+                stream = loop {
+                    tokio::time::sleep(delay).await;
+                    if tx.is_closed() {
+                        return;
+                    }
+                    match playback.subscribe_to_stream_updates().await {
+                        Ok(v) => break v,
+                        Err(err) => {
+                            warn!("Error subscribing to server updates again: {err:#}");
+                            delay = (delay * 2).min(RESUBSCRIBE_DELAY_MAX);
+                        }
+                    }
+                };
+
+                if cmd_tx.send(PlayerCmd::GetProgress).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Forward all updates of `stream` to `tx` until the stream ends
+    ///
+    /// Returns `false` if `tx` is closed, `true` if the stream ended.
+    async fn forward_stream_updates(
+        stream: &mut Streaming<StreamUpdates>,
+        tx: &UnboundedSender<UpdateEvents>,
+    ) -> bool {
+        loop {
+            match stream.message().await {
+                Ok(Some(ev)) => match UpdateEvents::try_from(ev) {
+                    Ok(ev) => {
+                        if tx.send(ev).is_err() {
+                            return false;
+                        }
+                    }
+                    Err(err) => warn!("Error converting server update: {err:#}"),
+                },
+                Ok(None) => {
+                    info!("Server closed the update stream");
+                    return true;
+                }
+                Err(err) => {
+                    error!("Error receiving server update: {err:#}");
+                    return true;
+                }
+            }
+        }
+    }
+
     /// ### run
     ///
     /// Main loop for Ui thread
@@ -93,18 +174,23 @@ impl UI {
     ///
     /// This function does NOT handle initializing and finializing the terminal
     async fn run_inner(&mut self) -> Result<()> {
+        // get the initial state, everything after is pushed by the server
+        self.model.command(&PlayerCmd::GetProgress);
         // Main loop
         let mut progress_interval = 0;
         while !self.model.quit {
             self.model.te_update_lyric_options();
             // self.model.update_player_msg();
             self.model.update_outside_msg();
-            if self.model.layout != TermusicLayout::Podcast {
+            if self.model.layout == TermusicLayout::Podcast {
+                self.model.lyric_update_for_transcript();
+            } else {
                 self.model.lyric_update();
             }
             if progress_interval == 0 {
                 self.model.run();
             }
+            self.handle_stream_events();
             self.run_playback().await?;
             progress_interval += 1;
             if progress_interval >= 80 {
@@ -184,6 +270,7 @@ impl UI {
         self.model.player_update_current_track_after();
 
         self.model.lyric_update_for_podcast_by_current_track();
+        self.model.podcast_fetch_transcript();
 
         if let Err(e) = self.model.podcast_mark_current_track_played() {
             self.model
@@ -223,6 +310,81 @@ impl UI {
         }
     }
 
+    /// Handle all [`UpdateEvents`] the server pushed since the last call
+    fn handle_stream_events(&mut self) {
+        while let Ok(ev) = self.stream_rx.try_recv() {
+            match ev {
+                UpdateEvents::MissedEvents { amount } => {
+                    warn!("Missed {amount} server updates, re-syncing");
+                    self.model.command(&PlayerCmd::GetProgress);
+                }
+                UpdateEvents::TrackChanged(info) => {
+                    if let Some(progress) = info.progress {
+                        self.model.progress_update(
+                            progress.position,
+                            progress.total_duration.unwrap_or_default(),
+                        );
+                    }
+                    // the index is a playlist index, which always fits into a usize
+                    #[allow(clippy::cast_possible_truncation)]
+                    self.handle_current_track_index(info.current_track_index as usize);
+                }
+                UpdateEvents::StatusChanged { status } => {
+                    self.handle_status(status);
+                    self.model.progress_update_title();
+                }
+                UpdateEvents::VolumeChanged { volume } => {
+                    self.model.config_server.write().settings.player.volume = volume;
+                    self.model.progress_update_title();
+                }
+                UpdateEvents::SpeedChanged { speed } => {
+                    self.model.config_server.write().settings.player.speed = speed;
+                    self.model.progress_update_title();
+                }
+                UpdateEvents::GaplessChanged { gapless } => {
+                    self.model.config_server.write().settings.player.gapless = gapless;
+                    self.model.progress_update_title();
+                }
+                UpdateEvents::PlaylistChanged => {
+                    if let Err(err) = self.model.playlist.reload_tracks() {
+                        self.model
+                            .mount_error_popup(err.context("reloading changed playlist"));
+                    }
+                    self.model.playlist_sync();
+                }
+                UpdateEvents::RadioTitleChanged { title } => {
+                    self.model.lyric_update_for_radio(title);
+                }
+                UpdateEvents::ChapterChanged { title } => {
+                    self.model.lyric_update_for_chapter(title);
+                }
+                UpdateEvents::Progress(progress) => {
+                    self.model.progress_update(
+                        progress.position,
+                        progress.total_duration.unwrap_or_default(),
+                    );
+                }
+                UpdateEvents::LibraryChanged => {
+                    self.model.library_reload_tree();
+                    self.model
+                        .show_message_timeout_label_help("Library updated", None, None, None);
+                }
+                UpdateEvents::PodcastsChanged => {
+                    if let Err(err) = self.model.podcast_reload() {
+                        self.model
+                            .mount_error_popup(err.context("reloading changed podcasts"));
+                    }
+                }
+                UpdateEvents::SavedPlaylistsChanged => {
+                    if self.model.app.mounted(&Id::SavedPlaylistsPopup) {
+                        self.model
+                            .command(&PlayerCmd::SavedPlaylist(SavedPlaylistCmd::List));
+                    }
+                }
+            }
+        }
+    }
+
     async fn run_playback(&mut self) -> Result<()> {
         if let Ok(cmd) = self.cmd_rx.try_recv() {
             match cmd {
@@ -247,6 +409,9 @@ impl UI {
                     }
 
                     self.model.lyric_update_for_radio(response.radio_title);
+                    if self.model.chapter_title != response.chapter_title {
+                        self.model.lyric_update_for_chapter(response.chapter_title);
+                    }
 
                     self.handle_status(Status::from_u32(response.status));
                 }
@@ -261,6 +426,10 @@ impl UI {
                 PlayerCmd::SkipPrevious => self.playback.skip_previous().await?,
                 PlayerCmd::ReloadConfig => self.playback.reload_config().await?,
                 PlayerCmd::ReloadPlaylist => self.playback.reload_playlist().await?,
+                PlayerCmd::PlaylistUndo => self.playback.playlist_undo().await?,
+                PlayerCmd::PlaylistRedo => self.playback.playlist_redo().await?,
+                PlayerCmd::ChapterNext => self.playback.chapter_next().await?,
+                PlayerCmd::ChapterPrevious => self.playback.chapter_previous().await?,
                 PlayerCmd::SeekBackward => {
                     let pprogress = self.playback.seek_backward().await?;
                     self.model.progress_update(
@@ -302,9 +471,70 @@ impl UI {
                     self.model.config_server.write().settings.player.volume = volume;
                     self.model.progress_update_title();
                 }
+                PlayerCmd::SetEqualizer(mut equalizer) => {
+                    let response = self
+                        .playback
+                        .set_equalizer(
+                            equalizer.enabled,
+                            equalizer.preset.clone(),
+                            equalizer.preset_bands().unwrap_or_default(),
+                        )
+                        .await?;
+                    // keep the local copy in sync with what the server applied
+                    equalizer.enabled = response.enabled;
+                    equalizer.presets.insert(
+                        response.preset.clone(),
+                        response.bands.into_iter().map(Into::into).collect(),
+                    );
+                    equalizer.preset = response.preset;
+                    self.model.config_server.write().settings.player.equalizer = equalizer;
+                    if self.model.app.mounted(&Id::EqualizerPopup) {
+                        self.model.mount_equalizer_popup();
+                    }
+                }
+                PlayerCmd::SavedPlaylist(cmd) => {
+                    if let Err(err) = self.run_saved_playlist(cmd).await {
+                        self.model.mount_error_popup(err);
+                    }
+                }
                 _ => {}
             }
         }
         Ok(())
     }
+
+    /// Execute a saved playlist operation on the server
+    async fn run_saved_playlist(&mut self, cmd: SavedPlaylistCmd) -> Result<()> {
+        match cmd {
+            SavedPlaylistCmd::List => {
+                self.model.saved_playlists = self.playback.get_saved_playlists().await?;
+                if self.model.app.mounted(&Id::SavedPlaylistsPopup) {
+                    self.model.mount_saved_playlists_popup();
+                }
+            }
+            SavedPlaylistCmd::Create { name, tracks } => {
+                self.playback.saved_playlist_create(name, tracks).await?;
+            }
+            SavedPlaylistCmd::Rename { from, to } => {
+                self.playback.saved_playlist_rename(from, to).await?;
+            }
+            SavedPlaylistCmd::Delete(name) => self.playback.saved_playlist_delete(name).await?,
+            SavedPlaylistCmd::Append { name, tracks } => {
+                self.playback.saved_playlist_append(name, tracks).await?;
+            }
+            SavedPlaylistCmd::Load { name, replace } => {
+                self.playback
+                    .saved_playlist_load(name.clone(), replace)
+                    .await?;
+                self.model.show_message_timeout_label_help(
+                    format!("Loaded playlist \"{name}\""),
+                    None,
+                    None,
+                    None,
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
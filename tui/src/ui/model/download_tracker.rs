@@ -1,16 +1,27 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use termusiclib::podcast::{DownloadCancel, DownloadProgress};
 
 pub struct DownloadTracker {
     items: HashSet<String>,
+    /// Episode downloads by url, from requesting them until they are finished
+    downloads: HashMap<String, Download>,
     // pub time_stamp_for_cache: Instant,
 }
 
+/// A requested episode download
+struct Download {
+    cancel: DownloadCancel,
+    /// Latest reported progress, `None` until the download actually started
+    progress: Option<DownloadProgress>,
+}
+
 impl Default for DownloadTracker {
     fn default() -> Self {
         let items = HashSet::new();
         // let time_stamp_for_cache = Instant::now();
         Self {
             items,
+            downloads: HashMap::new(),
             // time_stamp_for_cache,
         }
     }
@@ -24,10 +35,74 @@ impl DownloadTracker {
 
     pub fn decrease_one(&mut self, url: &str) {
         self.items.remove(url);
+        self.downloads.remove(url);
     }
 
     pub fn contains(&self, url: &str) -> bool {
-        self.items.contains(url)
+        self.items.contains(url) || self.downloads.contains_key(url)
+    }
+
+    /// Register a new episode download, returning the handle to pass to the download
+    pub fn add_download(&mut self, url: &str) -> DownloadCancel {
+        let cancel = DownloadCancel::default();
+        self.downloads.insert(
+            url.to_string(),
+            Download {
+                cancel: cancel.clone(),
+                progress: None,
+            },
+        );
+        cancel
+    }
+
+    /// Cancel the download of `url`, returns `false` if it is not being downloaded
+    ///
+    /// The download is removed once it reports that it has been cancelled.
+    pub fn cancel_download(&self, url: &str) -> bool {
+        match self.downloads.get(url) {
+            Some(download) => {
+                download.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Update the progress of the download of `url`
+    ///
+    /// Returns `true` if the displayed percentage changed.
+    pub fn update_progress(&mut self, url: &str, progress: DownloadProgress) -> bool {
+        let Some(download) = self.downloads.get_mut(url) else {
+            return false;
+        };
+        let old_percent = download.progress.and_then(|v| v.percent());
+        download.progress = Some(progress);
+        old_percent != progress.percent()
+    }
+
+    /// Get how much of `url` is downloaded in percent, if it is being downloaded and the size is known
+    pub fn percent(&self, url: &str) -> Option<u64> {
+        self.downloads
+            .get(url)
+            .and_then(|download| download.progress)
+            .and_then(|progress| progress.percent())
+    }
+
+    /// Get the combined percentage of all running downloads of which the size is known
+    fn total_percent(&self) -> Option<u64> {
+        let (downloaded, total) = self
+            .downloads
+            .values()
+            .filter_map(|download| download.progress)
+            .filter_map(|progress| Some((progress.downloaded, progress.total?)))
+            .fold((0, 0), |acc, (downloaded, total)| {
+                (acc.0 + downloaded, acc.1 + total)
+            });
+        DownloadProgress {
+            downloaded,
+            total: Some(total),
+        }
+        .percent()
     }
 
     pub fn visible(&self) -> bool {
@@ -92,6 +167,28 @@ impl DownloadTracker {
         }
     }
 
+    pub fn message_download_progress(&self) -> String {
+        let len = self.downloads.len();
+        let percent = self
+            .total_percent()
+            .map(|v| format!(" {v}%"))
+            .unwrap_or_default();
+        if len > 1 {
+            format!(" {len} items downloading...{percent} ")
+        } else {
+            format!(" 1 item downloading...{percent} ")
+        }
+    }
+
+    pub fn message_download_cancelled(&self, title: &str) -> String {
+        let len = self.items.len();
+        if len > 0 {
+            format!(" Download of {title:^.10} cancelled. {len} downloads are still running. ")
+        } else {
+            format!(" Download of {title:^.20} cancelled. ")
+        }
+    }
+
     pub fn message_download_complete(&self) -> String {
         let len = self.items.len();
         if len > 0 {
@@ -135,6 +232,18 @@ impl DownloadTracker {
         }
     }
 
+    pub fn message_download_already_running(&self, title: &str) -> String {
+        let len = self.items.len();
+
+        if len > 0 {
+            format!(
+                " Item {title:^.10} is already being downloaded by the server. {len} downloads are still running. "
+            )
+        } else {
+            format!(" Item {title:^.20} is already being downloaded by the server.")
+        }
+    }
+
     pub fn message_download_error_embed_data(&self, title: &str) -> String {
         let len = self.items.len();
 
@@ -29,6 +29,7 @@ mod youtube_options;
 use crate::ui::Application;
 use crate::CombinedSettings;
 use download_tracker::DownloadTracker;
+use termusiclib::config::v2::server::ComProtocol;
 use termusiclib::config::v2::tui::keys::Keys;
 use termusiclib::config::v2::tui::theme::ThemeWrap;
 use termusiclib::library_db::{DataBase, SearchCriteria};
@@ -48,8 +49,8 @@ use termusiclib::library_db::TrackDB;
 use termusiclib::podcast::{db::Database as DBPod, Podcast, PodcastFeed};
 use termusiclib::songtag::SongTag;
 use termusiclib::taskpool::TaskPool;
-use termusiclib::utils::get_app_config_path;
-use termusicplayback::{PlayerCmd, Playlist};
+use termusiclib::utils::{get_app_config_path, get_pin_yin};
+use termusicplayback::{PlayerCmd, Playlist, SavedPlaylistInfo};
 use tokio::sync::mpsc::UnboundedSender;
 use tui_realm_treeview::Tree;
 use tuirealm::event::NoUserEvent;
@@ -90,6 +91,10 @@ pub struct DatabaseWidgetData {
     pub search_results: Vec<String>,
     /// Results of the critea results search `(criteria -> search_results -> this)`
     pub search_tracks: Vec<TrackDB>,
+    /// Values selected in the previous levels when drilling down, like `[(Genre, "Jazz"), (Artist, "Miles Davis")]`
+    pub filters: Vec<(SearchCriteria, String)>,
+    /// How `search_tracks` are sorted
+    pub sort: DatabaseSort,
 }
 
 impl DatabaseWidgetData {
@@ -100,6 +105,81 @@ impl DatabaseWidgetData {
         // Reset instead of ".clear" as "clear" does not remove capacity and might not be used again and could potentially be large
         self.search_results = Vec::new();
         self.search_tracks = Vec::new();
+        self.filters = Vec::new();
+    }
+}
+
+/// How the Tracks in the Database Widget are sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatabaseSort {
+    /// By file name
+    Name,
+    Title,
+    /// By artist, then album in album order
+    Artist,
+    /// By album, then disc and track number
+    #[default]
+    Album,
+    /// Shortest first
+    Duration,
+    /// Most recently added first
+    Added,
+}
+
+impl DatabaseSort {
+    /// Get the next sort mode, wrapping around
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Title,
+            Self::Title => Self::Artist,
+            Self::Artist => Self::Album,
+            Self::Album => Self::Duration,
+            Self::Duration => Self::Added,
+            Self::Added => Self::Name,
+        }
+    }
+
+    /// Sort the given tracks by the current mode, equal values are sorted by file name
+    pub fn sort(self, tracks: &mut [TrackDB]) {
+        match self {
+            Self::Name => tracks.sort_by_cached_key(|k| get_pin_yin(&k.name)),
+            Self::Title => {
+                tracks.sort_by_cached_key(|k| (get_pin_yin(&k.title), get_pin_yin(&k.name)))
+            }
+            Self::Artist => tracks.sort_by_cached_key(|k| {
+                (
+                    get_pin_yin(&k.artist),
+                    get_pin_yin(&k.album),
+                    k.album_order_key(),
+                    get_pin_yin(&k.name),
+                )
+            }),
+            Self::Album => tracks.sort_by_cached_key(|k| {
+                (
+                    get_pin_yin(&k.album),
+                    k.album_order_key(),
+                    get_pin_yin(&k.name),
+                )
+            }),
+            Self::Duration => tracks.sort_by_cached_key(|k| (k.duration, get_pin_yin(&k.name))),
+            Self::Added => {
+                tracks.sort_by_cached_key(|k| (std::cmp::Reverse(k.added_at), get_pin_yin(&k.name)))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for DatabaseSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::Title => write!(f, "title"),
+            Self::Artist => write!(f, "artist"),
+            Self::Album => write!(f, "album"),
+            Self::Duration => write!(f, "duration"),
+            Self::Added => write!(f, "added"),
+        }
     }
 }
 
@@ -160,7 +240,13 @@ pub struct Model {
     pub tageditor_song: Option<Track>,
     pub time_pos: Duration,
     pub lyric_line: String,
+    /// Title of the chapter currently playing, empty if there is none
+    pub chapter_title: String,
     pub playlist: Playlist,
+    /// The saved playlists of the server, as of the last request
+    pub saved_playlists: Vec<SavedPlaylistInfo>,
+    /// The saved playlist to rename with the name popup, `None` to save the queue as a new playlist
+    pub saved_playlist_rename: Option<String>,
 
     #[cfg(all(feature = "cover-ueberzug", not(target_os = "windows")))]
     pub ueberzug_instance: UeInstance,
@@ -265,6 +351,7 @@ impl Model {
             tageditor_song: None,
             time_pos: Duration::default(),
             lyric_line: String::new(),
+            chapter_title: String::new(),
 
             library: MusicLibraryData {
                 tree_path: path,
@@ -288,6 +375,8 @@ impl Model {
                 criteria: db_criteria,
                 search_results: Vec::new(),
                 search_tracks: Vec::new(),
+                filters: Vec::new(),
+                sort: DatabaseSort::default(),
             },
             podcast: PodcastWidgetData {
                 podcasts,
@@ -307,6 +396,8 @@ impl Model {
             rx_to_main,
             download_tracker: DownloadTracker::default(),
             playlist,
+            saved_playlists: Vec::new(),
+            saved_playlist_rename: None,
             cmd_tx,
             current_song: None,
             xywh,
@@ -339,10 +430,31 @@ impl Model {
             self.mount_error_popup(e.context("theme save"));
         }
         self.mount_label_help();
-        self.db.sync_database(&self.library.tree_path);
+        if !self.is_library_synced_by_server() {
+            self.db.sync_database(&self.library.tree_path);
+        }
         self.playlist_sync();
     }
 
+    /// Get whether the server keeps the library database in sync for the shown music directory
+    ///
+    /// This requires the server to run on this machine with the library watcher enabled,
+    /// and the directory to be one of its music directories.
+    fn is_library_synced_by_server(&self) -> bool {
+        let config = self.config_server.read();
+        let com = &config.settings.com;
+        let is_local = com.protocol == ComProtocol::Uds || com.address.is_loopback();
+
+        is_local
+            && config.settings.player.library_watch.is_enabled()
+            && config
+                .settings
+                .player
+                .music_dirs
+                .iter()
+                .any(|dir| self.library.tree_path.starts_with(dir))
+    }
+
     /// Initialize terminal
     pub fn init_terminal(&mut self) {
         let original_hook = std::panic::take_hook();
@@ -371,7 +483,6 @@ impl Model {
     }
 
     pub fn run(&mut self) {
-        self.command(&PlayerCmd::GetProgress);
         self.progress_update_title();
         self.lyric_update_title();
     }
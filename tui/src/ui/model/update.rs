@@ -2,12 +2,14 @@ use crate::ui::{model::TermusicLayout, Model};
 use anyhow::anyhow;
 use std::thread::{self, sleep};
 use std::time::Duration;
+use termusiclib::config::v2::server::EqualizerBand;
 use termusiclib::library_db::SearchCriteria;
 use termusiclib::track::MediaType;
 use termusiclib::types::{
-    DBMsg, DLMsg, GSMsg, Id, IdTagEditor, LIMsg, LyricMsg, Msg, PCMsg, PLMsg, XYWHMsg, YSMsg,
+    DBMsg, DLMsg, EQMsg, GSMsg, Id, IdTagEditor, LIMsg, LyricMsg, Msg, PCMsg, PLMsg, SPMsg,
+    XYWHMsg, YSMsg,
 };
-use termusicplayback::PlayerCmd;
+use termusicplayback::{PlayerCmd, SavedPlaylistCmd};
 /**
  * MIT License
  *
@@ -93,12 +95,16 @@ impl Update<Msg> for Model {
                 | Msg::PlayerVolumeUp
                 | Msg::PlayerVolumeDown
                 | Msg::PlayerSeekForward
-                | Msg::PlayerSeekBackward => self.update_player(&msg),
+                | Msg::PlayerSeekBackward
+                | Msg::PlayerChapterNext
+                | Msg::PlayerChapterPrevious => self.update_player(&msg),
 
                 Msg::HelpPopupShow => {
                     self.mount_help_popup();
                     None
                 }
+                Msg::Equalizer(m) => self.update_equalizer(m),
+                Msg::SavedPlaylists(m) => self.update_saved_playlists(m),
                 Msg::HelpPopupClose => {
                     if self.app.mounted(&Id::HelpPopup) {
                         self.app.umount(&Id::HelpPopup).ok();
@@ -215,6 +221,113 @@ impl Model {
         None
     }
 
+    fn update_equalizer(&mut self, msg: EQMsg) -> Option<Msg> {
+        let mut equalizer = self.config_server.read().settings.player.equalizer.clone();
+        match msg {
+            EQMsg::PopupShow => {
+                self.mount_equalizer_popup();
+                return None;
+            }
+            EQMsg::PopupClose => {
+                if self.app.mounted(&Id::EqualizerPopup) {
+                    self.app.umount(&Id::EqualizerPopup).ok();
+                }
+                self.update_photo().ok();
+                return None;
+            }
+            EQMsg::ToggleEnabled => equalizer.enabled = !equalizer.enabled,
+            EQMsg::NextPreset => {
+                // presets are sorted by name, wrap around after the last one
+                let next = equalizer
+                    .presets
+                    .keys()
+                    .skip_while(|name| **name != equalizer.preset)
+                    .nth(1)
+                    .or_else(|| equalizer.presets.keys().next())
+                    .cloned();
+                if let Some(next) = next {
+                    equalizer.preset = next;
+                }
+            }
+            EQMsg::GainUp(index) | EQMsg::GainDown(index) => {
+                let step = if matches!(msg, EQMsg::GainUp(_)) {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let Some(band) = equalizer
+                    .presets
+                    .get_mut(&equalizer.preset)
+                    .and_then(|bands| bands.get_mut(index))
+                else {
+                    return None;
+                };
+                band.gain = (band.gain + step).clamp(
+                    *EqualizerBand::GAIN_RANGE.start(),
+                    *EqualizerBand::GAIN_RANGE.end(),
+                );
+            }
+        }
+
+        self.command(&PlayerCmd::SetEqualizer(equalizer));
+        None
+    }
+
+    fn update_saved_playlists(&mut self, msg: SPMsg) -> Option<Msg> {
+        let cmd = match msg {
+            SPMsg::PopupShow => {
+                self.mount_saved_playlists_popup();
+                SavedPlaylistCmd::List
+            }
+            SPMsg::PopupClose => {
+                self.umount_saved_playlists_popup();
+                return None;
+            }
+            SPMsg::Load(index) => SavedPlaylistCmd::Load {
+                name: self.saved_playlist_name(index)?,
+                replace: true,
+            },
+            SPMsg::Append(index) => SavedPlaylistCmd::Load {
+                name: self.saved_playlist_name(index)?,
+                replace: false,
+            },
+            SPMsg::AddQueue(index) => SavedPlaylistCmd::Append {
+                name: self.saved_playlist_name(index)?,
+                tracks: None,
+            },
+            SPMsg::Delete(index) => SavedPlaylistCmd::Delete(self.saved_playlist_name(index)?),
+            SPMsg::CreateShow => {
+                self.mount_saved_playlist_name_popup(None);
+                return None;
+            }
+            SPMsg::RenameShow(index) => {
+                let name = self.saved_playlist_name(index)?;
+                self.mount_saved_playlist_name_popup(Some(name));
+                return None;
+            }
+            SPMsg::NameInputCancel => {
+                self.umount_saved_playlist_name_popup();
+                return None;
+            }
+            SPMsg::NameInputOk(name) => {
+                let cmd = match self.saved_playlist_rename.take() {
+                    Some(from) => SavedPlaylistCmd::Rename { from, to: name },
+                    None => SavedPlaylistCmd::Create { name, tracks: None },
+                };
+                self.umount_saved_playlist_name_popup();
+                cmd
+            }
+        };
+
+        self.command(&PlayerCmd::SavedPlaylist(cmd));
+        None
+    }
+
+    /// Get the name of the saved playlist at the index
+    fn saved_playlist_name(&self, index: usize) -> Option<String> {
+        self.saved_playlists.get(index).map(|v| v.name.clone())
+    }
+
     #[allow(clippy::too_many_lines)]
     fn update_podcast(&mut self, msg: &PCMsg) -> Option<Msg> {
         match msg {
@@ -242,6 +355,16 @@ impl Model {
                 }
             }
             PCMsg::PodcastAddPopupCloseCancel => self.umount_podcast_add_popup(),
+            PCMsg::PlaybackSettingsPopupShow => self.mount_podcast_playback_settings_popup(),
+            PCMsg::PlaybackSettingsPopupCloseOk(input) => {
+                self.umount_podcast_playback_settings_popup();
+                if let Err(e) = self.podcast_set_playback_settings(input) {
+                    self.mount_error_popup(e.context("podcast set playback settings"));
+                }
+            }
+            PCMsg::PlaybackSettingsPopupCloseCancel => {
+                self.umount_podcast_playback_settings_popup();
+            }
             PCMsg::SyncData((id, pod)) => {
                 self.download_tracker.decrease_one(&pod.url);
                 self.show_message_timeout_label_help(
@@ -322,6 +445,11 @@ impl Model {
                     self.mount_error_popup(e.context("podcast episode download"));
                 }
             }
+            PCMsg::EpisodeDownloadCancel(index) => {
+                if let Err(e) = self.episode_download_cancel(*index) {
+                    self.mount_error_popup(e.context("podcast episode download cancel"));
+                }
+            }
             PCMsg::DLStart(ep_data) => {
                 self.download_tracker.increase_one(&ep_data.url);
                 self.show_message_timeout_label_help(
@@ -331,6 +459,35 @@ impl Model {
                     None,
                 );
             }
+            PCMsg::DLProgress(ep_data, progress) => {
+                if self
+                    .download_tracker
+                    .update_progress(&ep_data.url, *progress)
+                {
+                    self.show_message_timeout_label_help(
+                        self.download_tracker.message_download_progress(),
+                        None,
+                        None,
+                        None,
+                    );
+                    if let Err(e) = self.podcast_sync_episodes() {
+                        self.mount_error_popup(e.context("podcast sync episodes"));
+                    }
+                }
+            }
+            PCMsg::DLCancelled(ep_data) => {
+                self.download_tracker.decrease_one(&ep_data.url);
+                self.show_message_timeout_label_help(
+                    self.download_tracker
+                        .message_download_cancelled(&ep_data.title),
+                    None,
+                    None,
+                    None,
+                );
+                if let Err(e) = self.podcast_sync_episodes() {
+                    self.mount_error_popup(e.context("podcast sync episodes"));
+                }
+            }
             PCMsg::DLComplete(ep_data) => {
                 if let Err(e) = self.episode_download_complete(ep_data.clone()) {
                     self.mount_error_popup(e.context("podcast episode download complete"));
@@ -376,11 +533,26 @@ impl Model {
                     None,
                 );
             }
+            PCMsg::DLAlreadyRunning(ep_data) => {
+                self.download_tracker.decrease_one(&ep_data.url);
+                self.show_message_timeout_label_help(
+                    self.download_tracker
+                        .message_download_already_running(&ep_data.title),
+                    None,
+                    None,
+                    None,
+                );
+            }
             PCMsg::EpisodeDeleteFile(index) => {
                 if let Err(e) = self.episode_delete_file(*index) {
                     self.mount_error_popup(e.context("podcast episode delete"));
                 }
             }
+            PCMsg::TranscriptFetched(file, text) => {
+                if let Err(e) = self.podcast_transcript_fetched(file, text) {
+                    self.mount_error_popup(e.context("podcast transcript"));
+                }
+            }
             PCMsg::FeedDeleteShow => self.mount_feed_delete_confirm_radio(),
             PCMsg::FeedDeleteCloseOk => {
                 self.umount_feed_delete_confirm_radio();
@@ -443,6 +615,12 @@ impl Model {
                 }
                 self.command(&PlayerCmd::SeekBackward);
             }
+            Msg::PlayerChapterNext => {
+                self.command(&PlayerCmd::ChapterNext);
+            }
+            Msg::PlayerChapterPrevious => {
+                self.command(&PlayerCmd::ChapterPrevious);
+            }
             Msg::PlayerSpeedUp => {
                 self.command(&PlayerCmd::SpeedUp);
             }
@@ -572,8 +750,15 @@ impl Model {
             }
             DBMsg::SearchResult(index) => {
                 self.dw.criteria = SearchCriteria::from(*index);
+                self.dw.filters.clear();
                 self.database_update_search_results();
             }
+            DBMsg::SearchResultBack => {
+                self.database_drill_up();
+            }
+            DBMsg::CycleSort => {
+                self.database_cycle_sort();
+            }
             DBMsg::SearchTrack(index) => {
                 self.database_update_search_tracks(*index);
             }
@@ -836,6 +1021,19 @@ impl Model {
             PLMsg::PlaySelected(index) => {
                 self.playlist_play_selected(*index);
             }
+            PLMsg::PlayNext(index) => {
+                self.playlist_play_next(*index);
+            }
+            PLMsg::AddUpNext(index) => {
+                self.playlist_add_up_next(*index);
+            }
+            // the history is kept by the server, which sends the changed playlist back
+            PLMsg::Undo => {
+                self.command(&PlayerCmd::PlaylistUndo);
+            }
+            PLMsg::Redo => {
+                self.command(&PlayerCmd::PlaylistRedo);
+            }
             PLMsg::LoopModeCycle => {
                 self.command(&PlayerCmd::CycleLoop);
                 self.config_server.write().settings.player.loop_mode =
@@ -372,6 +372,18 @@ impl Model {
             let popup = draw_area_in_relative(f.size(), 88, 91);
             f.render_widget(Clear, popup);
             app.view(&Id::HelpPopup, f, popup);
+        } else if app.mounted(&Id::EqualizerPopup) {
+            let popup = draw_area_in_relative(f.size(), 60, 50);
+            f.render_widget(Clear, popup);
+            app.view(&Id::EqualizerPopup, f, popup);
+        } else if app.mounted(&Id::SavedPlaylistNamePopup) {
+            let popup = draw_area_in_absolute(f.size(), 60, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::SavedPlaylistNamePopup, f, popup);
+        } else if app.mounted(&Id::SavedPlaylistsPopup) {
+            let popup = draw_area_in_relative(f.size(), 60, 50);
+            f.render_widget(Clear, popup);
+            app.view(&Id::SavedPlaylistsPopup, f, popup);
         } else if app.mounted(&Id::DeleteConfirmRadioPopup) {
             let popup = draw_area_in_absolute(f.size(), 30, 3);
             f.render_widget(Clear, popup);
@@ -432,6 +444,10 @@ impl Model {
             let popup = draw_area_in_absolute(f.size(), 65, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::PodcastAddPopup, f, popup);
+        } else if app.mounted(&Id::PodcastPlaybackSettingsPopup) {
+            let popup = draw_area_in_absolute(f.size(), 65, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::PodcastPlaybackSettingsPopup, f, popup);
         }
         if app.mounted(&Id::MessagePopup) {
             let popup = draw_area_top_right_absolute(f.size(), 25, 4);
@@ -1,20 +1,36 @@
 use anyhow::Result;
+use std::time::Duration;
+use termusiclib::config::v2::server::EqualizerBand;
+use termusicplayback::auth::TokenInterceptor;
 use termusicplayback::player::music_player_client::MusicPlayerClient;
 use termusicplayback::player::{
-    CycleLoopRequest, GetProgressRequest, GetProgressResponse, PlaySelectedRequest,
-    ReloadConfigRequest, ReloadPlaylistRequest, SeekBackwardRequest, SeekForwardRequest,
-    SkipNextRequest, SkipPreviousRequest, SpeedDownRequest, SpeedUpRequest, ToggleGaplessRequest,
-    TogglePauseRequest, VolumeDownRequest, VolumeUpRequest,
+    ChapterNextRequest, ChapterPreviousRequest, CycleLoopRequest, Equalizer,
+    GetOutputDevicesRequest, GetPlaylistRequest, GetProgressRequest, GetProgressResponse,
+    GetSavedPlaylistsRequest, OutputDevices, PlaySelectedRequest, PlaylistAddTracksRequest,
+    PlaylistAddUpNextRequest, PlaylistPlayNextRequest, PlaylistRedoRequest, PlaylistTrack,
+    PlaylistTracks, PlaylistUndoRequest, ReloadConfigRequest, ReloadPlaylistRequest,
+    SavedPlaylistAppendRequest, SavedPlaylistCreateRequest, SavedPlaylistDeleteRequest,
+    SavedPlaylistLoadRequest, SavedPlaylistRenameRequest, SearchLibraryRequest,
+    SeekBackwardRequest, SeekForwardRequest, SeekToRequest, SetEqualizerRequest,
+    SetOutputDeviceRequest, SetSpeedRequest, SetVolumeRequest, SkipNextRequest,
+    SkipPreviousRequest, SpeedDownRequest, SpeedUpRequest, StreamUpdates,
+    SubscribeServerUpdatesRequest, ToggleGaplessRequest, TogglePauseRequest, VolumeDownRequest,
+    VolumeUpRequest,
 };
-use termusicplayback::{PlayerProgress, Status};
+use termusicplayback::{PlayerProgress, SavedPlaylistInfo, Status};
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Channel;
+use tonic::Streaming;
+
+/// The client type used to communicate with the server, sending the auth token if configured
+pub type Client = MusicPlayerClient<InterceptedService<Channel, TokenInterceptor>>;
 
 pub struct Playback {
-    client: MusicPlayerClient<Channel>,
+    client: Client,
 }
 
 impl Playback {
-    pub fn new(client: MusicPlayerClient<Channel>) -> Self {
+    pub fn new(client: Client) -> Self {
         Self { client }
     }
     pub async fn toggle_pause(&mut self) -> Result<Status> {
@@ -109,6 +125,36 @@ impl Playback {
         Ok(response.into())
     }
 
+    pub async fn seek_to(&mut self, position: Duration) -> Result<PlayerProgress> {
+        let request = tonic::Request::new(SeekToRequest {
+            position: Some(position.into()),
+        });
+        let response = self.client.seek_to(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(response.into())
+    }
+
+    pub async fn set_volume(&mut self, volume: u16) -> Result<u16> {
+        let request = tonic::Request::new(SetVolumeRequest {
+            volume: u32::from(volume),
+        });
+        let response = self.client.set_volume(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        // clamped to u16::MAX, also send is a u16, but protobuf does not support u16 directly
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(response.volume.min(u32::from(u16::MAX)) as u16)
+    }
+
+    pub async fn set_speed(&mut self, speed: i32) -> Result<i32> {
+        let request = tonic::Request::new(SetSpeedRequest { speed });
+        let response = self.client.set_speed(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(response.speed)
+    }
+
     pub async fn reload_config(&mut self) -> Result<()> {
         let request = tonic::Request::new(ReloadConfigRequest {});
         let response = self.client.reload_config(request).await?;
@@ -138,4 +184,183 @@ impl Playback {
         info!("Got response from server: {:?}", response);
         Ok(())
     }
+
+    pub async fn subscribe_to_stream_updates(&mut self) -> Result<Streaming<StreamUpdates>> {
+        let request = tonic::Request::new(SubscribeServerUpdatesRequest {});
+        let response = self.client.subscribe_server_updates(request).await?;
+        let response = response.into_inner();
+        info!("Subscribed to server updates");
+        Ok(response)
+    }
+
+    pub async fn get_playlist(&mut self) -> Result<PlaylistTracks> {
+        let request = tonic::Request::new(GetPlaylistRequest {});
+        let response = self.client.get_playlist(request).await?;
+        let response = response.into_inner();
+        Ok(response)
+    }
+
+    pub async fn playlist_add_tracks(&mut self, at_index: u64, tracks: Vec<String>) -> Result<()> {
+        let request = tonic::Request::new(PlaylistAddTracksRequest { at_index, tracks });
+        let response = self.client.playlist_add_tracks(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn playlist_play_next(&mut self, tracks: Vec<String>) -> Result<()> {
+        let request = tonic::Request::new(PlaylistPlayNextRequest { tracks });
+        let response = self.client.playlist_play_next(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn playlist_add_up_next(&mut self, tracks: Vec<String>) -> Result<()> {
+        let request = tonic::Request::new(PlaylistAddUpNextRequest { tracks });
+        let response = self.client.playlist_add_up_next(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn playlist_undo(&mut self) -> Result<()> {
+        let request = tonic::Request::new(PlaylistUndoRequest {});
+        let response = self.client.playlist_undo(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn playlist_redo(&mut self) -> Result<()> {
+        let request = tonic::Request::new(PlaylistRedoRequest {});
+        let response = self.client.playlist_redo(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn chapter_next(&mut self) -> Result<()> {
+        let request = tonic::Request::new(ChapterNextRequest {});
+        let response = self.client.chapter_next(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn chapter_previous(&mut self) -> Result<()> {
+        let request = tonic::Request::new(ChapterPreviousRequest {});
+        let response = self.client.chapter_previous(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn get_output_devices(&mut self) -> Result<OutputDevices> {
+        let request = tonic::Request::new(GetOutputDevicesRequest {});
+        let response = self.client.get_output_devices(request).await?;
+        let response = response.into_inner();
+        Ok(response)
+    }
+
+    /// Switch the output device, `None` for the default device
+    pub async fn set_output_device(&mut self, device: Option<String>) -> Result<()> {
+        let request = tonic::Request::new(SetOutputDeviceRequest {
+            device: device.unwrap_or_default(),
+        });
+        let response = self.client.set_output_device(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    /// Select the equalizer preset, replacing its bands if `bands` is not empty
+    pub async fn set_equalizer(
+        &mut self,
+        enabled: bool,
+        preset: String,
+        bands: &[EqualizerBand],
+    ) -> Result<Equalizer> {
+        let request = tonic::Request::new(SetEqualizerRequest {
+            enabled,
+            preset,
+            bands: bands.iter().map(Into::into).collect(),
+        });
+        let response = self.client.set_equalizer(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {:?}", response);
+        Ok(response)
+    }
+
+    /// Search the library of the server, `limit` `0` uses the server default
+    pub async fn search_library(
+        &mut self,
+        query: String,
+        limit: u32,
+    ) -> Result<Vec<PlaylistTrack>> {
+        let request = tonic::Request::new(SearchLibraryRequest { query, limit });
+        let response = self.client.search_library(request).await?;
+        let response = response.into_inner();
+        Ok(response.tracks)
+    }
+
+    pub async fn get_saved_playlists(&mut self) -> Result<Vec<SavedPlaylistInfo>> {
+        let request = tonic::Request::new(GetSavedPlaylistsRequest {});
+        let response = self.client.get_saved_playlists(request).await?;
+        let response = response.into_inner();
+        Ok(response.playlists.into_iter().map(Into::into).collect())
+    }
+
+    /// Save the given tracks as a new playlist, `None` saves the current playlist
+    pub async fn saved_playlist_create(
+        &mut self,
+        name: String,
+        tracks: Option<Vec<String>>,
+    ) -> Result<()> {
+        let request = tonic::Request::new(SavedPlaylistCreateRequest {
+            name,
+            from_queue: tracks.is_none(),
+            tracks: tracks.unwrap_or_default(),
+        });
+        let response = self.client.saved_playlist_create(request).await?;
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn saved_playlist_rename(&mut self, from: String, to: String) -> Result<()> {
+        let request = tonic::Request::new(SavedPlaylistRenameRequest { from, to });
+        let response = self.client.saved_playlist_rename(request).await?;
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn saved_playlist_delete(&mut self, name: String) -> Result<()> {
+        let request = tonic::Request::new(SavedPlaylistDeleteRequest { name });
+        let response = self.client.saved_playlist_delete(request).await?;
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    /// Add the given tracks to a saved playlist, `None` adds the current playlist
+    pub async fn saved_playlist_append(
+        &mut self,
+        name: String,
+        tracks: Option<Vec<String>>,
+    ) -> Result<()> {
+        let request = tonic::Request::new(SavedPlaylistAppendRequest {
+            name,
+            from_queue: tracks.is_none(),
+            tracks: tracks.unwrap_or_default(),
+        });
+        let response = self.client.saved_playlist_append(request).await?;
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
+
+    pub async fn saved_playlist_load(&mut self, name: String, replace: bool) -> Result<()> {
+        let request = tonic::Request::new(SavedPlaylistLoadRequest { name, replace });
+        let response = self.client.saved_playlist_load(request).await?;
+        info!("Got response from server: {:?}", response);
+        Ok(())
+    }
 }